@@ -0,0 +1,14 @@
+#![no_main]
+
+use crossy_chain::{verify_replay, GameConfig, Recording};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let config = GameConfig::default();
+    if let Ok(score) = verify_replay(data, &config) {
+        // An accepted replay always parses and is credited its claimed score
+        let json = std::str::from_utf8(data).expect("accepted replays are UTF-8");
+        let recording = Recording::parse(json).expect("accepted replays parse");
+        assert_eq!(score, recording.final_score);
+    }
+});
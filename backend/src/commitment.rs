@@ -0,0 +1,130 @@
+use crate::state::PlayerKey;
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Number of top leaderboard entries covered by the commitment
+pub const COMMITMENT_SIZE: usize = 100;
+
+/// Commitment to the current top of the leaderboard: the root of a binary
+/// hash tree over the entries in rank order
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardCommitment {
+    /// Hex tree root
+    pub root: String,
+    /// Entries committed to, at most `COMMITMENT_SIZE`
+    pub size: u32,
+    /// Block that last changed the commitment
+    pub block_height: u64,
+    /// When the commitment last changed (UNIX timestamp)
+    pub updated_at: u64,
+}
+
+/// A sibling hash on the path from a leaf to the root
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MerkleStep {
+    /// Hex hash of the sibling
+    pub hash: String,
+    /// Whether the sibling is hashed before (left of) the running hash
+    pub is_left: bool,
+}
+
+/// What a third party needs to check an entry against the commitment: hash
+/// the leaf, fold in each step of `path` and compare with `root`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardProof {
+    pub wallet_address: PlayerKey,
+    /// 1 for first place
+    pub rank: u32,
+    pub high_score: u32,
+    /// Hex hash of the entry, see `leaf_hash`
+    pub leaf: String,
+    /// Siblings from the leaf up to the root
+    pub path: Vec<MerkleStep>,
+    /// Hex root the path leads to, equal to the current commitment's
+    pub root: String,
+}
+
+/// The committed entries of a leaderboard index: highest score first, ties
+/// broken by wallet address, at most `COMMITMENT_SIZE`
+pub fn committed_entries(mut entries: Vec<(PlayerKey, u32)>) -> Vec<(PlayerKey, u32)> {
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(COMMITMENT_SIZE);
+    entries
+}
+
+/// Leaf hashes of committed entries, in rank order
+pub fn leaves(entries: &[(PlayerKey, u32)]) -> Vec<[u8; 32]> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, (wallet, high_score))| leaf_hash(index as u32 + 1, wallet, *high_score))
+        .collect()
+}
+
+/// Hash of a leaderboard entry: SHA-256 of a 0x00 byte, the big-endian rank
+/// (1 for first place), the wallet address and the big-endian score
+pub fn leaf_hash(rank: u32, wallet: &PlayerKey, high_score: u32) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update(rank.to_be_bytes());
+    hasher.update(wallet.to_string().as_bytes());
+    hasher.update(high_score.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Hash of an inner node: SHA-256 of a 0x01 byte and both children
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Next level of the tree; an odd last node is carried up unchanged
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks of two"),
+        })
+        .collect()
+}
+
+/// Root over `leaves`; the empty tree's root is the SHA-256 of nothing
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return Sha256::digest(b"").into();
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level[0]
+}
+
+/// Siblings needed to rebuild the root from leaf `index`, lowest first
+pub fn merkle_path(leaves: &[[u8; 32]], mut index: usize) -> Vec<MerkleStep> {
+    let mut path = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            path.push(MerkleStep {
+                hash: to_hex(&level[sibling]),
+                is_left: sibling < index,
+            });
+        }
+        level = parent_level(&level);
+        index /= 2;
+    }
+    path
+}
+
+/// Lowercase hex encoding of a hash
+pub fn to_hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
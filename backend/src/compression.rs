@@ -0,0 +1,60 @@
+use async_graphql::Enum;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use thiserror::Error;
+
+/// How a submitted or stored replay is encoded. Compressed replays are the
+/// base64 of the compressed recording JSON; most shrink 5-10x.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ReplayCompression {
+    /// Plain recording JSON
+    #[default]
+    None,
+    Zstd,
+    Gzip,
+}
+
+/// Why a compressed replay could not be decompressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CompressionError {
+    #[error("Invalid replay encoding: compressed replays must be base64")]
+    InvalidEncoding,
+
+    #[error("Corrupt replay: the compressed replay does not decompress to UTF-8 JSON")]
+    Corrupt,
+}
+
+/// Recover the recording JSON from a replay encoded with `compression`.
+///
+/// At most `max_size + 1` bytes are decompressed, so a replay that inflates
+/// past the size limit costs no more than the limit to reject: callers
+/// check the result with `validation::validate_replay_size` as for plain
+/// replays.
+pub fn decompress_replay(
+    data: &str,
+    compression: ReplayCompression,
+    max_size: u64,
+) -> Result<String, CompressionError> {
+    if compression == ReplayCompression::None {
+        return Ok(data.to_string());
+    }
+    let compressed = BASE64
+        .decode(data)
+        .map_err(|_| CompressionError::InvalidEncoding)?;
+
+    let limit = max_size.saturating_add(1);
+    let mut json = Vec::new();
+    let read = match compression {
+        ReplayCompression::Zstd => ruzstd::StreamingDecoder::new(compressed.as_slice())
+            .map_err(|_| CompressionError::Corrupt)?
+            .take(limit)
+            .read_to_end(&mut json),
+        _ => flate2::read::GzDecoder::new(compressed.as_slice())
+            .take(limit)
+            .read_to_end(&mut json),
+    };
+    read.map_err(|_| CompressionError::Corrupt)?;
+
+    String::from_utf8(json).map_err(|_| CompressionError::Corrupt)
+}
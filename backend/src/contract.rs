@@ -1,41 +1,785 @@
-use crate::state::{CrossyChainState, PlayerData};
+// A panic in the contract aborts the whole block, so failures must surface as ContractError
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing,
+    clippy::unreachable,
+    clippy::todo,
+    clippy::unimplemented
+)]
+
+use crate::commitment::{self, LeaderboardCommitment, COMMITMENT_SIZE};
+use crate::compression::{self, CompressionError, ReplayCompression};
+use crate::faucet::{FaucetAbi, FaucetRequest};
+use crate::rating::{HeadToHeadMode, MatchOutcome, RatingChange};
+use crate::state::{
+    challenge_week, ARCHIVE_AFTER, AdminAuditEntry, Appeal, AppealStatus, CalendarEntry,
+    CalendarEntryKind, ChallengeModifier, ClaimableReward, CommunityEvent, ConfigField,
+    CrossyChainState, DAILY_INDEX_RETENTION_DAYS, DAILY_TOP_N, EventEntry, EventKind, EventTeam,
+    FailedAction, FeaturedReplay, GameConfig, GameMode, GhostRace, GiftRecord, HighScoreSnapshot,
+    IndexRebuild, LeaderboardChange, LeaderboardChangeKind, LeaderboardFreeze, LootBoxCommit,
+    MAX_BLOCKLIST_TERMS, MAX_CALENDAR_ENTRIES, MAX_EVENT_SUBSCRIBERS, MAX_FRIENDS,
+    MAX_HELD_UPDATES_PER_BLOCK, MAX_LEADERBOARD_FREEZE, MAX_NAME_HISTORY, MAX_NOTIFICATIONS,
+    MAX_OPEN_EVENTS_PER_WALLET, MAX_PROVISIONAL_EXPIRED_PER_RUN, MAX_REWARDS_EXPIRED_PER_RUN,
+    MAX_SCHEDULED_ACTIONS, MAX_SUBMISSION_RECEIPTS, MAX_TEAMS_PER_EVENT, MAX_VERIFIED_BESTS,
+    MirroredPlayer, NameReview, NameScript, Notification, NotificationKind, ONBOARDING_REWARD_COINS,
+    OnboardingStep, PROVISIONAL_EXPIRY_INTERVAL, PendingHubScore, PlayerData, PlayerKey,
+    ProvisionalHighScore, Quest, QuestGoal, QuestProgress, QueuedRacer, RENAME_COOLDOWN, Race,
+    RaceChainInfo, RaceMatch, RaceSnapshot, ReceiptFlag, RecordHolder, RegionScripts, ReplayOrigin,
+    ReplayStats, RewardSource, Role, RunStats, SECONDS_PER_DAY, SHARD_TOP_N, STATE_SCHEMA_VERSION,
+    ScheduledAction, ScheduledActionKind, ScoreAttestation, ScoreInvalidation, ScoreScope,
+    ScoredRun, SeasonDecay, SeasonRecord, SeasonStanding, SessionKey, ShardTop, ShardTopEntry,
+    SubmissionReceipt, TeamMember, Tip, Trophy, VerificationItem, VerificationStatus,
+    VerificationVerdict, VerifiedBest,
+};
+use crate::eligibility::LeaderboardRules;
+use crate::notification_hub::{HubEvent, NotificationHubAbi};
+use crate::oracle::{OracleAttestation, ScoreOracleAbi};
+use crate::partner::{
+    PartnerCapability, PartnerRequest, PartnerResponse, PlayerProgress, COIN_HOLD_LIFETIME,
+    MAX_XP_GRANT,
+};
+use crate::proof::{self, ProofError, ProofStatement};
+use crate::replay::{self, GhostPoint, Recording, ReplayError, ReplayFormatError, ReplayMetadata};
+use crate::validation::{self, ValidationError};
 use async_trait::async_trait;
 use linera_sdk::{
-    base::{Owner, WithContractAbi},
-    views::{RootView, View, ViewStorageContext},
+    base::{
+        Account, Amount, ApplicationId, ApplicationPermissions, BcsSignable, ChainId,
+        ChainOwnership, ChannelName, Destination, Owner, PublicKey, Signature, StreamName,
+        TimeoutConfig, WithContractAbi,
+    },
+    views::{batch::Batch, ClonableView, RootView, View, ViewStorageContext},
     Contract, ContractRuntime,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use strum::IntoStaticStr;
 use thiserror::Error;
 
+/// Number of recent submission ids remembered per player for deduplication
+const RECENT_SUBMISSION_IDS: usize = 32;
+
+/// Channel race chains publish position snapshots on
+const SPECTATOR_CHANNEL: &[u8] = b"race-spectators";
+
+/// Most players a single `RebuildIndexes` or `RebuildLeaderboard` operation rebuilds
+const MAX_REBUILD_BATCH: u32 = 500;
+
+/// Most players a single `ArchiveInactivePlayers` operation archives
+const MAX_ARCHIVE_BATCH: u32 = 500;
+
+/// Most players a single `PruneReplays` operation re-indexes
+const MAX_PRUNE_BATCH: u32 = 500;
+
+/// Most players a block rebuilds while migrating state from an older layout
+const MIGRATION_BATCH: usize = 100;
+
+/// Most players a single `RebalanceShard` operation checks
+const MAX_REBALANCE_BATCH: u32 = 100;
+
+/// Longest reason accepted with `InvalidateScore`, in characters
+const MAX_INVALIDATION_REASON_LEN: usize = 500;
+
+/// Longest statement accepted with `AppealInvalidation`, in characters
+const MAX_APPEAL_STATEMENT_LEN: usize = 1_000;
+
+/// Longest community event tag, in characters
+const MAX_EVENT_TAG_LEN: usize = 32;
+
+/// Longest community event name, in characters
+const MAX_EVENT_NAME_LEN: usize = 64;
+
+/// Longest team relay team name, in characters
+const MAX_TEAM_NAME_LEN: usize = 30;
+
+/// Most pending invites a team relay team can have
+const MAX_TEAM_INVITES: usize = 5;
+
+/// Channel a chain publishes its leaderboard changes on, for read-only mirrors
+const MIRROR_CHANNEL: &[u8] = b"leaderboard-mirrors";
+
+/// Channel a chain publishes its calendar events on as they start
+const EVENTS_CHANNEL: &[u8] = b"calendar-events";
+
+/// Event stream every accepted score's attestation is emitted on
+const SCORE_ATTESTATION_STREAM: &[u8] = b"score-attestations";
+
+/// Publish a spectator snapshot every this many position updates
+const SNAPSHOT_INTERVAL: u32 = 5;
+
 /// Contract messages
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Message {
     /// Save a player's score
     SaveScore {
         score: u32,
-        replay_data: Option<String>, // JSON string of replay data
+        replay_data: Option<String>, // JSON string of replay data, or base64 if compressed
+        compression: ReplayCompression,
         timestamp: u64,
+        client_version: String, // MAJOR.MINOR.PATCH of the submitting client
+        submission_id: String,  // client-generated nonce, retries reuse the same id
+        run_stats: Option<RunStats>,
+        proof: Option<String>, // hex-encoded succinct proof that the replay yields the score
+        sequence: u64,          // must be exactly one more than the last accepted sequence
+        shard_epoch: u32,       // shard assignment the score was routed under
     },
     /// Register a player with optional display name
     RegisterPlayer {
         display_name: Option<String>,
     },
+    /// Notify a player's chain that they were paired for a race
+    RaceMatched {
+        wallet: String,
+        race_id: u64,
+        opponent: String,
+        seed: u64,
+        race_chain: Option<ChainId>,
+    },
+    /// Set up a freshly opened temporary race chain
+    RaceChainOpened {
+        race_id: u64,
+        race: Race,
+        info: RaceChainInfo,
+    },
+    /// Forward a participant's live position to the opponent's chain
+    RacePosition {
+        race_id: u64,
+        wallet: String,
+        position: GhostPoint,
+    },
+    /// Final result of a race played on a temporary race chain
+    RaceSettled {
+        race_id: u64,
+        race: Race,
+    },
+    /// Periodic position snapshot published to a race chain's spectators
+    RaceSnapshot {
+        snapshot: RaceSnapshot,
+    },
+    /// Event for the notification hub application, delivered to the chain it
+    /// was created on; an event the hub rejects is dropped
+    NotifyHub {
+        event: HubEvent,
+    },
+    /// Welcome bonus for the faucet application to grant, delivered to the
+    /// chain it was created on
+    GrantWelcomeBonus {
+        wallet: PlayerKey,
+        amount: Amount,
+    },
+    /// Ask the hub chain to draw the seed of a loot box bought on another chain
+    DrawLootSeed {
+        wallet: PlayerKey,
+        committed_at: u64,
+    },
+    /// Seed the hub chain drew for a loot box, sent back to where it was bought
+    LootSeed {
+        wallet: PlayerKey,
+        committed_at: u64,
+        seed: String,
+    },
+    /// Tell the chain that forwarded a score that the hub rejected it for
+    /// good, so it isn't resent
+    HubScoreRejected {
+        wallet: PlayerKey,
+        sequence: u64,
+        submission_id: String,
+        reason: String,
+    },
+    /// Calendar entry that just started, published to the chains subscribed
+    /// to event notifications
+    CalendarEntryStarted {
+        entry: CalendarEntry,
+    },
+    /// Move a player's account from another chain running this application
+    ImportAccount {
+        migration_id: u64,
+        player: PlayerData,
+        trophies: Vec<Trophy>,
+    },
+    /// Hand a player's account to the shard hub that holds it after the shards changed
+    ShardHandoff {
+        migration_id: u64,
+        shard_epoch: u32,
+        wallet: PlayerKey,
+        player: PlayerData,
+        trophies: Vec<Trophy>,
+    },
+    /// A shard hub's current top list, for the coordinator to merge
+    ShardTop {
+        shard_epoch: u32,
+        entries: Vec<ShardTopEntry>,
+    },
+    /// A leaderboard player's current leaderboard fields, or None once they
+    /// left the leaderboard, published to read-only mirrors
+    LeaderboardDelta {
+        wallet: PlayerKey,
+        player: Option<MirroredPlayer>,
+    },
+    /// A player's high score attested by their hub, sent on their request;
+    /// passed on to `application` on the receiving chain, if set
+    ScoreAttestation {
+        wallet: PlayerKey,
+        high_score: u32,
+        season: u32,
+        issued_at: u64,
+        digest: String,
+        application: Option<ApplicationId>,
+    },
+    /// A submission fee paid on a player's chain, sent along with its tokens
+    /// to their hub for its prize pool
+    SubmissionFee {
+        amount: Amount,
+    },
 }
 
 /// Contract operations (for cross-chain calls and mutations)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, IntoStaticStr)]
 pub enum Operation {
     /// Save a player's score (triggered by GraphQL mutation)
     SaveScore {
         score: u32,
-        replay_data: Option<String>, // JSON string of replay data
+        replay_data: Option<String>, // JSON string of replay data, or base64 if compressed
+        compression: ReplayCompression,
         timestamp: u64,
+        client_version: String, // MAJOR.MINOR.PATCH of the submitting client
+        submission_id: String,  // client-generated nonce, retries reuse the same id
+        run_stats: Option<RunStats>,
+        proof: Option<String>, // hex-encoded succinct proof that the replay yields the score
+    },
+    /// Save a run signed by the player, on behalf of a relayer that pays the
+    /// fees. The score is attributed to the owner of `player`.
+    RelayedSaveScore {
+        player: PublicKey,
+        run: RelayedRun,
+        signature: Signature,
     },
     /// Register a player with optional display name
     RegisterPlayer {
         display_name: Option<String>,
     },
+    /// Move tokens from the signer's account into the prize pool
+    FundPrizePool {
+        amount: Amount,
+    },
+    /// Reserve part of the prize pool as a claimable reward (verifier role)
+    GrantReward {
+        wallet: String,
+        amount: Amount,
+        source: RewardSource,
+        source_id: u32,
+    },
+    /// Transfer a granted reward to the signer's account
+    ClaimReward {
+        reward_id: u64,
+    },
+    /// Return every expired, unclaimed reward to the prize pool
+    ExpireRewards,
+    /// Change how long granted rewards stay claimable (super admin only)
+    SetRewardClaimWindow {
+        seconds: u64,
+    },
+    /// End the current season, award trophies to its top 3 and start the next one (verifier role)
+    CloseSeason,
+    /// Stop accepted scores from changing the leaderboard for `seconds`, so
+    /// the standings can be audited; scores are applied when it lifts (super admin only)
+    FreezeLeaderboard {
+        seconds: u64,
+    },
+    /// Lift the leaderboard freeze early and apply the scores it held back (super admin only)
+    UnfreezeLeaderboard,
+    /// Have the contract perform `kind` once `due_at` has passed; `event_tag`
+    /// names the event of an `EndEvent` action (super admin only)
+    ScheduleAction {
+        kind: ScheduledActionKind,
+        event_tag: Option<String>,
+        due_at: u64,
+    },
+    /// Add an entry to the event calendar, dropping the entries that ended
+    /// (super admin only)
+    AddCalendarEntry {
+        kind: CalendarEntryKind,
+        title: String,
+        starts_at: u64,
+        ends_at: u64,
+        event_tag: Option<String>,
+        modifier: Option<ChallengeModifier>,
+    },
+    /// Remove an entry from the event calendar (super admin only)
+    RemoveCalendarEntry {
+        entry_id: u64,
+    },
+    /// Follow the event calendar of `calendar_chain`, or stop following one.
+    /// Every subscriber on this chain moves with it (super admin only).
+    SetEventsSource {
+        calendar_chain: Option<ChainId>,
+    },
+    /// Notify the signer when events of the followed calendar start
+    SubscribeToEvents,
+    /// Stop notifying the signer when calendar events start
+    UnsubscribeFromEvents,
+    /// Drop a scheduled action before it fires (super admin only)
+    CancelScheduledAction {
+        action_id: u64,
+    },
+    /// Start racing against another player's high-score replay
+    StartGhostRace {
+        opponent: String,
+    },
+    /// Finish the active ghost race with the score reached. Only a result
+    /// sent with the run's replay JSON, checked to credit `score`, is rated.
+    SubmitGhostRaceResult {
+        score: u32,
+        replay_data: Option<String>,
+    },
+    /// Join the matchmaking queue for a live race; if both paired players
+    /// provide a public key the race is played on a temporary chain they co-own
+    EnqueueRace {
+        public_key: Option<PublicKey>,
+    },
+    /// Leave the matchmaking queue before being paired
+    LeaveRaceQueue,
+    /// Submit the score reached in a matched race
+    SubmitRaceRun {
+        race_id: u64,
+        score: u32,
+    },
+    /// Publish the participant's current position on a race chain
+    UpdateRacePosition {
+        race_id: u64,
+        position: GhostPoint,
+    },
+    /// Subscribe this chain to a race chain's spectator snapshots
+    SpectateRace {
+        race_chain: ChainId,
+    },
+    /// Stop receiving a race chain's spectator snapshots
+    StopSpectating {
+        race_chain: ChainId,
+    },
+    /// Feature a player's high-score replay in the community carousel (moderator role)
+    FeatureReplay {
+        wallet: String,
+    },
+    /// Remove a replay from the featured carousel (moderator role)
+    UnfeatureReplay {
+        wallet: String,
+    },
+    /// Like a player's current high-score replay (once per signer per replay)
+    LikeReplay {
+        wallet: String,
+    },
+    /// Count a view of a player's current high-score replay (once per signer per replay)
+    RecordReplayView {
+        wallet: String,
+    },
+    /// Re-index the stored replays of the next `batch_size` players; the last
+    /// batch prunes the ones outside the retention policy. Repeat until
+    /// `pruningStats.cursor` is null (moderator role)
+    PruneReplays {
+        batch_size: u32,
+    },
+    /// Set the oldest client version allowed to submit scores (super admin only)
+    SetMinClientVersion {
+        version: Option<String>,
+    },
+    /// Replace the deterministic game parameters (super admin only)
+    SetGameConfig {
+        config: GameConfig,
+    },
+    /// Accept SaveScore and RegisterPlayer messages from a game chain (super admin only)
+    AuthorizeChain {
+        chain_id: ChainId,
+    },
+    /// Stop accepting SaveScore and RegisterPlayer messages from a chain (super admin only)
+    RevokeChain {
+        chain_id: ChainId,
+    },
+    /// Set the hub chain scores are forwarded to, or stop forwarding (super admin only)
+    SetHubChain {
+        chain_id: Option<ChainId>,
+    },
+    /// Shard the global leaderboard across `shards` by wallet hash, merged on
+    /// `coordinator`; an empty list goes back to `hub_chain` (super admin only).
+    /// Every chain gets the same `epoch`, higher than the current one, so
+    /// chains that missed a change can't end up agreeing on different epochs.
+    SetHubShards {
+        shards: Vec<ChainId>,
+        coordinator: Option<ChainId>,
+        epoch: u32,
+    },
+    /// Check the next `batch_size` players of the roster and hand the accounts
+    /// this shard hub no longer holds to their new shards. Repeat until
+    /// `rebalanceCursor` is null (super admin only)
+    RebalanceShard {
+        batch_size: u32,
+    },
+    /// Turn this chain into a read-only mirror of `hub`'s leaderboard, or back
+    /// into a regular chain (super admin only)
+    SetMirrorMode {
+        hub: Option<ChainId>,
+    },
+    /// Publish every leaderboard player to the mirrors, to seed new ones (super admin only)
+    ResyncMirrors,
+    /// Rebuild the leaderboard index, replay holders and death tally from the
+    /// players map, `batch_size` players per operation; repeat until
+    /// `indexRebuild` is null (super admin only)
+    RebuildIndexes {
+        batch_size: u32,
+    },
+    /// Check the next `batch_size` players of the roster and move those
+    /// inactive for a year out of the players map and the leaderboard index,
+    /// pruning their replays. They come back on their next operation (super
+    /// admin only).
+    ArchiveInactivePlayers {
+        batch_size: u32,
+    },
+    /// Keep new high scores provisional for `blocks` blocks until verification
+    /// confirms them, or accept them outright with None (super admin only)
+    SetProvisionalWindow {
+        blocks: Option<u64>,
+    },
+    /// Make season standings of players inactive for `decay.inactive_after`
+    /// decay, or stop decay with None (super admin only)
+    SetSeasonDecay {
+        decay: Option<SeasonDecay>,
+    },
+    /// Roll back every provisional high score not confirmed in time
+    ExpireProvisionalScores,
+    /// Revert a player's high score to their previous verified best and
+    /// remove its replay (verifier role)
+    InvalidateScore {
+        wallet: String,
+        reason: String,
+    },
+    /// Contest the invalidation `score_id` of one of the signer's scores
+    AppealInvalidation {
+        score_id: u64,
+        statement: String,
+    },
+    /// Settle an appeal: give the invalidated score back with `overturn`, or
+    /// let the invalidation stand (super admin only)
+    ResolveAppeal {
+        score_id: u64,
+        overturn: bool,
+        note: Option<String>,
+    },
+    /// Create a community event; runs count from `starts_at` until `ends_at`
+    /// (super admin only)
+    CreateEvent {
+        tag: String,
+        name: String,
+        kind: EventKind,
+        starts_at: u64,
+        ends_at: u64,
+    },
+    /// Register the signer into a regional event, playing for `country` and `city`
+    RegisterForEvent {
+        tag: String,
+        country: String,
+        city: Option<String>,
+    },
+    /// Create a team in a team relay event, with the signer as captain
+    CreateTeam {
+        tag: String,
+        name: String,
+    },
+    /// Invite a player into the signer's team (team captain only)
+    InviteToTeam {
+        team_id: u64,
+        wallet: String,
+    },
+    /// Join a team the signer was invited into
+    AcceptTeamInvite {
+        team_id: u64,
+    },
+    /// Request from a partner application, sent with `call_application`
+    Partner {
+        request: PartnerRequest,
+    },
+    /// Allowlist a partner application for privileged requests; an empty list
+    /// removes it from the allowlist (super admin only)
+    SetPartnerCapabilities {
+        application_id: ApplicationId,
+        capabilities: Vec<PartnerCapability>,
+    },
+    /// Send an attestation of the signer's high score to `target_chain`,
+    /// for `application` there if set (hub chain only)
+    RequestScoreAttestation {
+        target_chain: ChainId,
+        application: Option<ApplicationId>,
+    },
+    /// Add and remove display name blocklist terms (moderator role)
+    UpdateNameBlocklist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Settle a flagged display name: clear it, or keep it and dismiss the
+    /// review (moderator role)
+    ResolveNameReview {
+        wallet: String,
+        clear_name: bool,
+    },
+    /// Set the scripts display names may use, for players from `country` if
+    /// set or everywhere else otherwise; an empty list allows every script,
+    /// or drops the country's override (moderator role)
+    SetNameScripts {
+        country: Option<String>,
+        scripts: Vec<NameScript>,
+    },
+    /// Declare the signer's country, which picks the regional rules applied
+    /// to them, or clear it
+    SetCountry {
+        country: Option<String>,
+    },
+    /// Mark a player as verified, protecting their display name from
+    /// look-alikes, or remove the mark (super admin only)
+    SetVerified {
+        wallet: String,
+        verified: bool,
+    },
+    /// Ban a player from the leaderboard, or lift the ban (moderator role)
+    SetBanned {
+        wallet: String,
+        banned: bool,
+    },
+    /// Keep the signer off the public leaderboard, or put them back on it
+    SetLeaderboardHidden {
+        hidden: bool,
+    },
+    /// Add a player to the signer's friends
+    AddFriend {
+        wallet: String,
+    },
+    /// Remove a player from the signer's friends
+    RemoveFriend {
+        wallet: String,
+    },
+    /// Freeze the results of an event once its window has closed (super admin only)
+    EndEvent {
+        tag: String,
+    },
+    /// Set the notification hub application notable events are forwarded to,
+    /// or stop forwarding (super admin only)
+    SetNotificationHub {
+        application_id: Option<ApplicationId>,
+    },
+    /// Have `faucet` grant `amount` to every wallet on its first registration,
+    /// or stop granting with None (super admin only)
+    SetWelcomeBonus {
+        faucet: Option<ApplicationId>,
+        amount: Amount,
+    },
+    /// Move the signer's account (scores, replay and trophies) to another chain.
+    /// A target chain that doesn't list this chain in its authorized chains
+    /// refuses the account, and it comes back here.
+    MigrateAccount {
+        target_chain: ChainId,
+    },
+    /// Offer to link another owner to the signer's profile; the owner must accept
+    RequestLink {
+        owner: Owner,
+    },
+    /// Accept a link request, after which the signer plays as `primary`'s profile
+    AcceptLink {
+        primary: Owner,
+    },
+    /// Let an ephemeral key submit scores for the signer until `expires_at`.
+    /// The key can only submit scores, nothing else.
+    AuthorizeSessionKey {
+        session_key: Owner,
+        expires_at: u64,
+    },
+    /// Withdraw a session key before it expires
+    RevokeSessionKey {
+        session_key: Owner,
+    },
+    /// Give a wallet an admin role, replacing any role it had (super admin only)
+    GrantRole {
+        wallet: Owner,
+        role: Role,
+    },
+    /// Take away a wallet's admin role (super admin only)
+    RevokeRole {
+        wallet: Owner,
+    },
+    /// Offer the application admin seat to another wallet (admin only)
+    ProposeAdminTransfer {
+        new_admin: Owner,
+    },
+    /// Take over the admin seat offered to the signer
+    AcceptAdminTransfer,
+    /// Change a bounded config value (super admin only)
+    UpdateConfig {
+        field: ConfigField,
+        value: u64,
+    },
+    /// Run the next batch of `RebuildIndexes`, `MAX_REBUILD_BATCH` players,
+    /// which applies the leaderboard cap once the last batch is done; repeat
+    /// until `indexRebuild` is null (moderator role)
+    RebuildLeaderboard,
+    /// Add a daily quest to the catalog (super admin only)
+    AddQuest {
+        title: String,
+        goal: QuestGoal,
+        target: u64,
+        /// Coins credited when the quest is claimed
+        reward: u64,
+    },
+    /// Remove a quest from the catalog (super admin only)
+    RemoveQuest {
+        quest_id: u32,
+    },
+    /// Collect the reward of a quest completed today
+    ClaimQuestReward {
+        quest_id: u32,
+    },
+    /// Set or clear the challenge modifier of a week (super admin only)
+    ScheduleModifier {
+        week: u64,
+        modifier: Option<ChallengeModifier>,
+    },
+    /// Buy a loot box, committing to the hex SHA-256 of a secret revealed when opening it
+    BuyLootBox {
+        commitment: String,
+    },
+    /// Open the bought loot box by revealing the committed secret
+    OpenLootBox {
+        secret: String,
+    },
+    /// Give some of the signer's coins to another player
+    GiftCoins {
+        to: Owner,
+        amount: u64,
+    },
+    /// Send native tokens from the signer to another player
+    TipPlayer {
+        to: Owner,
+        amount: Amount,
+    },
+    /// Set the one-time leaderboard entry fee (zero disables it)
+    SetLeaderboardEntryFee {
+        fee: Amount,
+    },
+    /// Pay the leaderboard entry fee to rank without meeting the games and age requirements
+    PayLeaderboardEntryFee,
+    /// Set the fee charged for every accepted score submission (zero disables it)
+    SetSubmissionFee {
+        fee: Amount,
+    },
+    /// Claim a queued high-score replay to re-simulate it (verifier role)
+    ClaimVerification {
+        id: u64,
+    },
+    /// Submit the verdict on a claimed replay (verifier role)
+    SubmitVerdict {
+        id: u64,
+        valid: bool,
+        result_hash: String,
+    },
+    /// Settle a replay the verifiers disagreed on (super admin role)
+    ResolveVerification {
+        id: u64,
+        valid: bool,
+    },
+    /// Drop notifications up to and including `up_to` from the signer's inbox
+    AckNotifications {
+        up_to: u64,
+    },
+    /// Take back the coins `application_id` holds for the signer once the
+    /// hold has expired
+    ReclaimCoinHold {
+        application_id: ApplicationId,
+    },
+}
+
+impl Operation {
+    /// Replay bytes the operation carries, as submitted
+    pub fn replay_bytes(&self) -> u64 {
+        match self {
+            Operation::SaveScore { replay_data, .. } => {
+                replay_data.as_ref().map_or(0, |replay| replay.len() as u64)
+            }
+            Operation::RelayedSaveScore { run, .. } => {
+                run.replay_data.as_ref().map_or(0, |replay| replay.len() as u64)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Name under which a privileged operation is recorded in the admin audit
+    /// log, or None for operations any player can perform
+    fn audit_action(&self) -> Option<&'static str> {
+        match self {
+            Operation::GrantReward { .. } => Some("GrantReward"),
+            Operation::SetRewardClaimWindow { .. } => Some("SetRewardClaimWindow"),
+            Operation::CloseSeason => Some("CloseSeason"),
+            Operation::FreezeLeaderboard { .. } => Some("FreezeLeaderboard"),
+            Operation::UnfreezeLeaderboard => Some("UnfreezeLeaderboard"),
+            Operation::ScheduleAction { .. } => Some("ScheduleAction"),
+            Operation::CancelScheduledAction { .. } => Some("CancelScheduledAction"),
+            Operation::SetEventsSource { .. } => Some("SetEventsSource"),
+            Operation::AddCalendarEntry { .. } => Some("AddCalendarEntry"),
+            Operation::RemoveCalendarEntry { .. } => Some("RemoveCalendarEntry"),
+            Operation::FeatureReplay { .. } => Some("FeatureReplay"),
+            Operation::UnfeatureReplay { .. } => Some("UnfeatureReplay"),
+            Operation::PruneReplays { .. } => Some("PruneReplays"),
+            Operation::SetMinClientVersion { .. } => Some("SetMinClientVersion"),
+            Operation::SetGameConfig { .. } => Some("SetGameConfig"),
+            Operation::AuthorizeChain { .. } => Some("AuthorizeChain"),
+            Operation::RevokeChain { .. } => Some("RevokeChain"),
+            Operation::SetHubChain { .. } => Some("SetHubChain"),
+            Operation::SetNotificationHub { .. } => Some("SetNotificationHub"),
+            Operation::SetWelcomeBonus { .. } => Some("SetWelcomeBonus"),
+            Operation::SetPartnerCapabilities { .. } => Some("SetPartnerCapabilities"),
+            Operation::SetVerified { .. } => Some("SetVerified"),
+            Operation::SetBanned { .. } => Some("SetBanned"),
+            Operation::UpdateNameBlocklist { .. } => Some("UpdateNameBlocklist"),
+            Operation::ResolveNameReview { .. } => Some("ResolveNameReview"),
+            Operation::SetNameScripts { .. } => Some("SetNameScripts"),
+            Operation::SetHubShards { .. } => Some("SetHubShards"),
+            Operation::SetMirrorMode { .. } => Some("SetMirrorMode"),
+            Operation::ResyncMirrors => Some("ResyncMirrors"),
+            Operation::RebalanceShard { .. } => Some("RebalanceShard"),
+            Operation::UpdateConfig { .. } => Some("UpdateConfig"),
+            Operation::SetLeaderboardEntryFee { .. } => Some("SetLeaderboardEntryFee"),
+            Operation::SetSubmissionFee { .. } => Some("SetSubmissionFee"),
+            Operation::ClaimVerification { .. } => Some("ClaimVerification"),
+            Operation::SubmitVerdict { .. } => Some("SubmitVerdict"),
+            Operation::ResolveVerification { .. } => Some("ResolveVerification"),
+            Operation::RebuildLeaderboard => Some("RebuildLeaderboard"),
+            Operation::RebuildIndexes { .. } => Some("RebuildIndexes"),
+            Operation::ArchiveInactivePlayers { .. } => Some("ArchiveInactivePlayers"),
+            Operation::SetProvisionalWindow { .. } => Some("SetProvisionalWindow"),
+            Operation::SetSeasonDecay { .. } => Some("SetSeasonDecay"),
+            Operation::InvalidateScore { .. } => Some("InvalidateScore"),
+            Operation::ResolveAppeal { .. } => Some("ResolveAppeal"),
+            Operation::CreateEvent { .. } => Some("CreateEvent"),
+            Operation::EndEvent { .. } => Some("EndEvent"),
+            Operation::AddQuest { .. } => Some("AddQuest"),
+            Operation::RemoveQuest { .. } => Some("RemoveQuest"),
+            Operation::ScheduleModifier { .. } => Some("ScheduleModifier"),
+            Operation::GrantRole { .. } => Some("GrantRole"),
+            Operation::RevokeRole { .. } => Some("RevokeRole"),
+            Operation::ProposeAdminTransfer { .. } => Some("ProposeAdminTransfer"),
+            Operation::AcceptAdminTransfer => Some("AcceptAdminTransfer"),
+            _ => None,
+        }
+    }
+}
+
+/// Response to an operation, or to a call from another application
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationResponse {
+    /// Nothing to report; also the response to a retry of a score already accepted
+    None,
+    /// The attestation of an accepted SaveScore or RelayedSaveScore
+    Attestation(ScoreAttestation),
+    /// The answer to a partner application's request
+    Partner(PartnerResponse),
 }
 
 /// Contract errors
@@ -50,251 +794,6179 @@ pub enum ContractError {
     #[error("Replay required: high scores must include replay data for verification")]
     ReplayRequired,
     
-    #[error("Replay too large: replay data exceeds 1MB limit")]
+    #[error("Replay too large: replay data exceeds the configured size limit")]
     ReplayTooLarge,
+
+    #[error("Replay quota exceeded: the player's stored replays would exceed their storage quota")]
+    ReplayQuotaExceeded,
+
+    #[error("Unauthorized: this operation requires an admin role the signer does not hold")]
+    AdminOnly,
+
+    #[error("Insufficient prize pool: not enough unreserved tokens for this reward")]
+    InsufficientPrizePool,
+
+    #[error("Reward not found: no claimable reward with this ID")]
+    RewardNotFound,
+
+    #[error("Reward expired: the claim window for this reward has closed")]
+    RewardExpired,
+
+    #[error("Invalid opponent: you cannot race against your own ghost")]
+    CannotRaceSelf,
+
+    #[error("Ghost unavailable: the opponent has no high-score replay to race against")]
+    GhostUnavailable,
+
+    #[error("No active ghost race: start a ghost race before submitting a result")]
+    NoActiveGhostRace,
+
+    #[error("Already queued: the player is already waiting for or playing a race")]
+    AlreadyInRace,
+
+    #[error("Not queued: the player is not waiting in the race queue")]
+    NotInRaceQueue,
+
+    #[error("Race not found: no race with this ID")]
+    RaceNotFound,
+
+    #[error("Not a participant: only the two paired players can submit runs for a race")]
+    NotRaceParticipant,
+
+    #[error("Run already submitted: each participant can only submit one run per race")]
+    RaceRunAlreadySubmitted,
+
+    #[error("Race chain error: the temporary race chain could not be closed")]
+    RaceChainCloseFailed,
     
-    #[error("View error: {0}")]
-    ViewError(#[from] linera_sdk::views::ViewError),
-}
+    #[error("Replay not found: the player has no stored replay")]
+    ReplayNotFound,
 
-/// The contract implementation
-pub struct CrossyChainContract {
-    state: CrossyChainState<ContractRuntime<Self>>,
-    runtime: ContractRuntime<Self>,
-}
+    #[error("Invalid replay: replay data is not a valid recording")]
+    InvalidReplay,
 
-#[async_trait]
-impl Contract for CrossyChainContract {
-    type Error = ContractError;
-    type Message = Message;
-    type Operation = Operation;
-    type State = CrossyChainState<ContractRuntime<Self>>;
+    #[error("Unreachable score: the replay's inputs cannot reach the score it claims")]
+    ScoreUnreachable,
 
-    async fn new(state: Self::State, runtime: ContractRuntime<Self>) -> Result<Self, Self::Error> {
-        Ok(Self { state, runtime })
-    }
+    #[error("Replay score mismatch: the replay is credited another score than the one submitted")]
+    ReplayScoreMismatch,
 
-    fn state_mut(&mut self) -> &mut Self::State {
-        &mut self.state
-    }
+    #[error("Invalid replay compression: the replay does not match its declared compression")]
+    InvalidReplayCompression,
 
-    async fn initialize(&mut self, _argument: Self::InitializationArgument) -> Result<(), Self::Error> {
-        Ok(())
-    }
+    #[error("Replay metadata missing: replays must include client version, platform and format version")]
+    ReplayMetadataMissing,
 
-    async fn execute_operation(&mut self, operation: Self::Operation) -> Result<(), Self::Error> {
-        match operation {
-            Operation::SaveScore {
-                score,
-                replay_data,
-                timestamp,
-            } => {
-                // Reject invalid scores
-                if score == 0 {
-                    return Err(ContractError::InvalidScore);
-                }
+    #[error("Unsupported replay format: this replay format version is not supported")]
+    UnsupportedReplayFormat,
 
-                // Get the authenticated signer (wallet address)
-                let sender = match self.runtime.authenticated_signer() {
-                    Some(owner) => owner.to_string(),
-                    None => return Err(ContractError::Unauthorized),
-                };
+    #[error("Client outdated: this client version is no longer accepted, please update the game")]
+    ClientVersionTooOld,
 
-                // Get or create player data
-                let mut player = self
-                    .state
-                    .players
-                    .get(&sender)
-                    .await?
-                    .unwrap_or_default();
+    #[error("Invalid version: client versions must look like MAJOR.MINOR.PATCH")]
+    InvalidClientVersion,
 
-                // Check if this is a new high score
-                let is_new_high_score = score > player.high_score;
-                
-                // STRICT VALIDATION: Require replay data for all new high scores
-                // This ensures anti-cheat verification is possible for leaderboard entries
-                if is_new_high_score {
-                    // Replay data is mandatory for high scores
-                    if replay_data.is_none() {
-                        return Err(ContractError::ReplayRequired);
-                    }
-                    
-                    let replay_json = replay_data.unwrap();
-                    
-                    // Validate replay data size (limit to 1MB to prevent state bloat)
-                    const MAX_REPLAY_SIZE: usize = 1_000_000; // 1MB
-                    if replay_json.len() > MAX_REPLAY_SIZE {
-                        return Err(ContractError::ReplayTooLarge);
-                    }
-                    
-                    // Update high score and replay atomically
-                    player.high_score = score;
-                    player.replay_data = Some(replay_json);
-                    
-                    // TODO: When Linera SDK blob storage is ready, upload to blob storage:
-                    // let replay_bytes = replay_json.into_bytes();
-                    // let blob_hash = self.runtime.publish_data_blob(replay_bytes).await?;
-                    // player.replay_blob_id = Some(format!("{:?}", blob_hash));
-                    // Then we can remove the replay_data field and use only replay_blob_id
-                }
-                // For non-high scores, we don't update anything related to replays
-                // This preserves the existing high-score replay
-
-                // Increment games played
-                player.games_played += 1;
-
-                // Update last played timestamp
-                player.last_played_at = Some(timestamp);
-
-                // Save updated player data
-                self.state.players.insert(&sender, player)?;
+    #[error("Name too long: display names are limited to 30 characters")]
+    NameTooLong,
 
-                Ok(())
-            }
-            Operation::RegisterPlayer { display_name } => {
-                // Get the authenticated signer (wallet address)
-                let sender = match self.runtime.authenticated_signer() {
-                    Some(owner) => owner.to_string(),
-                    None => return Err(ContractError::Unauthorized),
-                };
+    #[error("Name empty: display names cannot be empty or only whitespace")]
+    NameEmpty,
 
-                // Get or create player data
-                let mut player = self
-                    .state
-                    .players
-                    .get(&sender)
-                    .await?
-                    .unwrap_or_default();
+    #[error("Name blocked: display names cannot contain blocked terms")]
+    NameBlocked,
 
-                // Validate and update display name if provided
-                if let Some(name) = display_name {
-                    let trimmed = name.trim();
-                    if !trimmed.is_empty() && trimmed.len() <= 30 {
-                        player.display_name = Some(trimmed.to_string());
-                    }
-                    // If validation fails, keep existing display name
-                } else {
-                    // Explicitly setting to None clears the display name
-                    player.display_name = None;
-                }
+    #[error("Script not allowed: the display name uses a script its region doesn't allow")]
+    NameScriptNotAllowed,
 
-                // Save updated player data
-                self.state.players.insert(&sender, player)?;
+    #[error("Mixed scripts: display names cannot mix letters of different scripts")]
+    NameMixedScripts,
 
-                Ok(())
-            }
-        }
-    }
+    #[error("Invalid game config: speeds, weights, scoring and difficulty curve must be positive and ordered")]
+    InvalidGameConfig,
 
-    async fn execute_message(&mut self, message: Self::Message) -> Result<(), Self::Error> {
-        match message {
-            Message::SaveScore {
-                score,
-                replay_data,
-                timestamp,
-            } => {
-                // Reject invalid scores
-                if score == 0 {
-                    return Err(ContractError::InvalidScore);
-                }
+    #[error("Unknown config version: the replay was recorded under a game config version that does not exist")]
+    UnknownConfigVersion,
 
-                // Get the authenticated signer (wallet address)
-                let sender = match self.runtime.authenticated_signer() {
-                    Some(owner) => owner.to_string(),
-                    None => return Err(ContractError::Unauthorized),
-                };
+    #[error("Invalid submission id: submission ids must be 1 to 64 characters")]
+    InvalidSubmissionId,
 
-                // Get or create player data
-                let mut player = self
-                    .state
-                    .players
-                    .get(&sender)
-                    .await?
-                    .unwrap_or_default();
+    #[error("Out of order message: SaveScore sequence must be one more than the last accepted sequence")]
+    OutOfOrderMessage,
 
-                // Check if this is a new high score
-                let is_new_high_score = score > player.high_score;
-                
-                // STRICT VALIDATION: Require replay data for all new high scores
-                // This ensures anti-cheat verification is possible for leaderboard entries
-                if is_new_high_score {
-                    // Replay data is mandatory for high scores
-                    if replay_data.is_none() {
-                        return Err(ContractError::ReplayRequired);
-                    }
-                    
-                    let replay_json = replay_data.unwrap();
-                    
-                    // Validate replay data size (limit to 1MB to prevent state bloat)
-                    const MAX_REPLAY_SIZE: usize = 1_000_000; // 1MB
-                    if replay_json.len() > MAX_REPLAY_SIZE {
-                        return Err(ContractError::ReplayTooLarge);
-                    }
-                    
-                    // Update high score and replay atomically
-                    player.high_score = score;
-                    player.replay_data = Some(replay_json);
-                    
-                    // TODO: When Linera SDK blob storage is ready, upload to blob storage:
-                    // let replay_bytes = replay_json.into_bytes();
-                    // let blob_hash = self.runtime.publish_data_blob(replay_bytes).await?;
-                    // player.replay_blob_id = Some(format!("{:?}", blob_hash));
-                    // Then we can remove the replay_data field and use only replay_blob_id
-                }
-                // For non-high scores, we don't update anything related to replays
-                // This preserves the existing high-score replay
-
-                // Increment games played
-                player.games_played += 1;
-
-                // Update last played timestamp
-                player.last_played_at = Some(timestamp);
-
-                // Save updated player data
-                self.state.players.insert(&sender, player)?;
+    #[error("Invalid wallet address: expected a hex-encoded owner")]
+    InvalidWalletAddress,
 
-                Ok(())
-            }
-            Message::RegisterPlayer { display_name } => {
-                // Get the authenticated signer (wallet address)
-                let sender = match self.runtime.authenticated_signer() {
-                    Some(owner) => owner.to_string(),
-                    None => return Err(ContractError::Unauthorized),
-                };
+    #[error("Invalid timestamp: expected an RFC 3339 date-time such as 2024-05-01T12:00:00Z")]
+    InvalidTimestamp,
 
-                // Get or create player data
-                let mut player = self
-                    .state
-                    .players
-                    .get(&sender)
-                    .await?
-                    .unwrap_or_default();
+    #[error("Unauthorized origin: messages are only accepted from authorized game chains")]
+    UnauthorizedOrigin,
 
-                // Validate and update display name if provided
-                if let Some(name) = display_name {
-                    let trimmed = name.trim();
-                    if !trimmed.is_empty() && trimmed.len() <= 30 {
-                        player.display_name = Some(trimmed.to_string());
-                    }
-                    // If validation fails, keep existing display name
-                } else {
-                    // Explicitly setting to None clears the display name
-                    player.display_name = None;
-                }
+    #[error("Player not found: this wallet has no data on this chain")]
+    PlayerNotFound,
 
-                // Save updated player data
-                self.state.players.insert(&sender, player)?;
+    #[error("Invalid migration target: accounts can only be moved to another chain")]
+    InvalidMigrationTarget,
 
-                Ok(())
-            }
-        }
-    }
+    #[error("Cannot link self: an owner cannot be linked to its own profile")]
+    CannotLinkSelf,
 
-    async fn finalize(&mut self) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
+    #[error("Already linked: this owner already has a profile or is linked to one")]
+    AlreadyLinked,
 
-impl WithContractAbi for CrossyChainContract {
-    type Abi = crate::CrossyChainAbi;
+    #[error("No pending link: the profile owner has not requested this link")]
+    NoPendingLink,
+
+    #[error("Invalid session key: the key must differ from the owner and expire within 7 days")]
+    InvalidSessionKey,
+
+    #[error("Session key expired: authorize a new session key to keep submitting scores")]
+    SessionKeyExpired,
+
+    #[error("No pending admin transfer: the admin has not proposed a transfer to this wallet")]
+    NoPendingAdminTransfer,
+
+    #[error("Config value out of bounds: the value is outside the sanity bounds of this field")]
+    ConfigValueOutOfBounds,
+
+    #[error("Rate limited: too many scores submitted this hour")]
+    RateLimited,
+
+    #[error("Season not over: a season can only be closed once its configured length has passed")]
+    SeasonNotOver,
+
+    #[error("Death cause mismatch: the reported cause of death does not match the replay")]
+    DeathCauseMismatch,
+
+    #[error("Quest not found: no quest with this id in the catalog")]
+    QuestNotFound,
+
+    #[error("Invalid quest: quests need a title and a target greater than 0")]
+    InvalidQuest,
+
+    #[error("Quest not completed: today's progress has not reached the quest target")]
+    QuestNotCompleted,
+
+    #[error("Quest already claimed: the reward for this quest was already collected today")]
+    QuestAlreadyClaimed,
+
+    #[error("Invalid commitment: commitments must be a hex SHA-256 hash")]
+    InvalidCommitment,
+
+    #[error("Loot box pending: open the loot box already bought before buying another")]
+    LootBoxPending,
+
+    #[error("No loot box: there is no bought loot box to open")]
+    NoLootBoxPending,
+
+    #[error("Loot box not drawn yet: wait for the hub chain to draw its seed before opening it")]
+    LootSeedPending,
+
+    #[error("Commitment mismatch: the secret does not match the loot box commitment")]
+    CommitmentMismatch,
+
+    #[error("Cannot gift self: coins can only be gifted to another player")]
+    CannotGiftSelf,
+
+    #[error("Insufficient coins: the coin balance is lower than the amount")]
+    InsufficientCoins,
+
+    #[error("Too many coins: a run collects at most 3 coins per lane it reaches")]
+    TooManyCoins,
+
+    #[error("Account too new: wallets must have played for a while before gifting coins")]
+    AccountTooNew,
+
+    #[error("Gift limit exceeded: this gift would go over the daily gifting cap")]
+    GiftLimitExceeded,
+
+    #[error("Cannot tip self: tips can only be sent to another player")]
+    CannotTipSelf,
+
+    #[error("Invalid tip: the tip amount must be greater than 0")]
+    InvalidTipAmount,
+
+    #[error("No entry fee: the leaderboard entry fee is disabled")]
+    NoEntryFee,
+
+    #[error("Entry fee already paid: this player already paid the leaderboard entry fee")]
+    EntryFeeAlreadyPaid,
+
+    #[error("Verification not found: no queued replay with this id")]
+    VerificationNotFound,
+
+    #[error("Verification unavailable: the replay is already claimed or settled")]
+    VerificationUnavailable,
+
+    #[error("Not the claimer: only the verifier holding the claim can submit a verdict")]
+    NotVerificationClaimer,
+
+    #[error("Already verified: a verifier can only give one verdict per replay")]
+    AlreadyVerified,
+
+    #[error("Cannot verify own score: verifiers can't settle their own replays")]
+    CannotVerifyOwnScore,
+
+    #[error("Not escalated: only replays the verifiers disagreed on can be resolved")]
+    VerificationNotEscalated,
+
+    #[error("Invalid result hash: result hashes must be a hex SHA-256 hash")]
+    InvalidResultHash,
+
+    #[error("Malformed proof: proofs must be hex-encoded and at most 64KiB")]
+    MalformedProof,
+
+    #[error("Invalid proof: the proof does not show the replay yields the score")]
+    InvalidProof,
+
+    #[error("Wrong shard: this hub does not hold the wallet's scores under the current shard assignment")]
+    WrongShard,
+
+    #[error("Stale shard epoch: a shard assignment must have a higher epoch than the current one")]
+    StaleShardEpoch,
+
+    #[error("Read-only mirror: this chain only mirrors its hub's leaderboard")]
+    ReadOnlyMirror,
+
+    #[error("Invalid reason: reasons must be 1 to 500 characters")]
+    InvalidReason,
+
+    #[error("No score to invalidate: the player has no high score")]
+    NoScoreToInvalidate,
+
+    #[error("Invalidation not found: no score invalidation with that id")]
+    InvalidationNotFound,
+
+    #[error("Not your invalidation: players can only appeal invalidations of their own scores")]
+    NotInvalidatedPlayer,
+
+    #[error("Already appealed: each invalidation can be appealed once")]
+    AlreadyAppealed,
+
+    #[error("Invalid statement: appeal statements must be 1 to 1000 characters")]
+    InvalidStatement,
+
+    #[error("Appeal not found: no appeal against that invalidation")]
+    AppealNotFound,
+
+    #[error("Appeal already resolved: the appeal was already upheld or overturned")]
+    AppealAlreadyResolved,
+
+    #[error("Invalid region: expected a two-letter country code and a city of up to 64 characters")]
+    InvalidRegion,
+
+    #[error("Invalid event tag: tags must be 1 to 32 lower-case letters, digits or dashes")]
+    InvalidEventTag,
+
+    #[error("Invalid event name: event names must be 1 to 64 characters")]
+    InvalidEventName,
+
+    #[error("Invalid event window: events must end after they start")]
+    InvalidEventWindow,
+
+    #[error("Event exists: an event with that tag was already created")]
+    EventExists,
+
+    #[error("Event not found: no event with that tag")]
+    EventNotFound,
+
+    #[error("Event closed: the event has ended")]
+    EventClosed,
+
+    #[error("Already registered: players register into each event once")]
+    AlreadyRegistered,
+
+    #[error("Event still running: results are frozen once the event window closes")]
+    EventStillRunning,
+
+    #[error("Event already ended: the event results are already frozen")]
+    EventAlreadyEnded,
+
+    #[error("Wrong event kind: the operation does not apply to this kind of event")]
+    WrongEventKind,
+
+    #[error("Invalid team name: team names must be 1 to 30 characters")]
+    InvalidTeamName,
+
+    #[error("Team not found: no team with that id")]
+    TeamNotFound,
+
+    #[error("Roster locked: teams can only change before the event starts")]
+    TeamRosterLocked,
+
+    #[error("Already in a team: players play for one team per event")]
+    AlreadyInTeam,
+
+    #[error("Not the team captain: only the captain can invite players")]
+    NotTeamCaptain,
+
+    #[error("Team full: teams have 3 players")]
+    TeamFull,
+
+    #[error("Too many invites: a team can have at most 5 pending invites")]
+    TooManyTeamInvites,
+
+    #[error("Not invited: the player has no pending invite into that team")]
+    NotInvited,
+
+    #[error("Invalid season decay: decay must be 1 to 100 percent per week, starting within a year")]
+    InvalidSeasonDecay,
+
+    #[error("Invalid relayed run: the player's signature does not match the run")]
+    InvalidRelaySignature,
+
+    #[error("Relayed run expired: relayed runs must be submitted within an hour of signing")]
+    RelayedRunExpired,
+
+    #[error("Wrong relay chain: relayed runs must be submitted on the chain they were signed for")]
+    WrongRelayChain,
+
+    #[error("Relay off hub: relayed runs must be submitted on the player's hub chain")]
+    RelayOffHub,
+
+    #[error("Cannot add yourself as a friend")]
+    CannotFriendSelf,
+
+    #[error("Too many friends: a player can have at most 200 friends")]
+    TooManyFriends,
+
+    #[error("Invalid leaderboard freeze: a freeze must last 1 second to 24 hours")]
+    InvalidLeaderboardFreeze,
+
+    #[error("Too many scheduled actions: at most 50 actions can be scheduled at once")]
+    TooManyScheduledActions,
+
+    #[error("Scheduled action not found")]
+    ScheduledActionNotFound,
+
+    #[error("Calendar full: the event calendar holds at most 100 entries")]
+    CalendarFull,
+
+    #[error("Calendar entry not found")]
+    CalendarEntryNotFound,
+
+    #[error("No events source: this chain follows no event calendar")]
+    NoEventsSource,
+
+    #[error("Too many event subscribers: at most 500 players per chain get event notifications")]
+    TooManyEventSubscribers,
+
+    #[error("Too many events: a wallet can take part in at most 20 events until they end")]
+    TooManyEvents,
+
+    #[error("Too many teams: a team relay event holds at most 500 teams")]
+    TooManyTeams,
+
+    #[error("Not an application: partner requests must be called by an application")]
+    NotAnApplication,
+
+    #[error("Caller not allowed: the calling application is not allowlisted for {0:?}")]
+    CallerNotAllowed(PartnerCapability),
+
+    #[error("XP grant too large: a grant can be at most 10000 XP")]
+    XpGrantTooLarge,
+
+    #[error("Coin hold not found: the application holds no coins for this player")]
+    CoinHoldNotFound,
+
+    #[error("Coin hold expired: the coins are the player's to reclaim")]
+    CoinHoldExpired,
+
+    #[error("Coin hold not expired: the application can still settle it")]
+    CoinHoldNotExpired,
+
+    #[error("Not the hub: score attestations are issued by the player's hub chain")]
+    AttestationOffHub,
+
+    #[error("Attestation digest mismatch: the digest doesn't cover the attested fields")]
+    AttestationDigestMismatch,
+
+    #[error("Rename cooldown: the display name can change once every 7 days")]
+    RenameCooldown,
+
+    #[error("Name impersonates a verified player: choose a name that doesn't look like theirs")]
+    NameImpersonates,
+
+    #[error("Too many blocklist terms: the blocklist holds at most 1000 terms")]
+    TooManyBlocklistTerms,
+
+    #[error("Name review not found: the player has no flagged display name")]
+    NameReviewNotFound,
+
+    #[error("View error: {0}")]
+    ViewError(#[from] linera_sdk::views::ViewError),
+}
+
+impl ContractError {
+    /// Whether the error may go away if the same message is delivered again
+    /// later, as opposed to a rejection of its content
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ContractError::ViewError(_)
+                | ContractError::OutOfOrderMessage
+                | ContractError::WrongShard
+        )
+    }
+}
+
+impl From<ReplayFormatError> for ContractError {
+    fn from(error: ReplayFormatError) -> Self {
+        match error {
+            ReplayFormatError::Malformed => ContractError::InvalidReplay,
+            ReplayFormatError::UnknownVersion(_) => ContractError::UnsupportedReplayFormat,
+        }
+    }
+}
+
+impl From<ReplayError> for ContractError {
+    fn from(error: ReplayError) -> Self {
+        match error {
+            ReplayError::Format(error) => error.into(),
+            ReplayError::ScoreUnreachable { .. } => ContractError::ScoreUnreachable,
+            ReplayError::NotUtf8
+            | ReplayError::InputsOutOfOrder { .. }
+            | ReplayError::InputAfterEnd { .. } => ContractError::InvalidReplay,
+        }
+    }
+}
+
+impl From<CompressionError> for ContractError {
+    fn from(_: CompressionError) -> Self {
+        ContractError::InvalidReplayCompression
+    }
+}
+
+impl From<ProofError> for ContractError {
+    fn from(error: ProofError) -> Self {
+        match error {
+            ProofError::Malformed => ContractError::MalformedProof,
+            ProofError::Invalid | ProofError::Unsupported => ContractError::InvalidProof,
+        }
+    }
+}
+
+impl From<ValidationError> for ContractError {
+    fn from(error: ValidationError) -> Self {
+        match error {
+            ValidationError::InvalidScore => ContractError::InvalidScore,
+            ValidationError::ReplayTooLarge => ContractError::ReplayTooLarge,
+            ValidationError::InvalidClientVersion => ContractError::InvalidClientVersion,
+            ValidationError::InvalidSubmissionId => ContractError::InvalidSubmissionId,
+            ValidationError::NameTooLong => ContractError::NameTooLong,
+            ValidationError::NameEmpty => ContractError::NameEmpty,
+            ValidationError::InvalidWalletAddress => ContractError::InvalidWalletAddress,
+            ValidationError::InvalidTimestamp => ContractError::InvalidTimestamp,
+            ValidationError::InvalidRegion => ContractError::InvalidRegion,
+            ValidationError::NameBlocked => ContractError::NameBlocked,
+            ValidationError::NameScriptNotAllowed => ContractError::NameScriptNotAllowed,
+            ValidationError::NameMixedScripts => ContractError::NameMixedScripts,
+            ValidationError::TooManyCoins => ContractError::TooManyCoins,
+        }
+    }
+}
+
+/// Number of final standings kept per closed season for the hall of fame;
+/// the full standings are only in the season's data blob
+const SEASON_LEADERBOARD_SIZE: usize = 100;
+
+/// Cosmetic drops and their weights; rarer items have lower weights
+const LOOT_TABLE: [(&str, u64); 6] = [
+    ("color_blue", 50),
+    ("hat_cap", 30),
+    ("trail_rainbow", 10),
+    ("hat_crown", 5),
+    ("chicken_pirate", 4),
+    ("chicken_gold", 1),
+];
+
+/// How long a verifier's claim on a queued replay lasts before another
+/// verifier can take it over (seconds)
+const VERIFICATION_CLAIM_TIMEOUT: u64 = 60 * 60;
+
+/// Length of the window `max_submissions_per_hour` is counted over
+const RATE_LIMIT_WINDOW: u64 = 60 * 60;
+
+/// Longest time a session key can stay valid (7 days)
+const MAX_SESSION_KEY_LIFETIME: u64 = 7 * 24 * 60 * 60;
+
+/// Longest a relayed run stays valid after it is signed
+const MAX_RELAYED_RUN_LIFETIME: u64 = 60 * 60;
+
+/// A run as signed by its player for a relayer to submit with `RelayedSaveScore`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayedRun {
+    /// Chain the run may be submitted on: the player's hub chain
+    pub chain_id: ChainId,
+    /// Last time the run may be submitted (UNIX timestamp), at most an hour ahead
+    pub expires_at: u64,
+    pub score: u32,
+    pub replay_data: Option<String>,
+    pub compression: ReplayCompression,
+    pub timestamp: u64,
+    pub client_version: String,
+    /// Also keeps a relayer from submitting the same run twice
+    pub submission_id: String,
+    pub run_stats: Option<RunStats>,
+    pub proof: Option<String>,
+}
+
+impl BcsSignable for RelayedRun {}
+
+impl RelayedRun {
+    fn into_submission(self) -> ScoreSubmission {
+        ScoreSubmission {
+            score: self.score,
+            replay_data: self.replay_data,
+            compression: self.compression,
+            timestamp: self.timestamp,
+            client_version: self.client_version,
+            submission_id: self.submission_id,
+            run_stats: self.run_stats,
+            proof: self.proof,
+        }
+    }
+}
+
+/// Fields of a SaveScore operation or message, handled the same way for both
+#[derive(Clone)]
+struct ScoreSubmission {
+    score: u32,
+    replay_data: Option<String>,
+    compression: ReplayCompression,
+    timestamp: u64,
+    client_version: String,
+    submission_id: String,
+    run_stats: Option<RunStats>,
+    proof: Option<String>,
+}
+
+/// The contract implementation
+pub struct CrossyChainContract {
+    state: CrossyChainState<ContractRuntime<Self>>,
+    runtime: ContractRuntime<Self>,
+}
+
+#[async_trait]
+impl Contract for CrossyChainContract {
+    type Error = ContractError;
+    type Message = Message;
+    type Operation = Operation;
+    type State = CrossyChainState<ContractRuntime<Self>>;
+
+    async fn new(state: Self::State, runtime: ContractRuntime<Self>) -> Result<Self, Self::Error> {
+        Ok(Self { state, runtime })
+    }
+
+    fn state_mut(&mut self) -> &mut Self::State {
+        &mut self.state
+    }
+
+    async fn initialize(&mut self, _argument: Self::InitializationArgument) -> Result<(), Self::Error> {
+        // The wallet that creates the application administers it
+        let admin = self.runtime.authenticated_signer().map(PlayerKey::from);
+        self.state.admin.set(admin);
+        self.state.schema_version.set(STATE_SCHEMA_VERSION);
+        Ok(())
+    }
+
+    async fn execute_operation(
+        &mut self,
+        operation: Self::Operation,
+    ) -> Result<OperationResponse, Self::Error> {
+        // A mirror's state only changes through its hub's deltas
+        let is_mirror = self.state.config.get().mirror_of.is_some();
+        if is_mirror && !matches!(operation, Operation::SetMirrorMode { .. }) {
+            return Err(ContractError::ReadOnlyMirror);
+        }
+        self.migrate_state().await?;
+
+        // Time-based transitions happen on the first player operation after
+        // they are due. Admin operations never wait on them, so a stuck
+        // action can always be cancelled.
+        if operation.audit_action().is_none() {
+            self.run_due_actions().await?;
+        }
+
+        // Give scores that bounced off the hub another try; resolving the
+        // signer's account also brings it back from the archive
+        if let Some(owner) = self.runtime.authenticated_signer() {
+            self.player_account(PlayerKey::from(owner)).await?;
+            self.retry_hub_sync(PlayerKey::from(owner)).await?;
+        }
+
+        // A rejected operation fails the whole block, so only executed ones stay in the log
+        if let Some(action) = operation.audit_action() {
+            self.record_audit_entry(action, &operation);
+        }
+        let name: &'static str = (&operation).into();
+        let replay_bytes = operation.replay_bytes();
+
+        let mut response = OperationResponse::None;
+        let outcome = match operation {
+            Operation::SaveScore {
+                score,
+                replay_data,
+                compression,
+                timestamp,
+                client_version,
+                submission_id,
+                run_stats,
+                proof,
+            } => {
+                let owner = self.signer()?;
+                let sender = PlayerKey::from(owner);
+                let submission = ScoreSubmission {
+                    score,
+                    replay_data,
+                    compression,
+                    timestamp,
+                    client_version,
+                    submission_id,
+                    run_stats,
+                    proof,
+                };
+                let attestation = self.handle_save_score(sender, submission.clone(), false).await?;
+                // Retries of a counted submission are not charged again
+                if let Some(attestation) = attestation {
+                    self.charge_submission_fee(owner, sender);
+                    self.forward_score_to_hub(sender, submission).await?;
+                    response = OperationResponse::Attestation(attestation);
+                }
+                Ok(())
+            }
+            Operation::RelayedSaveScore {
+                player,
+                run,
+                signature,
+            } => {
+                let relayer = self.signer()?;
+                let sender = PlayerKey::from(Owner::from(player));
+                self.check_relayed_run(sender, &run)?;
+                signature
+                    .check(&run, player)
+                    .map_err(|_| ContractError::InvalidRelaySignature)?;
+
+                // The hub takes the run as it would an operation of the player,
+                // so nothing is forwarded
+                let submission = run.into_submission();
+                let attestation = self.handle_save_score(sender, submission, false).await?;
+                if let Some(attestation) = attestation {
+                    self.charge_submission_fee(relayer, sender);
+                    response = OperationResponse::Attestation(attestation);
+                }
+                Ok(())
+            }
+            Operation::RegisterPlayer { display_name } => {
+                let sender = PlayerKey::from(self.signer()?);
+                self.handle_register_player(sender, display_name).await
+            }
+            Operation::FundPrizePool { amount } => {
+                let owner = self.signer()?;
+                let application_account = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: None,
+                };
+                self.runtime.transfer(Some(owner), application_account, amount);
+
+                let pool = self.state.prize_pool.get().saturating_add(amount);
+                self.state.prize_pool.set(pool);
+
+                Ok(())
+            }
+            Operation::GrantReward {
+                wallet,
+                amount,
+                source,
+                source_id,
+            } => {
+                self.ensure_role(Role::Verifier).await?;
+                let wallet = validation::validate_wallet_address(&wallet)?;
+                // A linked owner's reward goes to the profile it plays as
+                let recipient = self.player_account(wallet).await?;
+                let reward_id = self.grant_reward(recipient, amount, source, source_id).await?;
+                let kind = NotificationKind::RewardClaimable;
+                self.notify(&recipient, kind, None, Some(reward_id)).await?;
+                Ok(())
+            }
+            Operation::ClaimReward { reward_id } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+
+                let reward = self
+                    .state
+                    .rewards
+                    .get(&reward_id)
+                    .await?
+                    .ok_or(ContractError::RewardNotFound)?;
+
+                // Rewards can only be claimed by the profile they were granted to
+                if reward.wallet_address != account {
+                    return Err(ContractError::Unauthorized);
+                }
+
+                // Expired rewards are left in place for ExpireRewards to return to the pool
+                if self.now() > reward.expires_at {
+                    return Err(ContractError::RewardExpired);
+                }
+
+                let destination = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: Some(owner),
+                };
+                self.runtime.transfer(None, destination, reward.amount);
+                self.remove_reward(reward_id, account).await?;
+
+                Ok(())
+            }
+            Operation::ExpireRewards => {
+                self.expire_rewards().await?;
+                Ok(())
+            }
+            Operation::SetRewardClaimWindow { seconds } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().reward_claim_window = seconds;
+                Ok(())
+            }
+            Operation::FreezeLeaderboard { seconds } => {
+                let owner = self.ensure_role(Role::SuperAdmin).await?;
+                if seconds == 0 || seconds > MAX_LEADERBOARD_FREEZE {
+                    return Err(ContractError::InvalidLeaderboardFreeze);
+                }
+                // Extending a running freeze keeps the updates it held back
+                let frozen_at = self.now();
+                let freeze = LeaderboardFreeze {
+                    frozen_at,
+                    until: frozen_at + seconds,
+                    frozen_by: owner.to_string(),
+                };
+                self.state.leaderboard_freeze.set(Some(freeze));
+                Ok(())
+            }
+            Operation::UnfreezeLeaderboard => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.unfreeze_leaderboard().await
+            }
+            Operation::ScheduleAction {
+                kind,
+                event_tag,
+                due_at,
+            } => {
+                let owner = self.ensure_role(Role::SuperAdmin).await?;
+                let event_tag = match kind {
+                    ScheduledActionKind::EndEvent => {
+                        let tag = event_tag.ok_or(ContractError::EventNotFound)?;
+                        if !self.state.events.contains_key(&tag).await? {
+                            return Err(ContractError::EventNotFound);
+                        }
+                        Some(tag)
+                    }
+                    // Announcements are scheduled with their calendar entry
+                    ScheduledActionKind::AnnounceCalendarEntry => {
+                        return Err(ContractError::CalendarEntryNotFound);
+                    }
+                    _ => None,
+                };
+                let owner = owner.to_string();
+                self.schedule_action(kind, event_tag, None, due_at, owner).await
+            }
+            Operation::CancelScheduledAction { action_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                // Cancelling also clears an action parked after a failure
+                if self.state.failed_actions.contains_key(&action_id).await? {
+                    self.state.failed_actions.remove(&action_id)?;
+                } else if self.state.scheduled_actions.contains_key(&action_id).await? {
+                    self.state.scheduled_actions.remove(&action_id)?;
+                } else {
+                    return Err(ContractError::ScheduledActionNotFound);
+                }
+                Ok(())
+            }
+            Operation::AddCalendarEntry {
+                kind,
+                title,
+                starts_at,
+                ends_at,
+                event_tag,
+                modifier,
+            } => {
+                let admin = self.ensure_role(Role::SuperAdmin).await?;
+                let title = title.trim().to_string();
+                if title.is_empty() || title.chars().count() > MAX_EVENT_NAME_LEN {
+                    return Err(ContractError::InvalidEventName);
+                }
+                if ends_at <= starts_at {
+                    return Err(ContractError::InvalidEventWindow);
+                }
+                if let Some(tag) = &event_tag {
+                    if !self.state.events.contains_key(tag).await? {
+                        return Err(ContractError::EventNotFound);
+                    }
+                }
+                let now = self.now();
+                for id in self.state.calendar.indices().await? {
+                    let ended = self
+                        .state
+                        .calendar
+                        .get(&id)
+                        .await?
+                        .map_or(true, |entry| entry.ends_at <= now);
+                    if ended {
+                        self.state.calendar.remove(&id)?;
+                    }
+                }
+                if self.state.calendar.count().await? >= MAX_CALENDAR_ENTRIES {
+                    return Err(ContractError::CalendarFull);
+                }
+
+                let id = *self.state.next_calendar_entry_id.get();
+                self.state.next_calendar_entry_id.set(id + 1);
+                let entry = CalendarEntry {
+                    id,
+                    kind,
+                    title,
+                    starts_at,
+                    ends_at,
+                    event_tag,
+                    modifier,
+                    created_by: admin.to_string(),
+                };
+                self.state.calendar.insert(&id, entry)?;
+                let kind = ScheduledActionKind::AnnounceCalendarEntry;
+                self.schedule_action(kind, None, Some(id), starts_at, admin.to_string()).await
+            }
+            Operation::RemoveCalendarEntry { entry_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if !self.state.calendar.contains_key(&entry_id).await? {
+                    return Err(ContractError::CalendarEntryNotFound);
+                }
+                // Its announcement is dropped when it comes due
+                self.state.calendar.remove(&entry_id)?;
+                Ok(())
+            }
+            Operation::SetEventsSource { calendar_chain } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let channel = ChannelName::from(EVENTS_CHANNEL.to_vec());
+                let source = *self.state.events_source.get();
+                if source != calendar_chain {
+                    if let Some(previous) = source {
+                        self.runtime.unsubscribe(previous, channel.clone());
+                    }
+                    if let Some(calendar_chain) = calendar_chain {
+                        self.runtime.subscribe(calendar_chain, channel);
+                    }
+                    self.state.events_source.set(calendar_chain);
+                }
+                Ok(())
+            }
+            Operation::SubscribeToEvents => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                if self.state.events_source.get().is_none() {
+                    return Err(ContractError::NoEventsSource);
+                }
+                if !self.state.event_subscribers.contains(&account).await?
+                    && self.state.event_subscribers.count().await? >= MAX_EVENT_SUBSCRIBERS
+                {
+                    return Err(ContractError::TooManyEventSubscribers);
+                }
+                self.state.event_subscribers.insert(&account)?;
+                Ok(())
+            }
+            Operation::UnsubscribeFromEvents => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                self.state.event_subscribers.remove(&account)?;
+                Ok(())
+            }
+            Operation::CloseSeason => {
+                self.ensure_role(Role::Verifier).await?;
+                if self.now() < self.season_ends_at() {
+                    return Err(ContractError::SeasonNotOver);
+                }
+                self.close_season().await?;
+                Ok(())
+            }
+            Operation::StartGhostRace { opponent } => {
+                let owner = self.signer()?;
+                let opponent_key = validation::validate_wallet_address(&opponent)?;
+                if opponent_key == PlayerKey::from(owner) {
+                    return Err(ContractError::CannotRaceSelf);
+                }
+                let sender = owner.to_string();
+
+                // The ghost is the opponent's stored high-score replay
+                let opponent_data = self
+                    .state
+                    .players
+                    .get(&opponent_key)
+                    .await?
+                    .filter(|data| data.replay_data.is_some())
+                    .ok_or(ContractError::GhostUnavailable)?;
+
+                // Snapshot the ghost's score so a later opponent high score doesn't change the race
+                let race = GhostRace {
+                    opponent: opponent_key.to_string(),
+                    opponent_score: opponent_data.high_score,
+                    started_at: self.now(),
+                };
+                self.state.ghost_races.insert(&sender, race)?;
+
+                let kind = NotificationKind::ChallengeReceived;
+                self.notify(&opponent_key, kind, Some(PlayerKey::from(owner)), None).await?;
+                Ok(())
+            }
+            Operation::SubmitGhostRaceResult { score, replay_data } => {
+                let owner = self.signer()?;
+                let sender = owner.to_string();
+                let race = self
+                    .state
+                    .ghost_races
+                    .get(&sender)
+                    .await?
+                    .ok_or(ContractError::NoActiveGhostRace)?;
+
+                let opponent = validation::validate_wallet_address(&race.opponent)?;
+                let pair = (PlayerKey::from(owner), opponent);
+                let mut tally = self.state.ghost_race_tallies.get(&pair).await?.unwrap_or_default();
+                let outcome = if score > race.opponent_score {
+                    tally.wins += 1;
+                    MatchOutcome::Win
+                } else if score < race.opponent_score {
+                    tally.losses += 1;
+                    MatchOutcome::Loss
+                } else {
+                    tally.draws += 1;
+                    MatchOutcome::Draw
+                };
+                self.state.ghost_race_tallies.insert(&pair, tally)?;
+                self.state.ghost_races.remove(&sender)?;
+
+                // Only a run its replay backs is rated, once per ghost snapshot
+                let Some(replay) = replay_data else {
+                    return Ok(());
+                };
+                let max_replay_size = self.state.config.get().max_replay_size;
+                validation::validate_replay_size(&replay, max_replay_size)?;
+                // Checked under the config the run was played with, if it says
+                let version = Recording::parse(&replay)
+                    .ok()
+                    .and_then(|recording| recording.metadata())
+                    .map_or(*self.state.config_version.get(), |meta| meta.config_version);
+                let config = self.game_config_at(version).await?;
+                if replay::verify_replay(replay.as_bytes(), &config).ok() != Some(score) {
+                    return Err(ContractError::InvalidReplay);
+                }
+                let rated = self.state.rated_ghost_races.get(&pair).await?;
+                if rated == Some(race.opponent_score) {
+                    return Ok(());
+                }
+                self.state.rated_ghost_races.insert(&pair, race.opponent_score)?;
+
+                // The ghost's owner didn't play, so only the racer is rated
+                let mode = HeadToHeadMode::GhostRace;
+                self.rate_against(mode, pair.0, opponent, outcome).await
+            }
+            Operation::EnqueueRace { public_key } => {
+                let owner = self.signer()?;
+                let sender = owner.to_string();
+                if self.state.queued_racers.contains_key(&sender).await?
+                    || self.state.active_races.contains_key(&sender).await?
+                {
+                    return Err(ContractError::AlreadyInRace);
+                }
+
+                let bracket = self
+                    .state
+                    .players
+                    .get(&PlayerKey::from(owner))
+                    .await?
+                    .map_or(0, |player| player.matchmaking_bucket());
+                let chain_id = self.runtime.chain_id();
+
+                match self.state.race_queue.get(&bracket).await? {
+                    // Someone in the same bracket is waiting: pair them up
+                    Some(waiting) => {
+                        self.state.race_queue.remove(&bracket)?;
+                        let opponent = self
+                            .state
+                            .queued_racers
+                            .get(&waiting)
+                            .await?
+                            .ok_or(ContractError::NotInRaceQueue)?;
+                        self.state.queued_racers.remove(&waiting)?;
+                        let racer = QueuedRacer {
+                            bracket,
+                            chain_id,
+                            public_key,
+                        };
+                        self.start_race((waiting, opponent), (sender, racer))?;
+                    }
+                    // Nobody waiting yet: wait for the next player in this bracket
+                    None => {
+                        self.state.race_queue.insert(&bracket, sender.clone())?;
+                        self.state.queued_racers.insert(
+                            &sender,
+                            QueuedRacer {
+                                bracket,
+                                chain_id,
+                                public_key,
+                            },
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+            Operation::LeaveRaceQueue => {
+                let sender = self.signer()?.to_string();
+                let queued = self
+                    .state
+                    .queued_racers
+                    .get(&sender)
+                    .await?
+                    .ok_or(ContractError::NotInRaceQueue)?;
+                self.state.race_queue.remove(&queued.bracket)?;
+                self.state.queued_racers.remove(&sender)?;
+                Ok(())
+            }
+            Operation::SubmitRaceRun { race_id, score } => {
+                let sender = self.signer()?.to_string();
+                let mut race = self
+                    .state
+                    .races
+                    .get(&race_id)
+                    .await?
+                    .ok_or(ContractError::RaceNotFound)?;
+
+                let slot = race
+                    .players
+                    .iter()
+                    .position(|player| *player == sender)
+                    .ok_or(ContractError::NotRaceParticipant)?;
+                let submitted = race
+                    .scores
+                    .get_mut(slot)
+                    .ok_or(ContractError::NotRaceParticipant)?;
+                if submitted.is_some() {
+                    return Err(ContractError::RaceRunAlreadySubmitted);
+                }
+                *submitted = Some(score);
+
+                // Once both runs are in, settle the race
+                if let [Some(first), Some(second)] = race.scores {
+                    race.winner = if first > second {
+                        Some(race.players[0].clone())
+                    } else if second > first {
+                        Some(race.players[1].clone())
+                    } else {
+                        None
+                    };
+                    race.finished = true;
+                    for player in &race.players {
+                        self.state.active_races.remove(player)?;
+                    }
+                    // Races on a temporary chain are rated by the hub when it
+                    // hears they settled
+                    if !self.state.race_chain_info.contains_key(&race_id).await? {
+                        self.rate_race(&race).await?;
+                    }
+
+                    // On a temporary race chain, report back and close the chain
+                    if let Some(info) = self.state.race_chain_info.get(&race_id).await? {
+                        let mut snapshot = self.race_snapshot(race_id, &race).await?;
+                        snapshot.finished = true;
+                        self.publish_snapshot(snapshot);
+                        for chain_id in [info.hub, info.home_chains[0], info.home_chains[1]] {
+                            self.runtime
+                                .prepare_message(Message::RaceSettled {
+                                    race_id,
+                                    race: race.clone(),
+                                })
+                                .with_authentication()
+                                .send_to(chain_id);
+                        }
+                        self.runtime
+                            .close_chain()
+                            .map_err(|_| ContractError::RaceChainCloseFailed)?;
+                    }
+                }
+
+                self.state.races.insert(&race_id, race)?;
+                Ok(())
+            }
+            Operation::UpdateRacePosition { race_id, position } => {
+                let sender = self.signer()?.to_string();
+                let race = self
+                    .state
+                    .races
+                    .get(&race_id)
+                    .await?
+                    .ok_or(ContractError::RaceNotFound)?;
+                let info = self
+                    .state
+                    .race_chain_info
+                    .get(&race_id)
+                    .await?
+                    .ok_or(ContractError::RaceNotFound)?;
+                let slot = race
+                    .players
+                    .iter()
+                    .position(|player| *player == sender)
+                    .ok_or(ContractError::NotRaceParticipant)?;
+
+                self.state
+                    .race_positions
+                    .insert(&(race_id, sender.clone()), position.clone())?;
+
+                // Push the update to the opponent's main chain
+                let [first_chain, second_chain] = info.home_chains;
+                let opponent_chain = if slot == 0 { second_chain } else { first_chain };
+                self.runtime
+                    .prepare_message(Message::RacePosition {
+                        race_id,
+                        wallet: sender,
+                        position,
+                    })
+                    .send_to(opponent_chain);
+
+                // Let spectators follow along without flooding them with every step
+                let updates = self.state.race_update_counts.get(&race_id).await?.unwrap_or(0) + 1;
+                self.state.race_update_counts.insert(&race_id, updates)?;
+                if updates % SNAPSHOT_INTERVAL == 0 {
+                    let snapshot = self.race_snapshot(race_id, &race).await?;
+                    self.publish_snapshot(snapshot);
+                }
+
+                Ok(())
+            }
+            Operation::SpectateRace { race_chain } => {
+                self.runtime
+                    .subscribe(race_chain, ChannelName::from(SPECTATOR_CHANNEL.to_vec()));
+                self.state.spectated_race_chains.insert(&race_chain)?;
+                Ok(())
+            }
+            Operation::StopSpectating { race_chain } => {
+                self.runtime
+                    .unsubscribe(race_chain, ChannelName::from(SPECTATOR_CHANNEL.to_vec()));
+                self.state.spectated_race_chains.remove(&race_chain)?;
+                Ok(())
+            }
+            Operation::FeatureReplay { wallet } => {
+                self.ensure_role(Role::Moderator).await?;
+                let key = validation::validate_wallet_address(&wallet)?;
+                let player = self
+                    .state
+                    .players
+                    .get(&key)
+                    .await?
+                    .ok_or(ContractError::ReplayNotFound)?;
+                let replay_data = player.replay_data.ok_or(ContractError::ReplayNotFound)?;
+
+                let featured = FeaturedReplay {
+                    score: player.high_score,
+                    featured_at: self.now(),
+                    replay_data,
+                    replay_compression: player.replay_compression,
+                };
+                self.state.featured_replays.insert(&key, featured)?;
+                Ok(())
+            }
+            Operation::UnfeatureReplay { wallet } => {
+                self.ensure_role(Role::Moderator).await?;
+                let key = validation::validate_wallet_address(&wallet)?;
+                self.state.featured_replays.remove(&key)?;
+                Ok(())
+            }
+            Operation::LikeReplay { wallet } => {
+                let liker = PlayerKey::from(self.signer()?);
+                let key = validation::validate_wallet_address(&wallet)?;
+                let (replay_score, mut stats) = self.current_replay_stats(&key).await?;
+
+                let like = (key, replay_score, liker);
+                if !self.state.replay_likes.contains(&like).await? {
+                    self.state.replay_likes.insert(&like)?;
+                    stats.likes += 1;
+                    self.state.replay_stats.insert(&key, stats)?;
+                }
+                Ok(())
+            }
+            Operation::RecordReplayView { wallet } => {
+                let viewer = PlayerKey::from(self.signer()?);
+                let key = validation::validate_wallet_address(&wallet)?;
+                let (replay_score, mut stats) = self.current_replay_stats(&key).await?;
+
+                let view = (key, replay_score, viewer);
+                if !self.state.replay_views.contains(&view).await? {
+                    self.state.replay_views.insert(&view)?;
+                    stats.views += 1;
+                    self.state.replay_stats.insert(&key, stats)?;
+                }
+                Ok(())
+            }
+            Operation::PruneReplays { batch_size } => {
+                self.ensure_role(Role::Moderator).await?;
+                self.prune_replays(batch_size.clamp(1, MAX_PRUNE_BATCH) as usize).await
+            }
+            Operation::SetMinClientVersion { version } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if let Some(version) = &version {
+                    validation::validate_client_version(version)?;
+                }
+                self.state.config.get_mut().min_client_version = version;
+                Ok(())
+            }
+            Operation::SetGameConfig { config } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if !config.is_valid() {
+                    return Err(ContractError::InvalidGameConfig);
+                }
+
+                // Keep the outgoing config so replays played under it stay verifiable
+                let version = *self.state.config_version.get();
+                let previous = self.state.game_config.get().clone();
+                self.state.game_config_history.insert(&version, previous)?;
+
+                self.state.config_version.set(version + 1);
+                self.state.game_config.set(config);
+                Ok(())
+            }
+            Operation::AuthorizeChain { chain_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.authorized_chains.insert(&chain_id)?;
+                Ok(())
+            }
+            Operation::RevokeChain { chain_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.authorized_chains.remove(&chain_id)?;
+                Ok(())
+            }
+            Operation::SetHubChain { chain_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().hub_chain = chain_id;
+                Ok(())
+            }
+            Operation::SetHubShards {
+                shards,
+                coordinator,
+                epoch,
+            } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let config = self.state.config.get_mut();
+                if epoch <= config.shard_epoch {
+                    return Err(ContractError::StaleShardEpoch);
+                }
+                config.hub_shards = shards;
+                config.shard_coordinator = coordinator;
+                config.shard_epoch = epoch;
+                // Top lists reported under the old assignment are stale
+                self.state.shard_tops.clear();
+                // A pass started under the old assignment starts over
+                self.state.rebalance_cursor.set(None);
+                Ok(())
+            }
+            Operation::RebalanceShard { batch_size } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.rebalance_shard(batch_size.clamp(1, MAX_REBALANCE_BATCH) as usize).await
+            }
+            Operation::SetMirrorMode { hub } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let channel = ChannelName::from(MIRROR_CHANNEL.to_vec());
+                if let Some(previous) = self.state.config.get().mirror_of {
+                    self.runtime.unsubscribe(previous, channel.clone());
+                }
+                if let Some(hub) = hub {
+                    self.runtime.subscribe(hub, channel);
+                }
+                self.state.config.get_mut().mirror_of = hub;
+                Ok(())
+            }
+            Operation::ResyncMirrors => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                for wallet in self.state.leaderboard_index.indices().await? {
+                    self.publish_leaderboard_delta(wallet).await?;
+                }
+                Ok(())
+            }
+            Operation::SetNotificationHub { application_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().notification_hub = application_id;
+                Ok(())
+            }
+            Operation::SetWelcomeBonus { faucet, amount } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let config = self.state.config.get_mut();
+                config.welcome_faucet = faucet;
+                config.welcome_bonus = amount;
+                Ok(())
+            }
+            Operation::MigrateAccount { target_chain } => {
+                let sender = PlayerKey::from(self.signer()?);
+                self.migrate_account(sender, target_chain).await
+            }
+            Operation::RequestLink { owner } => {
+                let signer = PlayerKey::from(self.signer()?);
+                let primary = self.player_account(signer).await?;
+                let secondary = PlayerKey::from(owner);
+                if secondary == primary {
+                    return Err(ContractError::CannotLinkSelf);
+                }
+                self.state.pending_links.insert(&secondary, primary)?;
+                Ok(())
+            }
+            Operation::AcceptLink { primary } => {
+                let secondary = PlayerKey::from(self.signer()?);
+                let primary = PlayerKey::from(primary);
+                if self.state.pending_links.get(&secondary).await? != Some(primary) {
+                    return Err(ContractError::NoPendingLink);
+                }
+
+                // An owner with its own history would silently lose it
+                if self.state.players.contains_key(&secondary).await?
+                    || self.state.linked_owners.contains_key(&secondary).await?
+                {
+                    return Err(ContractError::AlreadyLinked);
+                }
+
+                self.state.pending_links.remove(&secondary)?;
+                self.state.linked_owners.insert(&secondary, primary)?;
+                Ok(())
+            }
+            Operation::AuthorizeSessionKey {
+                session_key,
+                expires_at,
+            } => {
+                let owner = PlayerKey::from(self.signer()?);
+                let session_key = PlayerKey::from(session_key);
+                let now = self.now();
+                if session_key == owner
+                    || expires_at <= now
+                    || expires_at > now.saturating_add(MAX_SESSION_KEY_LIFETIME)
+                {
+                    return Err(ContractError::InvalidSessionKey);
+                }
+                // A session key must not be able to act for a second wallet
+                if let Some(existing) = self.state.session_keys.get(&session_key).await? {
+                    if existing.owner != owner {
+                        return Err(ContractError::InvalidSessionKey);
+                    }
+                }
+
+                self.state
+                    .session_keys
+                    .insert(&session_key, SessionKey { owner, expires_at })?;
+                Ok(())
+            }
+            Operation::RevokeSessionKey { session_key } => {
+                let owner = PlayerKey::from(self.signer()?);
+                let session_key = PlayerKey::from(session_key);
+                match self.state.session_keys.get(&session_key).await? {
+                    Some(session) if session.owner == owner => {
+                        self.state.session_keys.remove(&session_key)?;
+                        Ok(())
+                    }
+                    _ => Err(ContractError::Unauthorized),
+                }
+            }
+            Operation::GrantRole { wallet, role } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.roles.insert(&PlayerKey::from(wallet), role)?;
+                Ok(())
+            }
+            Operation::RevokeRole { wallet } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.roles.remove(&PlayerKey::from(wallet))?;
+                Ok(())
+            }
+            Operation::ProposeAdminTransfer { new_admin } => {
+                let owner = PlayerKey::from(self.signer()?);
+                if *self.state.admin.get() != Some(owner) {
+                    return Err(ContractError::AdminOnly);
+                }
+                self.state.pending_admin.set(Some(PlayerKey::from(new_admin)));
+                Ok(())
+            }
+            Operation::AcceptAdminTransfer => {
+                let owner = PlayerKey::from(self.signer()?);
+                if *self.state.pending_admin.get() != Some(owner) {
+                    return Err(ContractError::NoPendingAdminTransfer);
+                }
+                self.state.pending_admin.set(None);
+                self.state.admin.set(Some(owner));
+                Ok(())
+            }
+            Operation::UpdateConfig { field, value } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if !field.bounds().contains(&value) {
+                    return Err(ContractError::ConfigValueOutOfBounds);
+                }
+
+                let config = self.state.config.get_mut();
+                match field {
+                    ConfigField::MaxReplaySize => config.max_replay_size = value,
+                    ConfigField::ReplayStorageQuota => config.replay_storage_quota = value,
+                    ConfigField::OpMetricsSampleInterval => {
+                        config.op_metrics_sample_interval = value
+                    }
+                    ConfigField::MaxSubmissionsPerHour => {
+                        config.max_submissions_per_hour = value as u32
+                    }
+                    ConfigField::LeaderboardCap => config.leaderboard_cap = value as u32,
+                    ConfigField::SeasonLength => config.season_length = value,
+                    ConfigField::LeaderboardMinGames => config.leaderboard_min_games = value as u32,
+                    ConfigField::LeaderboardMinAccountAge => {
+                        config.leaderboard_min_account_age = value
+                    }
+                    // Indexed scores follow on their next update or with RebuildIndexes
+                    ConfigField::LeaderboardRequireVerified => {
+                        config.leaderboard_require_verified = value != 0
+                    }
+                }
+                if field == ConfigField::LeaderboardCap {
+                    self.evict_beyond_leaderboard_cap().await?;
+                }
+                Ok(())
+            }
+            Operation::RebuildLeaderboard => {
+                self.ensure_role(Role::Moderator).await?;
+                // The roster cursor keeps each batch to its own players
+                self.rebuild_indexes(MAX_REBUILD_BATCH as usize).await
+            }
+            Operation::RebuildIndexes { batch_size } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.rebuild_indexes(batch_size.clamp(1, MAX_REBUILD_BATCH) as usize).await
+            }
+            Operation::ArchiveInactivePlayers { batch_size } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let batch_size = batch_size.clamp(1, MAX_ARCHIVE_BATCH) as usize;
+                self.archive_inactive_players(batch_size).await
+            }
+            Operation::AddQuest {
+                title,
+                goal,
+                target,
+                reward,
+            } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if title.trim().is_empty() || target == 0 {
+                    return Err(ContractError::InvalidQuest);
+                }
+
+                let id = *self.state.next_quest_id.get();
+                self.state.next_quest_id.set(id + 1);
+                self.state.quests.insert(
+                    &id,
+                    Quest {
+                        id,
+                        title,
+                        goal,
+                        target,
+                        reward,
+                    },
+                )?;
+                Ok(())
+            }
+            Operation::RemoveQuest { quest_id } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if !self.state.quests.contains_key(&quest_id).await? {
+                    return Err(ContractError::QuestNotFound);
+                }
+                self.state.quests.remove(&quest_id)?;
+                Ok(())
+            }
+            Operation::ClaimQuestReward { quest_id } => {
+                let owner = self.signer()?;
+                let player = self.player_account(PlayerKey::from(owner)).await?;
+                let quest = self
+                    .state
+                    .quests
+                    .get(&quest_id)
+                    .await?
+                    .ok_or(ContractError::QuestNotFound)?;
+
+                // Progress from an earlier day doesn't count
+                let key = (player, quest_id);
+                let today = self.now() / SECONDS_PER_DAY;
+                let mut progress = self
+                    .state
+                    .quest_progress
+                    .get(&key)
+                    .await?
+                    .filter(|progress| progress.day == today)
+                    .ok_or(ContractError::QuestNotCompleted)?;
+                if progress.claimed {
+                    return Err(ContractError::QuestAlreadyClaimed);
+                }
+                if progress.progress < quest.target {
+                    return Err(ContractError::QuestNotCompleted);
+                }
+
+                let mut data = self
+                    .state
+                    .players
+                    .get(&player)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                data.coins = data.coins.saturating_add(quest.reward);
+                self.state.players.insert(&player, data)?;
+
+                progress.claimed = true;
+                self.state.quest_progress.insert(&key, progress)?;
+                Ok(())
+            }
+            Operation::ScheduleModifier { week, modifier } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                match modifier {
+                    Some(modifier) => self.state.modifier_schedule.insert(&week, modifier)?,
+                    None => self.state.modifier_schedule.remove(&week)?,
+                }
+                Ok(())
+            }
+            Operation::BuyLootBox { commitment } => {
+                let owner = self.signer()?;
+                let player = PlayerKey::from(owner);
+                let commitment = commitment.to_ascii_lowercase();
+                if commitment.len() != 64 || !commitment.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(ContractError::InvalidCommitment);
+                }
+                if self.state.loot_box_commits.contains_key(&player).await? {
+                    return Err(ContractError::LootBoxPending);
+                }
+
+                let price = self.state.config.get().loot_box_price;
+                let application_account = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: None,
+                };
+                self.runtime.transfer(Some(owner), application_account, price);
+                let pool = self.state.prize_pool.get().saturating_add(price);
+                self.state.prize_pool.set(pool);
+
+                // The seed is drawn by the hub once the commitment is made, so
+                // neither the secret nor the seed can be chosen knowing the other
+                let committed_at = self.runtime.block_height().0;
+                let hub = self.state.config.get().hub_for(&player);
+                let seed = match hub {
+                    Some(hub) if hub != self.runtime.chain_id() => {
+                        self.runtime
+                            .prepare_message(Message::DrawLootSeed {
+                                wallet: player,
+                                committed_at,
+                            })
+                            .with_tracking()
+                            .send_to(hub);
+                        None
+                    }
+                    _ => Some(self.draw_loot_seed(player, committed_at)),
+                };
+                self.state.loot_box_commits.insert(
+                    &player,
+                    LootBoxCommit {
+                        commitment,
+                        committed_at,
+                        price,
+                        seed,
+                    },
+                )?;
+                Ok(())
+            }
+            Operation::OpenLootBox { secret } => {
+                let player = PlayerKey::from(self.signer()?);
+                let commit = self
+                    .state
+                    .loot_box_commits
+                    .get(&player)
+                    .await?
+                    .ok_or(ContractError::NoLootBoxPending)?;
+                let seed = commit.seed.ok_or(ContractError::LootSeedPending)?;
+                if hex_digest(&[secret.as_bytes()]) != commit.commitment {
+                    return Err(ContractError::CommitmentMismatch);
+                }
+
+                let item = loot_drop(&secret, &seed).to_string();
+                let items = self.state.inventory.load_entry_mut(&player).await?;
+                let count = items.get(&item).await?.unwrap_or(0);
+                items.insert(&item, count + 1)?;
+                self.state.loot_box_commits.remove(&player)?;
+                Ok(())
+            }
+            Operation::GiftCoins { to, amount } => {
+                let signer = PlayerKey::from(self.signer()?);
+                let from = self.player_account(signer).await?;
+                let to = self.player_account(PlayerKey::from(to)).await?;
+                if from == to {
+                    return Err(ContractError::CannotGiftSelf);
+                }
+
+                let mut sender = self
+                    .state
+                    .players
+                    .get(&from)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                let mut recipient = self
+                    .state
+                    .players
+                    .get(&to)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                if sender.coins < amount {
+                    return Err(ContractError::InsufficientCoins);
+                }
+
+                // Fresh sybil accounts can't funnel farmed coins into a main account
+                let now = self.now();
+                let config = self.state.config.get();
+                let (min_age, daily_cap) = (config.min_gift_account_age, config.max_gifts_per_day);
+                if sender.account_age(now) < min_age {
+                    return Err(ContractError::AccountTooNew);
+                }
+                let today = now / SECONDS_PER_DAY;
+                if sender.gift_day != today {
+                    sender.gift_day = today;
+                    sender.gifted_on_gift_day = 0;
+                }
+                if sender.gifted_on_gift_day.saturating_add(amount) > daily_cap {
+                    return Err(ContractError::GiftLimitExceeded);
+                }
+
+                sender.coins -= amount;
+                sender.gifted_on_gift_day += amount;
+                recipient.coins = recipient.coins.saturating_add(amount);
+                self.state.players.insert(&from, sender)?;
+                self.state.players.insert(&to, recipient)?;
+
+                let gift = GiftRecord {
+                    id: self.state.gift_log.count() as u64,
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    amount,
+                    sent_at: now,
+                };
+                self.state.gift_log.push(gift);
+                Ok(())
+            }
+            Operation::TipPlayer { to, amount } => {
+                let owner = self.signer()?;
+                let from = self.player_account(PlayerKey::from(owner)).await?;
+                let recipient = self.player_account(PlayerKey::from(to)).await?;
+                if from == recipient {
+                    return Err(ContractError::CannotTipSelf);
+                }
+                if amount == Amount::ZERO {
+                    return Err(ContractError::InvalidTipAmount);
+                }
+
+                let destination = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: Some(to),
+                };
+                self.runtime.transfer(Some(owner), destination, amount);
+
+                let tip = Tip {
+                    from: from.to_string(),
+                    amount,
+                    sent_at: self.now(),
+                };
+                self.state.tips.load_entry_mut(&recipient).await?.push(tip);
+                Ok(())
+            }
+            Operation::SetLeaderboardEntryFee { fee } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().leaderboard_entry_fee = fee;
+                Ok(())
+            }
+            Operation::SetSubmissionFee { fee } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().submission_fee = fee;
+                Ok(())
+            }
+            Operation::PayLeaderboardEntryFee => {
+                let owner = self.signer()?;
+                let wallet = self.player_account(PlayerKey::from(owner)).await?;
+                let fee = self.state.config.get().leaderboard_entry_fee;
+                if fee == Amount::ZERO {
+                    return Err(ContractError::NoEntryFee);
+                }
+                let existing = self.state.players.get(&wallet).await?;
+                let is_new_player = existing.is_none();
+                let mut player = existing.unwrap_or_default();
+                if player.entry_fee_paid {
+                    return Err(ContractError::EntryFeeAlreadyPaid);
+                }
+
+                // The fee stays with the application, in the prize pool
+                let application_account = Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: None,
+                };
+                self.runtime.transfer(Some(owner), application_account, fee);
+                let pool = self.state.prize_pool.get().saturating_add(fee);
+                self.state.prize_pool.set(pool);
+
+                player.entry_fee_paid = true;
+                if is_new_player {
+                    player.first_seen_at = self.now();
+                    self.state.global_stats.get_mut().total_players += 1;
+                    self.enroll_player(wallet).await?;
+                }
+                self.state.players.insert(&wallet, player)?;
+                Ok(())
+            }
+            Operation::ClaimVerification { id } => {
+                let verifier = self.ensure_role(Role::Verifier).await?.to_string();
+                let now = self.now();
+                let mut item = self
+                    .state
+                    .verification_queue
+                    .get(&id)
+                    .await?
+                    .ok_or(ContractError::VerificationNotFound)?;
+                let claim_expired = item.status == VerificationStatus::Claimed
+                    && now >= item.claimed_at + VERIFICATION_CLAIM_TIMEOUT;
+                if item.status != VerificationStatus::Pending && !claim_expired {
+                    return Err(ContractError::VerificationUnavailable);
+                }
+                if item.wallet == verifier {
+                    return Err(ContractError::CannotVerifyOwnScore);
+                }
+                if item.verdicts.iter().any(|verdict| verdict.verifier == verifier) {
+                    return Err(ContractError::AlreadyVerified);
+                }
+
+                item.status = VerificationStatus::Claimed;
+                item.claimed_by = Some(verifier);
+                item.claimed_at = now;
+                self.state.verification_queue.insert(&id, item)?;
+                Ok(())
+            }
+            Operation::SubmitVerdict {
+                id,
+                valid,
+                result_hash,
+            } => {
+                let verifier = self.ensure_role(Role::Verifier).await?.to_string();
+                let result_hash = result_hash.to_ascii_lowercase();
+                if result_hash.len() != 64 || !result_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(ContractError::InvalidResultHash);
+                }
+                let mut item = self
+                    .state
+                    .verification_queue
+                    .get(&id)
+                    .await?
+                    .ok_or(ContractError::VerificationNotFound)?;
+                if item.status != VerificationStatus::Claimed
+                    || item.claimed_by.as_ref() != Some(&verifier)
+                {
+                    return Err(ContractError::NotVerificationClaimer);
+                }
+
+                // Honest verifiers re-simulating the same replay get the same result
+                let (first_valid, first_hash) = match item.verdicts.first() {
+                    Some(first) => (first.valid, first.result_hash.clone()),
+                    None => (valid, result_hash.clone()),
+                };
+                item.verdicts.push(VerificationVerdict {
+                    verifier,
+                    valid,
+                    result_hash,
+                    submitted_at: self.now(),
+                });
+                item.claimed_by = None;
+
+                let conflicting = item.verdicts.iter().any(|verdict| {
+                    verdict.valid != first_valid || verdict.result_hash != first_hash
+                });
+                let required = self.state.config.get().verifications_required as usize;
+                item.status = if conflicting {
+                    VerificationStatus::Escalated
+                } else if item.verdicts.len() < required {
+                    VerificationStatus::Pending
+                } else if first_valid {
+                    VerificationStatus::Verified
+                } else {
+                    VerificationStatus::Rejected
+                };
+                self.settle_verification(&item).await?;
+                self.state.verification_queue.insert(&id, item)?;
+                Ok(())
+            }
+            Operation::ResolveVerification { id, valid } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let mut item = self
+                    .state
+                    .verification_queue
+                    .get(&id)
+                    .await?
+                    .ok_or(ContractError::VerificationNotFound)?;
+                if item.status != VerificationStatus::Escalated {
+                    return Err(ContractError::VerificationNotEscalated);
+                }
+
+                item.status = if valid {
+                    VerificationStatus::Verified
+                } else {
+                    VerificationStatus::Rejected
+                };
+                self.settle_verification(&item).await?;
+                self.state.verification_queue.insert(&id, item)?;
+                Ok(())
+            }
+            Operation::AckNotifications { up_to } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let inbox = self.state.notifications.load_entry_mut(&account).await?;
+                while let Some(notification) = inbox.front().await? {
+                    if notification.id > up_to {
+                        break;
+                    }
+                    inbox.delete_front();
+                }
+                Ok(())
+            }
+            Operation::ReclaimCoinHold { application_id } => {
+                let owner = self.signer()?;
+                let wallet = self.player_account(PlayerKey::from(owner)).await?;
+                let key = (application_id, wallet);
+                let hold = self
+                    .state
+                    .partner_coin_holds
+                    .get(&key)
+                    .await?
+                    .ok_or(ContractError::CoinHoldNotFound)?;
+                if hold.expires_at > self.now() {
+                    return Err(ContractError::CoinHoldNotExpired);
+                }
+                self.state.partner_coin_holds.remove(&key)?;
+                self.credit_coins(wallet, hold.amount).await?;
+                Ok(())
+            }
+            Operation::SetProvisionalWindow { blocks } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                self.state.config.get_mut().provisional_window = blocks;
+                Ok(())
+            }
+            Operation::SetSeasonDecay { decay } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                if decay.is_some_and(|decay| !decay.is_valid()) {
+                    return Err(ContractError::InvalidSeasonDecay);
+                }
+                self.state.config.get_mut().season_decay = decay;
+                Ok(())
+            }
+            Operation::ExpireProvisionalScores => {
+                self.expire_provisional_scores().await?;
+                Ok(())
+            }
+            Operation::InvalidateScore { wallet, reason } => {
+                self.ensure_role(Role::Verifier).await?;
+                let wallet = validation::validate_wallet_address(&wallet)?;
+                let reason = reason.trim().to_string();
+                if reason.is_empty() || reason.chars().count() > MAX_INVALIDATION_REASON_LEN {
+                    return Err(ContractError::InvalidReason);
+                }
+                self.invalidate_score(wallet, reason).await
+            }
+            Operation::AppealInvalidation {
+                score_id,
+                statement,
+            } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let invalidation = self
+                    .state
+                    .score_invalidations
+                    .get(score_id as usize)
+                    .await?
+                    .ok_or(ContractError::InvalidationNotFound)?;
+                if invalidation.wallet_address != account {
+                    return Err(ContractError::NotInvalidatedPlayer);
+                }
+                if self.state.appeals.contains_key(&score_id).await? {
+                    return Err(ContractError::AlreadyAppealed);
+                }
+                let statement = statement.trim().to_string();
+                if statement.is_empty() || statement.chars().count() > MAX_APPEAL_STATEMENT_LEN {
+                    return Err(ContractError::InvalidStatement);
+                }
+
+                let appeal = Appeal {
+                    score_id,
+                    wallet_address: account,
+                    statement,
+                    status: AppealStatus::Pending,
+                    filed_at: self.now(),
+                    resolved_by: None,
+                    resolution_note: None,
+                    resolved_at: None,
+                };
+                self.state.appeals.insert(&score_id, appeal)?;
+                self.state.appeal_log.push(score_id);
+                Ok(())
+            }
+            Operation::ResolveAppeal {
+                score_id,
+                overturn,
+                note,
+            } => {
+                let admin = self.ensure_role(Role::SuperAdmin).await?;
+                let mut appeal = self
+                    .state
+                    .appeals
+                    .get(&score_id)
+                    .await?
+                    .ok_or(ContractError::AppealNotFound)?;
+                if appeal.status != AppealStatus::Pending {
+                    return Err(ContractError::AppealAlreadyResolved);
+                }
+
+                let snapshot = self.state.invalidated_high_scores.get(&score_id).await?;
+                self.state.invalidated_high_scores.remove(&score_id)?;
+                if let (true, Some(snapshot)) = (overturn, snapshot) {
+                    self.restore_invalidated_score(appeal.wallet_address, snapshot).await?;
+                }
+
+                appeal.status = if overturn {
+                    AppealStatus::Overturned
+                } else {
+                    AppealStatus::Upheld
+                };
+                appeal.resolved_by = Some(admin.to_string());
+                appeal.resolution_note = note;
+                appeal.resolved_at = Some(self.now());
+                self.state.appeals.insert(&score_id, appeal)?;
+                self.skip_resolved_appeals().await
+            }
+            Operation::CreateEvent {
+                tag,
+                name,
+                kind,
+                starts_at,
+                ends_at,
+            } => {
+                let admin = self.ensure_role(Role::SuperAdmin).await?;
+                let valid_tag = (1..=MAX_EVENT_TAG_LEN).contains(&tag.len())
+                    && tag.bytes().all(|byte| {
+                        byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-'
+                    });
+                if !valid_tag {
+                    return Err(ContractError::InvalidEventTag);
+                }
+                let name = name.trim().to_string();
+                if name.is_empty() || name.chars().count() > MAX_EVENT_NAME_LEN {
+                    return Err(ContractError::InvalidEventName);
+                }
+                if ends_at <= starts_at {
+                    return Err(ContractError::InvalidEventWindow);
+                }
+                if self.state.events.contains_key(&tag).await? {
+                    return Err(ContractError::EventExists);
+                }
+
+                let event = CommunityEvent {
+                    tag: tag.clone(),
+                    name,
+                    kind,
+                    starts_at,
+                    ends_at,
+                    created_by: admin.to_string(),
+                    frozen_at: None,
+                };
+                self.state.events.insert(&tag, event)?;
+                // Results freeze on their own once the window closes
+                let kind = ScheduledActionKind::EndEvent;
+                self.schedule_action(kind, Some(tag), None, ends_at, admin.to_string()).await
+            }
+            Operation::RegisterForEvent { tag, country, city } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let (country, city) = validation::validate_region(&country, city.as_deref())?;
+                let event = self
+                    .state
+                    .events
+                    .get(&tag)
+                    .await?
+                    .ok_or(ContractError::EventNotFound)?;
+                if event.kind != EventKind::Regional {
+                    return Err(ContractError::WrongEventKind);
+                }
+                let now = self.now();
+                if event.frozen_at.is_some() || now >= event.ends_at {
+                    return Err(ContractError::EventClosed);
+                }
+                let entries = self.state.event_entries.load_entry_mut(&tag).await?;
+                if entries.contains_key(&account).await? {
+                    return Err(ContractError::AlreadyRegistered);
+                }
+
+                let entry = EventEntry {
+                    country,
+                    city,
+                    best_score: 0,
+                    runs: 0,
+                    registered_at: now,
+                };
+                entries.insert(&account, entry)?;
+                self.add_event_of_wallet(account, tag).await
+            }
+            Operation::EndEvent { tag } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let event = self
+                    .state
+                    .events
+                    .get(&tag)
+                    .await?
+                    .ok_or(ContractError::EventNotFound)?;
+                if event.frozen_at.is_some() {
+                    return Err(ContractError::EventAlreadyEnded);
+                }
+                if self.now() < event.ends_at {
+                    return Err(ContractError::EventStillRunning);
+                }
+                self.end_event(event).await
+            }
+            Operation::CreateTeam { tag, name } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let name = name.trim().to_string();
+                if name.is_empty() || name.chars().count() > MAX_TEAM_NAME_LEN {
+                    return Err(ContractError::InvalidTeamName);
+                }
+                self.ensure_team_roster_open(&tag).await?;
+                let memberships = self.state.team_memberships.load_entry_mut(&tag).await?;
+                if memberships.contains_key(&account).await? {
+                    return Err(ContractError::AlreadyInTeam);
+                }
+
+                let mut team_ids = self
+                    .state
+                    .teams_by_event
+                    .get(&tag)
+                    .await?
+                    .unwrap_or_default();
+                if team_ids.len() >= MAX_TEAMS_PER_EVENT {
+                    return Err(ContractError::TooManyTeams);
+                }
+
+                let team_id = *self.state.next_team_id.get();
+                self.state.next_team_id.set(team_id + 1);
+                let team = EventTeam {
+                    team_id,
+                    tag: tag.clone(),
+                    name,
+                    captain: account,
+                    members: Vec::new(),
+                    invited: Vec::new(),
+                    created_at: self.now(),
+                };
+                team_ids.push(team_id);
+                self.state.teams_by_event.insert(&tag, team_ids)?;
+                self.join_team(team, account).await
+            }
+            Operation::InviteToTeam { team_id, wallet } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let invitee = validation::validate_wallet_address(&wallet)?;
+                let mut team = self
+                    .state
+                    .event_teams
+                    .get(&team_id)
+                    .await?
+                    .ok_or(ContractError::TeamNotFound)?;
+                if team.captain != account {
+                    return Err(ContractError::NotTeamCaptain);
+                }
+                self.ensure_team_roster_open(&team.tag).await?;
+                if team.is_full() {
+                    return Err(ContractError::TeamFull);
+                }
+                let memberships = self.state.team_memberships.load_entry_mut(&team.tag).await?;
+                if memberships.contains_key(&invitee).await? {
+                    return Err(ContractError::AlreadyInTeam);
+                }
+                // Inviting a player twice changes nothing
+                if team.invited.contains(&invitee) {
+                    Ok(())
+                } else if team.invited.len() >= MAX_TEAM_INVITES {
+                    Err(ContractError::TooManyTeamInvites)
+                } else {
+                    team.invited.push(invitee);
+                    self.state.event_teams.insert(&team_id, team)?;
+                    let kind = NotificationKind::TeamInvite;
+                    self.notify(&invitee, kind, Some(account), Some(team_id)).await
+                }
+            }
+            Operation::AcceptTeamInvite { team_id } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let mut team = self
+                    .state
+                    .event_teams
+                    .get(&team_id)
+                    .await?
+                    .ok_or(ContractError::TeamNotFound)?;
+                if !team.invited.contains(&account) {
+                    return Err(ContractError::NotInvited);
+                }
+                self.ensure_team_roster_open(&team.tag).await?;
+                if team.is_full() {
+                    return Err(ContractError::TeamFull);
+                }
+                let memberships = self.state.team_memberships.load_entry_mut(&team.tag).await?;
+                if memberships.contains_key(&account).await? {
+                    return Err(ContractError::AlreadyInTeam);
+                }
+
+                team.invited.retain(|invited| *invited != account);
+                self.join_team(team, account).await
+            }
+            Operation::AddFriend { wallet } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let friend = validation::validate_wallet_address(&wallet)?;
+                let friend = self.player_account(friend).await?;
+                if friend == account {
+                    return Err(ContractError::CannotFriendSelf);
+                }
+                let mut friends = self.state.friends.get(&account).await?.unwrap_or_default();
+                // Adding a friend twice changes nothing
+                if friends.contains(&friend) {
+                    Ok(())
+                } else if friends.len() >= MAX_FRIENDS {
+                    Err(ContractError::TooManyFriends)
+                } else {
+                    friends.push(friend);
+                    self.state.friends.insert(&account, friends)?;
+                    self.complete_onboarding_step(account, OnboardingStep::FriendAdded).await
+                }
+            }
+            Operation::RemoveFriend { wallet } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let friend = validation::validate_wallet_address(&wallet)?;
+                let friend = self.player_account(friend).await?;
+                let mut friends = self.state.friends.get(&account).await?.unwrap_or_default();
+                friends.retain(|wallet| *wallet != friend);
+                if friends.is_empty() {
+                    self.state.friends.remove(&account)?;
+                } else {
+                    self.state.friends.insert(&account, friends)?;
+                }
+                Ok(())
+            }
+            Operation::Partner { request } => {
+                let caller = self
+                    .runtime
+                    .authenticated_caller_id()
+                    .ok_or(ContractError::NotAnApplication)?;
+                if let Some(capability) = request.capability() {
+                    let allowed = self
+                        .state
+                        .partner_capabilities
+                        .get(&caller)
+                        .await?
+                        .unwrap_or_default();
+                    if !allowed.contains(&capability) {
+                        return Err(ContractError::CallerNotAllowed(capability));
+                    }
+                }
+                let answer = self.handle_partner_request(caller, request).await?;
+                response = OperationResponse::Partner(answer);
+                Ok(())
+            }
+            Operation::SetPartnerCapabilities {
+                application_id,
+                mut capabilities,
+            } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                capabilities.sort();
+                capabilities.dedup();
+                if capabilities.is_empty() {
+                    self.state.partner_capabilities.remove(&application_id)?;
+                } else {
+                    self.state.partner_capabilities.insert(&application_id, capabilities)?;
+                }
+                Ok(())
+            }
+            Operation::RequestScoreAttestation {
+                target_chain,
+                application,
+            } => {
+                let owner = self.signer()?;
+                let wallet = self.player_account(PlayerKey::from(owner)).await?;
+                let chain_id = self.runtime.chain_id();
+                if self.state.config.get().hub_for(&wallet).is_some_and(|hub| hub != chain_id) {
+                    return Err(ContractError::AttestationOffHub);
+                }
+                let high_score = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?
+                    .high_score;
+                let season = *self.state.current_season.get();
+                let issued_at = self.now();
+                let digest = oracle_digest(chain_id, &wallet, high_score, season, issued_at);
+                self.runtime
+                    .prepare_message(Message::ScoreAttestation {
+                        wallet,
+                        high_score,
+                        season,
+                        issued_at,
+                        digest,
+                        application,
+                    })
+                    .with_authentication()
+                    .send_to(target_chain);
+                Ok(())
+            }
+            Operation::SetVerified { wallet, verified } => {
+                self.ensure_role(Role::SuperAdmin).await?;
+                let wallet = validation::validate_wallet_address(&wallet)?;
+                let wallet = self.player_account(wallet).await?;
+                let mut player = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                if let (true, Some(name)) = (verified, &player.display_name) {
+                    self.check_impersonation(wallet, name).await?;
+                }
+                self.release_verified_name(wallet, player.display_name.as_deref()).await?;
+                player.verified = verified;
+                self.claim_verified_name(wallet, &player)?;
+                self.state.players.insert(&wallet, player)?;
+                self.reindex_player(wallet).await
+            }
+            Operation::SetBanned { wallet, banned } => {
+                self.ensure_role(Role::Moderator).await?;
+                let wallet = validation::validate_wallet_address(&wallet)?;
+                let wallet = self.player_account(wallet).await?;
+                let mut player = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                player.banned = banned;
+                self.state.players.insert(&wallet, player)?;
+                self.reindex_player(wallet).await
+            }
+            Operation::SetLeaderboardHidden { hidden } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let mut player = self
+                    .state
+                    .players
+                    .get(&account)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                player.hidden = hidden;
+                self.state.players.insert(&account, player)?;
+                self.reindex_player(account).await
+            }
+            Operation::UpdateNameBlocklist { add, remove } => {
+                self.ensure_role(Role::Moderator).await?;
+                let blocklist = self.state.name_blocklist.get_mut();
+                let remove = remove
+                    .iter()
+                    .map(|term| validation::name_skeleton(term))
+                    .collect::<Vec<_>>();
+                blocklist.terms.retain(|term| !remove.contains(term));
+                for term in &add {
+                    let term = validation::name_skeleton(term);
+                    if !term.is_empty() && !blocklist.terms.contains(&term) {
+                        blocklist.terms.push(term);
+                    }
+                }
+                if blocklist.terms.len() > MAX_BLOCKLIST_TERMS {
+                    return Err(ContractError::TooManyBlocklistTerms);
+                }
+                blocklist.terms.sort();
+                blocklist.version += 1;
+                Ok(())
+            }
+            Operation::ResolveNameReview { wallet, clear_name } => {
+                self.ensure_role(Role::Moderator).await?;
+                let wallet = validation::validate_wallet_address(&wallet)?;
+                let wallet = self.player_account(wallet).await?;
+                if !self.state.name_reviews.contains_key(&wallet).await? {
+                    return Err(ContractError::NameReviewNotFound);
+                }
+                self.state.name_reviews.remove(&wallet)?;
+                if clear_name {
+                    if let Some(mut player) = self.state.players.get(&wallet).await? {
+                        self.release_verified_name(wallet, player.display_name.as_deref())
+                            .await?;
+                        player.display_name = None;
+                        self.state.players.insert(&wallet, player)?;
+                    }
+                }
+                Ok(())
+            }
+            Operation::SetNameScripts { country, scripts } => {
+                self.ensure_role(Role::Moderator).await?;
+                let mut scripts = scripts;
+                scripts.sort();
+                scripts.dedup();
+                let policy = self.state.name_script_policy.get_mut();
+                match country {
+                    None => policy.scripts = scripts,
+                    Some(country) => {
+                        let (country, _) = validation::validate_region(&country, None)?;
+                        policy.region_overrides.retain(|region| region.country != country);
+                        if !scripts.is_empty() {
+                            policy.region_overrides.push(RegionScripts { country, scripts });
+                            policy.region_overrides.sort_by(|a, b| a.country.cmp(&b.country));
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Operation::SetCountry { country } => {
+                let owner = self.signer()?;
+                let account = self.player_account(PlayerKey::from(owner)).await?;
+                let country = match country {
+                    Some(country) => Some(validation::validate_region(&country, None)?.0),
+                    None => None,
+                };
+                let mut player = self
+                    .state
+                    .players
+                    .get(&account)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                // Only names set from now on follow the new region's rules
+                player.country = country;
+                self.state.players.insert(&account, player)?;
+                Ok(())
+            }
+        };
+        outcome?;
+
+        self.record_op_metrics(name, replay_bytes).await?;
+        Ok(response)
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) -> Result<(), Self::Error> {
+        let is_mirror = self.state.config.get().mirror_of.is_some();
+        if is_mirror && !matches!(message, Message::LeaderboardDelta { .. }) {
+            return Err(ContractError::ReadOnlyMirror);
+        }
+        self.migrate_state().await?;
+
+        match message {
+            Message::SaveScore {
+                score,
+                replay_data,
+                compression,
+                timestamp,
+                client_version,
+                submission_id,
+                run_stats,
+                proof,
+                sequence,
+                shard_epoch,
+            } => {
+                let submission = ScoreSubmission {
+                    score,
+                    replay_data,
+                    compression,
+                    timestamp,
+                    client_version,
+                    submission_id,
+                    run_stats,
+                    proof,
+                };
+
+                // A score this chain forwarded came back: keep it for the next retry
+                if self.runtime.message_is_bounced() == Some(true) {
+                    let sender = PlayerKey::from(self.signer()?);
+                    self.record_bounced_score(sender, submission, sequence).await?;
+                    return Ok(());
+                }
+
+                let sender = self.message_sender().await?;
+                self.check_shard(&sender, shard_epoch)?;
+
+                // Each chain numbers the scores it forwards, so order is kept per origin
+                // chain. A replayed message changes nothing.
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let key = (origin, sender);
+                let last = self.state.accepted_sequences.get(&key).await?.unwrap_or(0);
+                if sequence <= last {
+                    return Ok(());
+                }
+                if sequence != last + 1 {
+                    return Err(ContractError::OutOfOrderMessage);
+                }
+
+                // A score rejected for good still takes its sequence, so the scores
+                // after it aren't stuck; the origin chain is told instead of resending
+                let submission_id = submission.submission_id.clone();
+                match self.handle_save_score(sender, submission, true).await {
+                    Ok(_) => {}
+                    Err(error) if error.is_transient() => return Err(error),
+                    Err(error) => {
+                        self.runtime
+                            .prepare_message(Message::HubScoreRejected {
+                                wallet: sender,
+                                sequence,
+                                submission_id,
+                                reason: error.to_string(),
+                            })
+                            .send_to(origin);
+                    }
+                }
+                self.state.accepted_sequences.insert(&key, sequence)?;
+                Ok(())
+            }
+            Message::NotifyHub { event } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let is_local = origin == self.runtime.chain_id();
+                if !is_local && !self.state.authorized_chains.contains(&origin).await? {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                let Some(hub) = self.state.config.get().notification_hub else {
+                    return Ok(());
+                };
+                self.runtime.call_application(false, hub.with_abi::<NotificationHubAbi>(), &event);
+                Ok(())
+            }
+            Message::GrantWelcomeBonus { wallet, amount } => {
+                // The faucet refused: let the next registration try again
+                if self.runtime.message_is_bounced() == Some(true) {
+                    self.state.welcome_bonus_claimed.remove(&wallet)?;
+                    return Ok(());
+                }
+
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let is_local = origin == self.runtime.chain_id();
+                if !is_local && !self.state.authorized_chains.contains(&origin).await? {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                let faucet = self
+                    .state
+                    .config
+                    .get()
+                    .welcome_faucet
+                    .ok_or(ContractError::Unauthorized)?;
+                let request = FaucetRequest::Grant {
+                    owner: wallet.0,
+                    amount,
+                };
+                self.runtime.call_application(false, faucet.with_abi::<FaucetAbi>(), &request);
+                Ok(())
+            }
+            Message::DrawLootSeed {
+                wallet,
+                committed_at,
+            } => {
+                // The hub refused to draw: refund the box
+                if self.runtime.message_is_bounced() == Some(true) {
+                    return self.refund_loot_box(wallet, committed_at).await;
+                }
+
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if !self.state.authorized_chains.contains(&origin).await? {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                let seed = self.draw_loot_seed(wallet, committed_at);
+                self.runtime
+                    .prepare_message(Message::LootSeed {
+                        wallet,
+                        committed_at,
+                        seed,
+                    })
+                    .send_to(origin);
+                Ok(())
+            }
+            Message::LootSeed {
+                wallet,
+                committed_at,
+                seed,
+            } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if self.state.config.get().hub_for(&wallet) != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                // A seed for a box that was since opened or replaced is dropped
+                if let Some(mut commit) = self.state.loot_box_commits.get(&wallet).await? {
+                    if commit.committed_at == committed_at && commit.seed.is_none() {
+                        commit.seed = Some(seed);
+                        self.state.loot_box_commits.insert(&wallet, commit)?;
+                    }
+                }
+                Ok(())
+            }
+            Message::HubScoreRejected {
+                wallet,
+                sequence,
+                submission_id: _,
+                reason: _,
+            } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if self.state.config.get().hub_for(&wallet) != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                let kind = NotificationKind::ScoreRejected;
+                self.notify(&wallet, kind, None, Some(sequence)).await
+            }
+            Message::RegisterPlayer { display_name } => {
+                let sender = self.message_sender().await?;
+                self.handle_register_player(sender, display_name).await
+            }
+            Message::RaceMatched {
+                wallet,
+                race_id,
+                opponent,
+                seed,
+                race_chain,
+            } => {
+                // Pairings come from the wallet's hub, or from this chain
+                // when it paired the players itself
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let key = validation::validate_wallet_address(&wallet)?;
+                let hub = self.state.config.get().hub_for(&key);
+                if origin != self.runtime.chain_id() && hub != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                if let Some(race_chain) = race_chain {
+                    self.state.matched_race_chains.insert(&race_id, race_chain)?;
+                }
+                self.state.race_matches.insert(
+                    &wallet,
+                    RaceMatch {
+                        race_id,
+                        opponent,
+                        seed,
+                        race_chain,
+                    },
+                )?;
+                Ok(())
+            }
+            Message::RaceChainOpened {
+                race_id,
+                race,
+                info,
+            } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if origin != info.hub {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                self.state.races.insert(&race_id, race)?;
+                self.state.race_chain_info.insert(&race_id, info)?;
+                Ok(())
+            }
+            Message::RacePosition {
+                race_id,
+                wallet,
+                position,
+            } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let race_chain = self.state.matched_race_chains.get(&race_id).await?;
+                if race_chain != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                self.state.race_positions.insert(&(race_id, wallet), position)?;
+                Ok(())
+            }
+            Message::SubmissionFee { amount } => {
+                // Player chains send it right after moving the tokens to this
+                // chain, and shard hubs for the runs they take
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let is_local = origin == self.runtime.chain_id();
+                if !is_local
+                    && !self.state.authorized_chains.contains(&origin).await?
+                    && !self.state.config.get().hub_shards.contains(&origin)
+                {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                let pool = self.state.prize_pool.get().saturating_add(amount);
+                self.state.prize_pool.set(pool);
+                Ok(())
+            }
+            Message::CalendarEntryStarted { entry } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if *self.state.events_source.get() != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+
+                // Keep the entry so `upcomingEvents` shows it on this chain
+                // too, apart from local entries whose ids may be the same
+                let now = self.now();
+                for key in self.state.remote_calendar.indices().await? {
+                    let ended = self
+                        .state
+                        .remote_calendar
+                        .get(&key)
+                        .await?
+                        .map_or(true, |entry| entry.ends_at <= now);
+                    if ended {
+                        self.state.remote_calendar.remove(&key)?;
+                    }
+                }
+                let entry_id = entry.id;
+                self.state.remote_calendar.insert(&(origin, entry_id), entry)?;
+                // Subscribers are capped at `MAX_EVENT_SUBSCRIBERS`
+                for wallet in self.state.event_subscribers.indices().await? {
+                    let kind = NotificationKind::EventStarted;
+                    self.notify(&wallet, kind, None, Some(entry_id)).await?;
+                }
+                Ok(())
+            }
+            Message::RaceSnapshot { snapshot } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if !self.state.spectated_race_chains.contains(&origin).await? {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                self.state.race_snapshots.insert(&snapshot.race_id, snapshot)?;
+                Ok(())
+            }
+            Message::RaceSettled { race_id, race } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                // The hub knows the race chain it opened, player chains the
+                // one they were paired on
+                let race_chain = match self.state.races.get(&race_id).await? {
+                    Some(known) if known.finished => return Ok(()),
+                    Some(known) => known.race_chain,
+                    None => self.state.matched_race_chains.get(&race_id).await?,
+                };
+                if race_chain != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+
+                // Only the hub knows about active races; player chains just keep the result
+                let mut on_hub = false;
+                for player in &race.players {
+                    if self.state.active_races.get(player).await? == Some(race_id) {
+                        self.state.active_races.remove(player)?;
+                        on_hub = true;
+                    }
+                }
+                if on_hub && race.finished {
+                    self.rate_race(&race).await?;
+                }
+                self.state.races.insert(&race_id, race)?;
+                Ok(())
+            }
+            Message::ImportAccount {
+                migration_id,
+                player,
+                trophies,
+            } => {
+                let sender = PlayerKey::from(self.signer()?);
+                // The target chain refused the account: keep it here
+                if self.runtime.message_is_bounced() == Some(true) {
+                    let migration = (self.runtime.chain_id(), migration_id);
+                    return self.import_account(sender, migration, player, trophies).await;
+                }
+
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if !self.state.authorized_chains.contains(&origin).await? {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                self.import_account(sender, (origin, migration_id), player, trophies)
+                    .await
+            }
+            Message::ShardHandoff {
+                migration_id,
+                shard_epoch,
+                wallet,
+                player,
+                trophies,
+            } => {
+                // The new shard refused the account: keep it here
+                if self.runtime.message_is_bounced() == Some(true) {
+                    let migration = (self.runtime.chain_id(), migration_id);
+                    return self.import_account(wallet, migration, player, trophies).await;
+                }
+
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if !self.state.config.get().hub_shards.contains(&origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                self.check_shard(&wallet, shard_epoch)?;
+                self.import_account(wallet, (origin, migration_id), player, trophies)
+                    .await?;
+                self.publish_shard_top(Some(wallet)).await
+            }
+            Message::ShardTop {
+                shard_epoch,
+                entries,
+            } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                let config = self.state.config.get();
+                if !config.hub_shards.contains(&origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                // A list built under an older assignment is superseded by the next one
+                if shard_epoch != config.shard_epoch {
+                    return Ok(());
+                }
+
+                let top = ShardTop {
+                    shard_epoch,
+                    entries,
+                    updated_at: self.now(),
+                };
+                self.state.shard_tops.insert(&origin, top)?;
+                Ok(())
+            }
+            Message::LeaderboardDelta { wallet, player } => {
+                let origin = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                if self.state.config.get().mirror_of != Some(origin) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+
+                let known = self.state.players.contains_key(&wallet).await?;
+                let stats = self.state.global_stats.get_mut();
+                match player {
+                    Some(player) => {
+                        if !known {
+                            stats.total_players += 1;
+                        }
+                        let high_score = player.high_score;
+                        self.state.players.insert(&wallet, PlayerData::from(player))?;
+                        self.enroll_player(wallet).await?;
+                        self.set_leaderboard_entry(wallet, high_score).await?;
+                    }
+                    None => {
+                        if known {
+                            stats.total_players = stats.total_players.saturating_sub(1);
+                        }
+                        self.state.players.remove(&wallet)?;
+                        self.remove_leaderboard_entry(wallet).await?;
+                    }
+                }
+                Ok(())
+            }
+            Message::ScoreAttestation {
+                wallet,
+                high_score,
+                season,
+                issued_at,
+                digest,
+                application,
+            } => {
+                // A receiver that rejected it has nothing to hand back
+                if self.runtime.message_is_bounced() == Some(true) {
+                    return Ok(());
+                }
+                let source_chain = self
+                    .runtime
+                    .message_id()
+                    .ok_or(ContractError::UnauthorizedOrigin)?
+                    .chain_id;
+                // Only the wallet's hub, as this chain is configured, attests
+                // its scores; the digest binds the attestation to that hub
+                if self.state.config.get().hub_for(&wallet) != Some(source_chain) {
+                    return Err(ContractError::UnauthorizedOrigin);
+                }
+                if digest != oracle_digest(source_chain, &wallet, high_score, season, issued_at) {
+                    return Err(ContractError::AttestationDigestMismatch);
+                }
+                let attestation = OracleAttestation {
+                    source_chain,
+                    wallet,
+                    high_score,
+                    season,
+                    issued_at,
+                    digest,
+                };
+                self.state.oracle_attestations.insert(&wallet, attestation.clone())?;
+                if let Some(application) = application {
+                    let application = application.with_abi::<ScoreOracleAbi>();
+                    self.runtime.call_application(false, application, &attestation);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn finalize(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl CrossyChainContract {
+    /// Current block time as a UNIX timestamp in seconds
+    fn now(&mut self) -> u64 {
+        self.runtime.system_time().micros() / 1_000_000
+    }
+
+    /// The runtime, for the test harness to move between blocks
+    #[cfg(feature = "testing")]
+    pub(crate) fn runtime_mut(&mut self) -> &mut ContractRuntime<Self> {
+        &mut self.runtime
+    }
+
+    /// Get the authenticated signer or fail with Unauthorized
+    fn signer(&mut self) -> Result<Owner, ContractError> {
+        self.runtime
+            .authenticated_signer()
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Ensure the operation was signed by a holder of `role`. The admin and
+    /// granted super admins hold every role.
+    async fn ensure_role(&mut self, role: Role) -> Result<Owner, ContractError> {
+        let owner = self.signer()?;
+        let key = PlayerKey::from(owner);
+        if *self.state.admin.get() == Some(key) {
+            return Ok(owner);
+        }
+        match self.state.roles.get(&key).await? {
+            Some(Role::SuperAdmin) => Ok(owner),
+            Some(granted) if granted == role => Ok(owner),
+            _ => Err(ContractError::AdminOnly),
+        }
+    }
+
+    /// Reserve `amount` from the prize pool as a claimable reward for `wallet`
+    async fn grant_reward(
+        &mut self,
+        wallet: PlayerKey,
+        amount: Amount,
+        source: RewardSource,
+        source_id: u32,
+    ) -> Result<u64, ContractError> {
+        let pool = *self.state.prize_pool.get();
+        let remaining = pool
+            .try_sub(amount)
+            .map_err(|_| ContractError::InsufficientPrizePool)?;
+        self.state.prize_pool.set(remaining);
+
+        let granted_at = self.now();
+        let expires_at = granted_at.saturating_add(self.state.config.get().reward_claim_window);
+
+        let reward_id = *self.state.next_reward_id.get();
+        self.state.next_reward_id.set(reward_id + 1);
+        self.state.rewards.insert(
+            &reward_id,
+            ClaimableReward {
+                wallet_address: wallet,
+                amount,
+                source,
+                source_id,
+                granted_at,
+                expires_at,
+            },
+        )?;
+        let mut reward_ids = self.state.wallet_rewards.get(&wallet).await?.unwrap_or_default();
+        reward_ids.push(reward_id);
+        self.state.wallet_rewards.insert(&wallet, reward_ids)?;
+
+        Ok(reward_id)
+    }
+
+    /// Drop a claimed or expired reward and its entry in `wallet`'s rewards
+    async fn remove_reward(
+        &mut self,
+        reward_id: u64,
+        wallet: PlayerKey,
+    ) -> Result<(), ContractError> {
+        self.state.rewards.remove(&reward_id)?;
+        let mut reward_ids = self.state.wallet_rewards.get(&wallet).await?.unwrap_or_default();
+        reward_ids.retain(|id| *id != reward_id);
+        if reward_ids.is_empty() {
+            self.state.wallet_rewards.remove(&wallet)?;
+        } else {
+            self.state.wallet_rewards.insert(&wallet, reward_ids)?;
+        }
+        Ok(())
+    }
+
+    /// Count a game in the player's results for the current season. Its
+    /// score is indexed by `index_run_score`.
+    async fn record_season_game(&mut self, wallet: &PlayerKey) -> Result<(), ContractError> {
+        let scope = ScoreScope::new(GameMode::Classic, *self.state.current_season.get());
+        let played_at = self.now();
+        self.state.record_scoped_score(scope, wallet, 0, played_at).await?;
+        Ok(())
+    }
+
+    /// Raise the player's best to `score` in every event that was running at
+    /// `at` and isn't frozen yet, counting a run if `counts_run`
+    async fn record_event_scores(
+        &mut self,
+        wallet: &PlayerKey,
+        score: u32,
+        at: u64,
+        counts_run: bool,
+    ) -> Result<(), ContractError> {
+        let Some(tags) = self.state.events_by_wallet.get(wallet).await? else {
+            return Ok(());
+        };
+        let runs = u32::from(counts_run);
+        for tag in tags {
+            let Some(event) = self.state.events.get(&tag).await? else {
+                continue;
+            };
+            if !event.is_running(at) || event.frozen_at.is_some() {
+                continue;
+            }
+            match event.kind {
+                EventKind::Regional => {
+                    let entries = self.state.event_entries.load_entry_mut(&tag).await?;
+                    if let Some(mut entry) = entries.get(wallet).await? {
+                        entry.best_score = entry.best_score.max(score);
+                        entry.runs += runs;
+                        entries.insert(wallet, entry)?;
+                    }
+                }
+                EventKind::TeamRelay => {
+                    let memberships = self.state.team_memberships.load_entry_mut(&tag).await?;
+                    let Some(team_id) = memberships.get(wallet).await? else {
+                        continue;
+                    };
+                    let Some(mut team) = self.state.event_teams.get(&team_id).await? else {
+                        continue;
+                    };
+                    if let Some(member) = team
+                        .members
+                        .iter_mut()
+                        .find(|member| member.wallet_address == *wallet)
+                    {
+                        member.best_score = member.best_score.max(score);
+                        member.runs += runs;
+                    }
+                    self.state.event_teams.insert(&team_id, team)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `tag` is a team relay event whose rosters can still change
+    async fn ensure_team_roster_open(&mut self, tag: &str) -> Result<(), ContractError> {
+        let event = self
+            .state
+            .events
+            .get(tag)
+            .await?
+            .ok_or(ContractError::EventNotFound)?;
+        if event.kind != EventKind::TeamRelay {
+            return Err(ContractError::WrongEventKind);
+        }
+        if event.frozen_at.is_some() || self.now() >= event.starts_at {
+            return Err(ContractError::TeamRosterLocked);
+        }
+        Ok(())
+    }
+
+    /// Add `wallet` to `team` and save it; a full team drops its pending invites
+    async fn join_team(
+        &mut self,
+        mut team: EventTeam,
+        wallet: PlayerKey,
+    ) -> Result<(), ContractError> {
+        team.members.push(TeamMember {
+            wallet_address: wallet,
+            best_score: 0,
+            runs: 0,
+        });
+        if team.is_full() {
+            team.invited.clear();
+        }
+        self.state
+            .team_memberships
+            .load_entry_mut(&team.tag)
+            .await?
+            .insert(&wallet, team.team_id)?;
+        self.add_event_of_wallet(wallet, team.tag.clone()).await?;
+        self.state.event_teams.insert(&team.team_id, team)?;
+        Ok(())
+    }
+
+    /// Add `tag` to the events `wallet` takes part in, dropping the frozen
+    /// ones, which no longer take scores
+    async fn add_event_of_wallet(
+        &mut self,
+        wallet: PlayerKey,
+        tag: String,
+    ) -> Result<(), ContractError> {
+        let mut tags = Vec::new();
+        for tag in self.state.events_by_wallet.get(&wallet).await?.unwrap_or_default() {
+            let open = self
+                .state
+                .events
+                .get(&tag)
+                .await?
+                .is_some_and(|event| event.frozen_at.is_none());
+            if open {
+                tags.push(tag);
+            }
+        }
+        if tags.len() >= MAX_OPEN_EVENTS_PER_WALLET {
+            return Err(ContractError::TooManyEvents);
+        }
+        tags.push(tag);
+        self.state.events_by_wallet.insert(&wallet, tags)?;
+        Ok(())
+    }
+
+    /// Rate both players of a settled live race
+    async fn rate_race(&mut self, race: &Race) -> Result<(), ContractError> {
+        let [first, second] = &race.players;
+        let outcome = match &race.winner {
+            None => MatchOutcome::Draw,
+            Some(winner) if winner == first => MatchOutcome::Win,
+            Some(_) => MatchOutcome::Loss,
+        };
+        let (Ok(first), Ok(second)) = (first.parse(), second.parse()) else {
+            return Ok(());
+        };
+        self.rate_match(HeadToHeadMode::LiveRace, (first, second), outcome).await
+    }
+
+    /// Update the ratings of both players of a match `outcome` is the first
+    /// player's result of, and add the change to their rating history.
+    /// Linked owners are rated as the profile they are linked to.
+    async fn rate_match(
+        &mut self,
+        mode: HeadToHeadMode,
+        (first, second): (PlayerKey, PlayerKey),
+        outcome: MatchOutcome,
+    ) -> Result<(), ContractError> {
+        let first = self.player_account(first).await?;
+        let second = self.player_account(second).await?;
+        let first_rating = self.state.ratings.get(&first).await?.unwrap_or_default();
+        let second_rating = self.state.ratings.get(&second).await?.unwrap_or_default();
+        let recorded_at = self.now();
+
+        let sides = [
+            (first, first_rating.clone(), &second, second_rating.rating, outcome),
+            (second, second_rating, &first, first_rating.rating, outcome.reversed()),
+        ];
+        for (wallet, mut rating, opponent, opponent_rating, outcome) in sides {
+            let rating_before = rating.rating;
+            rating.record(opponent_rating, outcome);
+            let change = RatingChange {
+                mode,
+                opponent: opponent.to_string(),
+                outcome,
+                rating_before,
+                rating_after: rating.rating,
+                recorded_at,
+            };
+            self.state.ratings.insert(&wallet, rating)?;
+            self.state.rating_history.load_entry_mut(&wallet).await?.push(change);
+        }
+        Ok(())
+    }
+
+    /// Rate `wallet`'s `outcome` against `opponent`'s current rating, leaving
+    /// the opponent's rating as it is
+    async fn rate_against(
+        &mut self,
+        mode: HeadToHeadMode,
+        wallet: PlayerKey,
+        opponent: PlayerKey,
+        outcome: MatchOutcome,
+    ) -> Result<(), ContractError> {
+        let wallet = self.player_account(wallet).await?;
+        let opponent = self.player_account(opponent).await?;
+        let mut rating = self.state.ratings.get(&wallet).await?.unwrap_or_default();
+        let opponent_rating = self.state.ratings.get(&opponent).await?.unwrap_or_default();
+
+        let rating_before = rating.rating;
+        rating.record(opponent_rating.rating, outcome);
+        let change = RatingChange {
+            mode,
+            opponent: opponent.to_string(),
+            outcome,
+            rating_before,
+            rating_after: rating.rating,
+            recorded_at: self.now(),
+        };
+        self.state.ratings.insert(&wallet, rating)?;
+        self.state.rating_history.load_entry_mut(&wallet).await?.push(change);
+        Ok(())
+    }
+
+    /// Mint trophies for the top 3 of the current season, publish the final
+    /// standings as a data blob, then move on to the next season
+    async fn close_season(&mut self) -> Result<(), ContractError> {
+        let season = *self.state.current_season.get();
+        let awarded_at = self.now();
+
+        let scope = ScoreScope::new(GameMode::Classic, season);
+        // Standings of inactive players have decayed by the season's close
+        let decay = self.state.config.get().season_decay;
+        let mut standings = self
+            .state
+            .scope_scores(scope)
+            .await?
+            .into_iter()
+            .map(|(wallet, scores)| (wallet, scores.standing(decay, awarded_at)))
+            .collect::<Vec<_>>();
+        // Scores of the season running when scoped scores were added
+        for wallet in self.state.season_scores.indices().await? {
+            if let Some(score) = self.state.season_scores.get(&wallet).await? {
+                match standings.iter_mut().find(|(scoped, _)| *scoped == wallet) {
+                    Some((_, best)) => *best = (*best).max(score),
+                    None => standings.push((wallet, score)),
+                }
+            }
+        }
+        // Players the leaderboard rules keep off get no rank or trophy
+        let mut eligible = Vec::with_capacity(standings.len());
+        for (wallet, score) in standings {
+            if self.passes_index_rules(&wallet).await? {
+                eligible.push((wallet, score));
+            }
+        }
+        let mut standings = eligible;
+
+        // Highest score first; ties are broken by wallet address so every validator agrees
+        standings.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        for (index, (wallet, score)) in standings.iter().take(3).enumerate() {
+            let trophy = Trophy {
+                season,
+                rank: index as u8 + 1,
+                score: *score,
+                awarded_at,
+            };
+            self.state.trophies.load_entry_mut(wallet).await?.push(trophy);
+        }
+
+        let final_standings = standings
+            .iter()
+            .enumerate()
+            .map(|(index, (wallet, score))| SeasonStanding {
+                rank: index as u32 + 1,
+                wallet_address: wallet.to_string(),
+                score: *score,
+            })
+            .collect::<Vec<_>>();
+        self.state.season_leaderboards.insert(
+            &season,
+            final_standings.iter().take(SEASON_LEADERBOARD_SIZE).cloned().collect(),
+        )?;
+        let blob = serde_json::to_vec(&final_standings).unwrap_or_default();
+        let standings_blob = self.runtime.publish_data_blob(blob);
+        self.state.season_history.insert(
+            &season,
+            SeasonRecord {
+                season,
+                started_at: *self.state.season_started_at.get(),
+                closed_at: awarded_at,
+                player_count: final_standings.len() as u32,
+                standings_blob: standings_blob.to_string(),
+            },
+        )?;
+
+        // Only the finishers `seasonLeaderboard` shows are notified, so the
+        // block closing a season stays bounded however many played it
+        for (wallet, _) in standings.iter().take(SEASON_LEADERBOARD_SIZE) {
+            let kind = NotificationKind::SeasonEnded;
+            self.notify(wallet, kind, None, Some(season as u64)).await?;
+        }
+        let chain_id = self.runtime.chain_id();
+        self.forward_to_notification_hub(HubEvent::SeasonEnded {
+            chain_id,
+            season,
+            winners: standings.iter().take(3).map(|(wallet, _)| wallet.to_string()).collect(),
+            player_count: standings.len() as u32,
+        });
+
+        self.state.season_scores.clear();
+        self.state.current_season.set(season + 1);
+        self.state.season_started_at.set(awarded_at);
+        Ok(())
+    }
+
+    /// Create a race between two queued players and send both the shared seed.
+    /// When both players supplied a public key, a temporary chain owned by the
+    /// two of them is opened to host the race.
+    fn start_race(
+        &mut self,
+        (first, first_racer): (String, QueuedRacer),
+        (second, second_racer): (String, QueuedRacer),
+    ) -> Result<(), ContractError> {
+        let race_id = *self.state.next_race_id.get();
+        self.state.next_race_id.set(race_id + 1);
+
+        let seed = self.race_seed(race_id, &first, &second);
+        let mut race = Race {
+            players: [first.clone(), second.clone()],
+            seed,
+            scores: [None, None],
+            winner: None,
+            finished: false,
+            started_at: self.now(),
+            race_chain: None,
+        };
+
+        if let (Some(first_key), Some(second_key)) = (first_racer.public_key, second_racer.public_key) {
+            let ownership = ChainOwnership::multiple(
+                [(first_key, 100), (second_key, 100)],
+                u32::MAX,
+                TimeoutConfig::default(),
+            );
+            let (_, race_chain) =
+                self.runtime
+                    .open_chain(ownership, ApplicationPermissions::default(), Amount::ZERO);
+            race.race_chain = Some(race_chain);
+
+            let info = RaceChainInfo {
+                hub: self.runtime.chain_id(),
+                home_chains: [first_racer.chain_id, second_racer.chain_id],
+            };
+            self.runtime
+                .prepare_message(Message::RaceChainOpened {
+                    race_id,
+                    race: race.clone(),
+                    info,
+                })
+                .send_to(race_chain);
+        }
+
+        let race_chain = race.race_chain;
+        self.state.races.insert(&race_id, race)?;
+        self.state.active_races.insert(&first, race_id)?;
+        self.state.active_races.insert(&second, race_id)?;
+
+        for (wallet, opponent, chain_id) in [
+            (first.clone(), second.clone(), first_racer.chain_id),
+            (second, first, second_racer.chain_id),
+        ] {
+            self.runtime
+                .prepare_message(Message::RaceMatched {
+                    wallet,
+                    race_id,
+                    opponent,
+                    seed,
+                    race_chain,
+                })
+                .with_authentication()
+                .send_to(chain_id);
+        }
+
+        Ok(())
+    }
+
+    /// Collect the latest known position of every participant in a race
+    async fn race_snapshot(&mut self, race_id: u64, race: &Race) -> Result<RaceSnapshot, ContractError> {
+        let mut positions = Vec::new();
+        for player in &race.players {
+            positions.push(
+                self.state
+                    .race_positions
+                    .get(&(race_id, player.clone()))
+                    .await?
+                    .unwrap_or(GhostPoint {
+                        t: 0,
+                        lane: 0,
+                        column: crate::replay::BOARD_COLUMNS / 2,
+                    }),
+            );
+        }
+
+        Ok(RaceSnapshot {
+            race_id,
+            players: race.players.clone(),
+            positions,
+            scores: race.scores,
+            finished: race.finished,
+            taken_at: self.now(),
+        })
+    }
+
+    /// Send a snapshot to every chain subscribed to this race chain's spectator channel
+    fn publish_snapshot(&mut self, snapshot: RaceSnapshot) {
+        let channel = ChannelName::from(SPECTATOR_CHANNEL.to_vec());
+        self.runtime
+            .prepare_message(Message::RaceSnapshot { snapshot })
+            .send_to(Destination::Subscribers(channel));
+    }
+
+    /// Send a calendar entry that started to every chain subscribed to this
+    /// chain's events channel
+    fn publish_calendar_entry(&mut self, entry: CalendarEntry) {
+        let channel = ChannelName::from(EVENTS_CHANNEL.to_vec());
+        self.runtime
+            .prepare_message(Message::CalendarEntryStarted { entry })
+            .send_to(Destination::Subscribers(channel));
+    }
+
+    /// Derive a race seed neither player can choose, from the race and the current block
+    fn race_seed(&mut self, race_id: u64, first: &str, second: &str) -> u64 {
+        let mut hasher = Sha256::new();
+        hasher.update(race_id.to_le_bytes());
+        hasher.update(first.as_bytes());
+        hasher.update(second.as_bytes());
+        hasher.update(self.runtime.block_height().0.to_le_bytes());
+        hasher.update(self.runtime.system_time().micros().to_le_bytes());
+        let digest = hasher.finalize();
+
+        digest_prefix_u64(&digest)
+    }
+
+    /// Remove up to `MAX_REWARDS_EXPIRED_PER_RUN` expired rewards and
+    /// return their amounts to the prize pool. Returns whether expired
+    /// rewards remain.
+    async fn expire_rewards(&mut self) -> Result<bool, ContractError> {
+        let now = self.now();
+        let mut pool = *self.state.prize_pool.get();
+        let mut expired = 0;
+
+        for reward_id in self.state.rewards.indices().await? {
+            if let Some(reward) = self.state.rewards.get(&reward_id).await? {
+                if now > reward.expires_at {
+                    if expired == MAX_REWARDS_EXPIRED_PER_RUN {
+                        self.state.prize_pool.set(pool);
+                        return Ok(true);
+                    }
+                    pool = pool.saturating_add(reward.amount);
+                    self.remove_reward(reward_id, reward.wallet_address).await?;
+                    expired += 1;
+                }
+            }
+        }
+
+        self.state.prize_pool.set(pool);
+        Ok(false)
+    }
+
+    /// Get the like/view counters of a player's current replay. Counters
+    /// belonging to an older replay are reset, since a new high score
+    /// replaces the replay they were counting.
+    async fn current_replay_stats(
+        &mut self,
+        wallet: &PlayerKey,
+    ) -> Result<(u32, ReplayStats), ContractError> {
+        let replay_score = self
+            .state
+            .players
+            .get(wallet)
+            .await?
+            .filter(|player| player.replay_data.is_some())
+            .ok_or(ContractError::ReplayNotFound)?
+            .high_score;
+
+        let stats = self
+            .state
+            .replay_stats
+            .get(wallet)
+            .await?
+            .filter(|stats| stats.replay_score == replay_score)
+            .unwrap_or(ReplayStats {
+                replay_score,
+                likes: 0,
+                views: 0,
+            });
+
+        Ok((replay_score, stats))
+    }
+
+    /// Drop replays that are neither among the top `replay_retention_top_n`
+    /// high scores nor featured
+    async fn prune_excess_replays(&mut self) -> Result<(), ContractError> {
+        let keep = self.state.config.get().replay_retention_top_n as usize;
+
+        let mut holders = Vec::new();
+        for wallet in self.state.replay_holders.indices().await? {
+            if let Some(score) = self.state.replay_holders.get(&wallet).await? {
+                holders.push((wallet, score));
+            }
+        }
+        if holders.len() <= keep {
+            return Ok(());
+        }
+
+        holders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (wallet, _) in holders.into_iter().skip(keep) {
+            if self.state.featured_replays.contains_key(&wallet).await? {
+                continue;
+            }
+            self.prune_replay(&wallet).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Run the next batch of a `PruneReplays` pass
+    ///
+    /// Replays stored before the holder index existed are indexed from the
+    /// roster, `batch_size` players at a time; the last batch applies the
+    /// retention policy and ends the pass.
+    async fn prune_replays(&mut self, batch_size: usize) -> Result<(), ContractError> {
+        let cursor = self.state.pruning_stats.get().cursor.unwrap_or(0);
+        let roster_len = self.state.player_roster.count();
+        let start = (cursor as usize).min(roster_len);
+        let end = start.saturating_add(batch_size).min(roster_len);
+
+        for wallet in self.state.player_roster.read(start..end).await? {
+            if let Some(player) = self.state.players.get(&wallet).await? {
+                if player.replay_data.is_some() {
+                    self.state.replay_holders.insert(&wallet, player.high_score)?;
+                }
+            }
+        }
+
+        if end < roster_len {
+            self.state.pruning_stats.get_mut().cursor = Some(end as u64);
+            return Ok(());
+        }
+        self.state.pruning_stats.get_mut().cursor = None;
+        self.prune_excess_replays().await
+    }
+
+    /// Remove a player's stored replay and record the bytes freed
+    async fn prune_replay(&mut self, wallet: &PlayerKey) -> Result<(), ContractError> {
+        self.state.replay_holders.remove(wallet)?;
+
+        let Some(mut player) = self.state.players.get(wallet).await? else {
+            return Ok(());
+        };
+        let Some(replay) = player.replay_data.take() else {
+            return Ok(());
+        };
+        self.state.players.insert(wallet, player)?;
+
+        let now = self.now();
+        let stats = self.state.pruning_stats.get_mut();
+        stats.replays_pruned += 1;
+        stats.bytes_pruned += replay.len() as u64;
+        stats.last_pruned_at = Some(now);
+
+        Ok(())
+    }
+
+    /// Check the metadata carried by a replay envelope and return it for storage
+    fn validate_replay_metadata(&mut self, replay_json: &str) -> Result<ReplayMetadata, ContractError> {
+        let recording = Recording::parse(replay_json)?;
+        let meta = recording
+            .metadata()
+            .ok_or(ContractError::ReplayMetadataMissing)?;
+
+        self.check_client_version(&meta.client_version)?;
+
+        // The run must have been played under a config version this chain knows about
+        if meta.config_version > *self.state.config_version.get() {
+            return Err(ContractError::UnknownConfigVersion);
+        }
+
+        Ok(meta)
+    }
+
+    /// Game config of `version`: the current one, or the one it replaced
+    async fn game_config_at(&self, version: u32) -> Result<GameConfig, ContractError> {
+        if version == *self.state.config_version.get() {
+            return Ok(self.state.game_config.get().clone());
+        }
+        self.state
+            .game_config_history
+            .get(&version)
+            .await?
+            .ok_or(ContractError::UnknownConfigVersion)
+    }
+
+    /// Ensure a client version is well formed and not older than `min_client_version`
+    fn check_client_version(&mut self, version: &str) -> Result<(), ContractError> {
+        let client_version = validation::validate_client_version(version)?;
+        if let Some(min_version) = &self.state.config.get().min_client_version {
+            if validation::validate_client_version(min_version).is_ok_and(|min| client_version < min) {
+                return Err(ContractError::ClientVersionTooOld);
+            }
+        }
+        Ok(())
+    }
+
+    /// Wallet a cross-chain message acts for: the signer that authenticated
+    /// the message on its origin chain. Messages are always delivered between
+    /// instances of this same application, so checking the origin chain is
+    /// enough to keep unofficial chains from pushing scores in.
+    async fn message_sender(&mut self) -> Result<PlayerKey, ContractError> {
+        let origin = self
+            .runtime
+            .message_id()
+            .ok_or(ContractError::UnauthorizedOrigin)?
+            .chain_id;
+        let is_local = origin == self.runtime.chain_id();
+        if !is_local && !self.state.authorized_chains.contains(&origin).await? {
+            return Err(ContractError::UnauthorizedOrigin);
+        }
+
+        self.runtime
+            .authenticated_signer()
+            .map(PlayerKey::from)
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Apply a score submission for `sender`, shared by the SaveScore operation
+    /// and message. `forwarded` scores were already rate limited on the chain
+    /// they were submitted to, and their order is checked by the caller.
+    /// Returns the attestation of the accepted score, or None if the
+    /// submission was a retry already counted.
+    async fn handle_save_score(
+        &mut self,
+        sender: PlayerKey,
+        submission: ScoreSubmission,
+        forwarded: bool,
+    ) -> Result<Option<ScoreAttestation>, ContractError> {
+        let ScoreSubmission {
+            score,
+            replay_data,
+            compression,
+            timestamp,
+            client_version,
+            submission_id,
+            run_stats,
+            proof,
+        } = submission;
+        let sender = self.submitting_account(sender).await?;
+
+        // Reject invalid scores
+        validation::validate_score(score)?;
+        validation::validate_submission_id(&submission_id)?;
+
+        // Reject submissions from clients older than the configured minimum
+        self.check_client_version(&client_version)?;
+
+        // Get or create player data
+        let existing = self.state.players.get(&sender).await?;
+        let is_new_player = existing.is_none();
+        let mut player = existing.unwrap_or_default();
+
+        // A retried transaction must not count the same game twice
+        if player.recent_submission_ids.contains(&submission_id) {
+            return Ok(None);
+        }
+        player.recent_submission_ids.push(submission_id.clone());
+        if player.recent_submission_ids.len() > RECENT_SUBMISSION_IDS {
+            player.recent_submission_ids.remove(0);
+        }
+
+        if !forwarded {
+            self.check_rate_limit(&mut player)?;
+        }
+
+        // Size limits and digests apply to the recording JSON, however it was sent
+        let max_replay_size = self.state.config.get().max_replay_size;
+        let replay_json = replay_data
+            .as_deref()
+            .map(|replay| compression::decompress_replay(replay, compression, max_replay_size))
+            .transpose()?;
+        let replay_digest = replay_json.as_deref().map(|replay| hex_digest(&[replay.as_bytes()]));
+
+        // Coins are bounded by the lanes the run reached: the furthest lane
+        // of its replay, or its score without one
+        if let Some(run_stats) = &run_stats {
+            let recording = replay_json.as_deref().map(Recording::parse).transpose()?;
+            let lanes = match recording {
+                Some(recording) => replay::ghost_trace(&recording)
+                    .iter()
+                    .map(|point| u64::from(point.lane.unsigned_abs()))
+                    .max()
+                    .unwrap_or(0),
+                None => u64::from(score),
+            };
+            validation::validate_run_coins(run_stats.coins, lanes)?;
+        }
+
+        // Check if this is a new high score
+        let is_new_high_score = score > player.high_score;
+        let mut proven = false;
+        let mut provisional_previous = None;
+
+        // STRICT VALIDATION: Require replay data for all new high scores
+        // This ensures anti-cheat verification is possible for leaderboard entries
+        if is_new_high_score {
+            // Replay data is mandatory for high scores
+            let (Some(stored_replay), Some(replay_json)) = (replay_data, replay_json) else {
+                return Err(ContractError::ReplayRequired);
+            };
+
+            // Validate the decompressed replay size against the configured limit
+            validation::validate_replay_size(&replay_json, max_replay_size)?;
+
+            // Validate the replay envelope (format, platform, client version)
+            let replay_meta = self.validate_replay_metadata(&replay_json)?;
+
+            // The replay must be credited the submitted score under the config
+            // it was played with
+            let config = self.game_config_at(replay_meta.config_version).await?;
+            if replay::verify_replay(replay_json.as_bytes(), &config)? != score {
+                return Err(ContractError::ReplayScoreMismatch);
+            }
+
+            // The replay is stored as sent, compressed or not. It replaces the
+            // player's classic one; replays kept for other modes still count
+            // against the quota.
+            let current = player.replay_data.as_ref().map_or(0, |replay| replay.len() as u64);
+            let kept = player.replay_bytes().saturating_sub(current);
+            let quota = self.state.config.get().replay_storage_quota;
+            if kept.saturating_add(stored_replay.len() as u64) > quota {
+                return Err(ContractError::ReplayQuotaExceeded);
+            }
+
+            self.check_death_cause(&replay_json, run_stats.as_ref())?;
+            // A valid succinct proof settles the run without waiting for verifiers
+            proven = match &proof {
+                Some(proof) => self.check_run_proof(&replay_json, score, proof)?,
+                None => false,
+            };
+
+            // Summarize now so views and heuristics don't reload the full replay
+            let recording = Recording::parse(&replay_json)?;
+            let checksum = replay_digest.clone().unwrap_or_default();
+            let replay_summary = replay::summarize(&recording, checksum);
+
+            // With a provisional window, keep the last confirmed high score
+            // until verification confirms this one
+            let previous = player.provisional.take().map(|provisional| provisional.previous);
+            if self.state.config.get().provisional_window.is_some() && !proven {
+                provisional_previous =
+                    Some(previous.unwrap_or_else(|| player.high_score_snapshot()));
+            }
+
+            // Update high score and replay atomically
+            player.high_score = score;
+            player.replay_data = Some(stored_replay);
+            player.replay_compression = compression;
+            player.replay_format_version = replay_meta.format_version;
+            player.replay_summary = Some(replay_summary);
+            player.replay_origin = Some(self.replay_origin());
+            player.replay_meta = Some(replay_meta);
+
+            // TODO: When Linera SDK blob storage is ready, upload to blob storage:
+            // let replay_bytes = replay_json.into_bytes();
+            // let blob_hash = self.runtime.publish_data_blob(replay_bytes).await?;
+            // player.replay_blob_id = Some(format!("{:?}", blob_hash));
+            // Then we can remove the replay_data field and use only replay_blob_id
+        }
+        // For non-high scores, we don't update anything related to replays
+        // This preserves the existing high-score replay
+
+        // Increment games played
+        player.games_played += 1;
+        player.record_mode_game(GameMode::Classic, self.now());
+        player.record_placement_run(score);
+        // Accounts from before creation was tracked keep counting as old
+        if is_new_player {
+            player.first_seen_at = self.now();
+        }
+
+        // Daily activity, for streaks and the games-per-day record
+        let day = self.now() / SECONDS_PER_DAY;
+        if player.play_day == day {
+            player.games_on_play_day += 1;
+        } else {
+            player.day_streak = if player.play_day + 1 == day {
+                player.day_streak + 1
+            } else {
+                1
+            };
+            player.play_day = day;
+            player.games_on_play_day = 1;
+        }
+        player.best_streak = player.best_streak.max(player.day_streak);
+        self.update_records(&sender, &player);
+
+        // A provisional high score counts as a game now, but only reaches the
+        // score indexes once verification confirms it
+        let provisional = provisional_previous.is_some();
+
+        // Lifetime aggregates, kept here since averages can't be rebuilt without the full history
+        player.total_score += score as u64;
+        if self.state.global_stats.get().top_day != day {
+            self.roll_daily_indexes(day).await?;
+        }
+        let coins = run_stats.as_ref().map_or(0, |run_stats| run_stats.coins);
+        let stats = self.state.global_stats.get_mut();
+        stats.record_run(day, score, coins);
+        if is_new_player {
+            stats.total_players += 1;
+            self.enroll_player(sender).await?;
+        }
+        if let Some(run_stats) = &run_stats {
+            player.total_playtime_ms += run_stats.duration_ms;
+            player.coins = player.coins.saturating_add(run_stats.coins);
+            if let Some(cause) = run_stats.death_cause {
+                player.death_tally.add(cause);
+                self.state.death_tally.get_mut().add(cause);
+            }
+        }
+
+        // Runs count for the challenge running when they are submitted
+        let modifier = self
+            .state
+            .modifier_schedule
+            .get(&challenge_week(self.now()))
+            .await?;
+        let run = ScoredRun {
+            score,
+            played_at: timestamp,
+            submitted_at: self.now(),
+            season: *self.state.current_season.get(),
+            modifier,
+        };
+        if !provisional {
+            player.record_personal_best(&run);
+        }
+
+        // Update last played timestamp
+        player.last_played_at = Some(timestamp);
+        self.recheck_display_name(sender, &mut player).await?;
+
+        // Save updated player data
+        self.state.players.insert(&sender, player)?;
+
+        self.advance_quests(&sender, score, run_stats.as_ref()).await?;
+        self.complete_onboarding_step(sender, OnboardingStep::FirstRun).await?;
+        if is_new_high_score {
+            self.complete_onboarding_step(sender, OnboardingStep::FirstReplay).await?;
+        }
+
+        // Count the game in the current season and running events
+        self.record_season_game(&sender).await?;
+        self.record_event_scores(&sender, 0, run.submitted_at, true).await?;
+        // A freeze holds back the run's standings along with its leaderboard entry
+        let frozen = self.leaderboard_frozen().await?;
+        if frozen && !provisional {
+            self.state.held_runs.push_back((sender, run.clone()));
+        } else if !provisional {
+            self.index_run_score(sender, &run).await?;
+        }
+
+        // A new replay may push an older one out of the retained top N,
+        // and a new high score may push another player off the leaderboard
+        if is_new_high_score {
+            let verification_id = self.queue_verification(&sender, score, proven).await?;
+            self.track_provisional(sender, verification_id, provisional_previous, run)
+                .await?;
+            self.state.replay_holders.insert(&sender, score)?;
+            self.prune_excess_replays().await?;
+            if frozen {
+                self.state.frozen_leaderboard_updates.insert(&sender)?;
+            } else {
+                self.set_leaderboard_entry(sender, score).await?;
+                self.evict_beyond_leaderboard_cap().await?;
+                self.publish_shard_top(Some(sender)).await?;
+            }
+        } else if self.state.leaderboard_index.contains_key(&sender).await? {
+            // Games played and lifetime stats changed without a new high score
+            if frozen {
+                self.state.frozen_leaderboard_updates.insert(&sender)?;
+            } else {
+                self.publish_leaderboard_delta(sender).await?;
+            }
+        }
+
+        let mut flags = Vec::new();
+        if is_new_high_score {
+            flags.push(ReceiptFlag::NewHighScore);
+        }
+        if provisional {
+            flags.push(ReceiptFlag::Provisional);
+        }
+        if proven {
+            flags.push(ReceiptFlag::Proven);
+        }
+        if forwarded {
+            flags.push(ReceiptFlag::Relayed);
+        }
+        if frozen {
+            flags.push(ReceiptFlag::LeaderboardFrozen);
+        }
+        let receipt = SubmissionReceipt {
+            submission_id: submission_id.clone(),
+            score,
+            mode: GameMode::Classic,
+            block_height: self.runtime.block_height().0,
+            played_at: timestamp,
+            recorded_at: self.now(),
+            modifier,
+            flags,
+        };
+        let receipts = self.state.submission_receipts.load_entry_mut(&sender).await?;
+        receipts.push_back(receipt);
+        while receipts.count() > MAX_SUBMISSION_RECEIPTS {
+            receipts.delete_front();
+        }
+
+        // Read back, as pruning above may have dropped the replay just stored
+        let replay_bytes = self
+            .state
+            .players
+            .get(&sender)
+            .await?
+            .map_or(0, |player| player.replay_bytes());
+        let quota = self.state.config.get().replay_storage_quota;
+        let attestation = self.attest_score(
+            sender,
+            score,
+            timestamp,
+            submission_id,
+            replay_digest,
+            quota.saturating_sub(replay_bytes),
+        );
+        Ok(Some(attestation))
+    }
+
+    /// Set or clear `sender`'s display name, shared by the RegisterPlayer
+    /// operation and message
+    async fn handle_register_player(
+        &mut self,
+        sender: PlayerKey,
+        display_name: Option<String>,
+    ) -> Result<(), ContractError> {
+        let sender = self.player_account(sender).await?;
+
+        // Get or create player data
+        let existing = self.state.players.get(&sender).await?;
+        let is_new_player = existing.is_none();
+        if is_new_player {
+            self.state.global_stats.get_mut().total_players += 1;
+            self.enroll_player(sender).await?;
+        }
+        let mut player = existing.unwrap_or_default();
+
+        // Accounts from before creation was tracked keep counting as old
+        if is_new_player {
+            player.first_seen_at = self.now();
+        }
+
+        // Validate and update display name if provided; explicitly setting
+        // None clears the display name
+        let display_name = match display_name {
+            Some(name) => Some(validation::validate_display_name(&name)?),
+            None => None,
+        };
+        let blocklist = self.state.name_blocklist.get();
+        if let Some(name) = &display_name {
+            validation::check_name_blocklist(name, &blocklist.terms)?;
+        }
+        // The new name was just checked, and a cleared one needs no check
+        player.name_checked_version = blocklist.version;
+        if display_name != player.display_name {
+            if let Some(name) = &display_name {
+                let policy = self.state.name_script_policy.get();
+                let allowed = policy.allowed_for(player.country.as_deref());
+                validation::validate_name_scripts(name, allowed)?;
+                self.check_impersonation(sender, name).await?;
+            }
+            self.release_verified_name(sender, player.display_name.as_deref()).await?;
+            self.rename(&mut player, display_name)?;
+            self.claim_verified_name(sender, &player)?;
+        }
+
+        let name_set = player.display_name.is_some();
+
+        // Save updated player data
+        self.state.players.insert(&sender, player)?;
+
+        self.complete_onboarding_step(sender, OnboardingStep::Registered).await?;
+        if name_set {
+            self.complete_onboarding_step(sender, OnboardingStep::NameSet).await?;
+        }
+        self.grant_welcome_bonus(sender).await
+    }
+
+    /// Change `player`'s display name. Setting the first name is free; any
+    /// later change must wait `RENAME_COOLDOWN` after the previous one, so a
+    /// player can't impersonate others by rapid renaming.
+    fn rename(
+        &mut self,
+        player: &mut PlayerData,
+        display_name: Option<String>,
+    ) -> Result<(), ContractError> {
+        let now = self.now();
+        let first_name = player.display_name.is_none() && player.name_history.is_empty();
+        if !first_name {
+            let cooling_down = player
+                .renamed_at
+                .is_some_and(|renamed_at| now < renamed_at.saturating_add(RENAME_COOLDOWN));
+            if cooling_down {
+                return Err(ContractError::RenameCooldown);
+            }
+            player.renamed_at = Some(now);
+        }
+        if let Some(previous) = player.display_name.take() {
+            player.name_history.insert(0, previous);
+            player.name_history.truncate(MAX_NAME_HISTORY);
+        }
+        player.display_name = display_name;
+        Ok(())
+    }
+
+    /// Check `player`'s display name against a blocklist updated since it was
+    /// last checked, flagging it for moderators if it now matches
+    async fn recheck_display_name(
+        &mut self,
+        wallet: PlayerKey,
+        player: &mut PlayerData,
+    ) -> Result<(), ContractError> {
+        let blocklist = self.state.name_blocklist.get();
+        let version = blocklist.version;
+        if player.name_checked_version >= version {
+            return Ok(());
+        }
+        player.name_checked_version = version;
+        let blocked = player.display_name.as_deref().filter(|name| {
+            validation::check_name_blocklist(name, &blocklist.terms).is_err()
+        });
+        let Some(name) = blocked.map(str::to_string) else {
+            return Ok(());
+        };
+        let review = NameReview {
+            wallet_address: wallet,
+            display_name: name,
+            blocklist_version: version,
+            flagged_at: self.now(),
+        };
+        self.state.name_reviews.insert(&wallet, review)?;
+        Ok(())
+    }
+
+    /// Fail if `name` is a confusable variant of another player's verified name
+    async fn check_impersonation(
+        &mut self,
+        wallet: PlayerKey,
+        name: &str,
+    ) -> Result<(), ContractError> {
+        let skeleton = validation::name_skeleton(name);
+        match self.state.verified_names.get(&skeleton).await? {
+            Some(owner) if owner != wallet => Err(ContractError::NameImpersonates),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drop `wallet`'s claim on the skeleton of `name`, its previous display name
+    async fn release_verified_name(
+        &mut self,
+        wallet: PlayerKey,
+        name: Option<&str>,
+    ) -> Result<(), ContractError> {
+        let Some(name) = name else {
+            return Ok(());
+        };
+        let skeleton = validation::name_skeleton(name);
+        if self.state.verified_names.get(&skeleton).await? == Some(wallet) {
+            self.state.verified_names.remove(&skeleton)?;
+        }
+        Ok(())
+    }
+
+    /// Protect the display name of `player`, stored under `wallet`, if they are verified
+    fn claim_verified_name(
+        &mut self,
+        wallet: PlayerKey,
+        player: &PlayerData,
+    ) -> Result<(), ContractError> {
+        if let (true, Some(name)) = (player.verified, &player.display_name) {
+            let skeleton = validation::name_skeleton(name);
+            self.state.verified_names.insert(&skeleton, wallet)?;
+        }
+        Ok(())
+    }
+
+    /// Mark `step` of `wallet`'s onboarding completed. Completing the last
+    /// step grants `ONBOARDING_REWARD_COINS`, once.
+    async fn complete_onboarding_step(
+        &mut self,
+        wallet: PlayerKey,
+        step: OnboardingStep,
+    ) -> Result<(), ContractError> {
+        let mut onboarding = self.state.onboarding.get(&wallet).await?.unwrap_or_default();
+        if !onboarding.complete(step) {
+            return Ok(());
+        }
+        // Finishing takes a first run, so the player exists by then; a wallet
+        // without player data is never given one here
+        if onboarding.completed_at.is_none() && onboarding.is_finished() {
+            if let Some(mut player) = self.state.players.get(&wallet).await? {
+                onboarding.completed_at = Some(self.now());
+                player.coins = player.coins.saturating_add(ONBOARDING_REWARD_COINS);
+                self.state.players.insert(&wallet, player)?;
+            }
+        }
+        self.state.onboarding.insert(&wallet, onboarding)?;
+        Ok(())
+    }
+
+    /// Have the faucet grant `wallet` its welcome bonus, if one is configured
+    /// and the wallet never got it. Only the wallet's hub grants it, so a
+    /// registration seen by several chains pays out once. The grant is sent as
+    /// a message to the faucet's chain, so registering never waits on the
+    /// faucet; a grant it refuses bounces, and the next registration retries.
+    async fn grant_welcome_bonus(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        let config = self.state.config.get();
+        let (Some(faucet), amount) = (config.welcome_faucet, config.welcome_bonus) else {
+            return Ok(());
+        };
+        let chain_id = self.runtime.chain_id();
+        if amount == Amount::ZERO || config.hub_for(&wallet).is_some_and(|hub| hub != chain_id) {
+            return Ok(());
+        }
+        if self.state.welcome_bonus_claimed.contains(&wallet).await? {
+            return Ok(());
+        }
+
+        self.state.welcome_bonus_claimed.insert(&wallet)?;
+        self.runtime
+            .prepare_message(Message::GrantWelcomeBonus { wallet, amount })
+            .with_tracking()
+            .send_to(faucet.creation.chain_id);
+        Ok(())
+    }
+
+    /// Send a recorded score on to the hub chain, if this chain is not the hub
+    async fn forward_score_to_hub(
+        &mut self,
+        sender: PlayerKey,
+        submission: ScoreSubmission,
+    ) -> Result<(), ContractError> {
+        let Some(hub) = self.state.config.get().hub_for(&sender) else {
+            return Ok(());
+        };
+        if hub == self.runtime.chain_id() {
+            return Ok(());
+        }
+
+        let sequence = self.state.hub_sequences.get(&sender).await?.unwrap_or(0) + 1;
+        self.state.hub_sequences.insert(&sender, sequence)?;
+        self.send_score_to_hub(hub, submission, sequence);
+        Ok(())
+    }
+
+    /// Send a SaveScore message to the hub, tracked so a rejection bounces back here
+    fn send_score_to_hub(&mut self, hub: ChainId, submission: ScoreSubmission, sequence: u64) {
+        let shard_epoch = self.state.config.get().shard_epoch;
+        self.runtime
+            .prepare_message(Message::SaveScore {
+                score: submission.score,
+                replay_data: submission.replay_data,
+                compression: submission.compression,
+                timestamp: submission.timestamp,
+                client_version: submission.client_version,
+                submission_id: submission.submission_id,
+                run_stats: submission.run_stats,
+                proof: submission.proof,
+                sequence,
+                shard_epoch,
+            })
+            .with_authentication()
+            .with_tracking()
+            .send_to(hub);
+    }
+
+    /// Keep a bounced score until its wallet's next operation resends it
+    async fn record_bounced_score(
+        &mut self,
+        wallet: PlayerKey,
+        submission: ScoreSubmission,
+        sequence: u64,
+    ) -> Result<(), ContractError> {
+        let pending = PendingHubScore {
+            score: submission.score,
+            replay_data: submission.replay_data,
+            compression: submission.compression,
+            timestamp: submission.timestamp,
+            client_version: submission.client_version,
+            submission_id: submission.submission_id,
+            run_stats: submission.run_stats,
+            proof: submission.proof,
+            bounced_at: self.now(),
+        };
+        let pending_scores = self.state.pending_hub_sync.load_entry_mut(&wallet).await?;
+        pending_scores.insert(&sequence, pending)?;
+        Ok(())
+    }
+
+    /// Resend every bounced score of `wallet`, oldest first, since the hub
+    /// only accepts each wallet's scores in sequence order
+    async fn retry_hub_sync(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        let Some(hub) = self.state.config.get().hub_for(&wallet) else {
+            return Ok(());
+        };
+
+        let mut pending = Vec::new();
+        if let Some(pending_scores) = self.state.pending_hub_sync.try_load_entry(&wallet).await? {
+            for sequence in pending_scores.indices().await? {
+                if let Some(score) = pending_scores.get(&sequence).await? {
+                    pending.push((sequence, score));
+                }
+            }
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+        self.state.pending_hub_sync.remove_entry(&wallet)?;
+        pending.sort_by_key(|(sequence, _)| *sequence);
+        for (sequence, score) in pending {
+            let submission = ScoreSubmission {
+                score: score.score,
+                replay_data: score.replay_data,
+                compression: score.compression,
+                timestamp: score.timestamp,
+                client_version: score.client_version,
+                submission_id: score.submission_id,
+                run_stats: score.run_stats,
+                proof: score.proof,
+            };
+            self.send_score_to_hub(hub, submission, sequence);
+        }
+
+        Ok(())
+    }
+
+    /// Package `wallet`'s account into a signed message to `target_chain` and
+    /// drop it here, so the account lives on exactly one chain
+    async fn migrate_account(
+        &mut self,
+        wallet: PlayerKey,
+        target_chain: ChainId,
+    ) -> Result<(), ContractError> {
+        if target_chain == self.runtime.chain_id() {
+            return Err(ContractError::InvalidMigrationTarget);
+        }
+
+        let (player, trophies) = self.take_account(wallet).await?;
+        let migration_id = *self.state.next_migration_id.get();
+        self.state.next_migration_id.set(migration_id + 1);
+        self.runtime
+            .prepare_message(Message::ImportAccount {
+                migration_id,
+                player,
+                trophies,
+            })
+            .with_authentication()
+            .with_tracking()
+            .send_to(target_chain);
+
+        Ok(())
+    }
+
+    /// Remove `wallet`'s account (data, replay, leaderboard entry and trophies)
+    /// from this chain, to be sent to another one
+    async fn take_account(
+        &mut self,
+        wallet: PlayerKey,
+    ) -> Result<(PlayerData, Vec<Trophy>), ContractError> {
+        let player = self
+            .state
+            .players
+            .get(&wallet)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)?;
+        let trophies = match self.state.trophies.try_load_entry(&wallet).await? {
+            Some(log) => {
+                let count = log.count();
+                log.read(0..count).await?
+            }
+            None => Vec::new(),
+        };
+
+        self.state.players.remove(&wallet)?;
+        let stats = self.state.global_stats.get_mut();
+        stats.total_players = stats.total_players.saturating_sub(1);
+        self.state.replay_holders.remove(&wallet)?;
+        self.remove_leaderboard_entry(wallet).await?;
+        self.state.trophies.remove_entry(&wallet)?;
+        Ok((player, trophies))
+    }
+
+    /// Merge an account moved from another chain into `wallet`'s data here.
+    /// Messages only come from instances of this application, so the data was
+    /// produced by this contract; each migration is applied at most once.
+    async fn import_account(
+        &mut self,
+        wallet: PlayerKey,
+        migration: (ChainId, u64),
+        imported: PlayerData,
+        trophies: Vec<Trophy>,
+    ) -> Result<(), ContractError> {
+        if self.state.imported_migrations.contains(&migration).await? {
+            return Ok(());
+        }
+        self.state.imported_migrations.insert(&migration)?;
+
+        let player = match self.state.players.get(&wallet).await? {
+            None => {
+                self.state.global_stats.get_mut().total_players += 1;
+                self.enroll_player(wallet).await?;
+                imported
+            }
+            Some(mut player) => {
+                // Keep the better high score together with the replay that proves it
+                if imported.high_score > player.high_score {
+                    player.high_score = imported.high_score;
+                    player.replay_data = imported.replay_data;
+                    player.replay_compression = imported.replay_compression;
+                    player.replay_blob_id = imported.replay_blob_id;
+                    player.replay_meta = imported.replay_meta;
+                    player.replay_format_version = imported.replay_format_version;
+                    player.replay_summary = imported.replay_summary;
+                    player.replay_origin = imported.replay_origin;
+                }
+                player.games_played += imported.games_played;
+                player.total_score = player.total_score.saturating_add(imported.total_score);
+                player.total_playtime_ms =
+                    player.total_playtime_ms.saturating_add(imported.total_playtime_ms);
+                player.best_streak = player.best_streak.max(imported.best_streak);
+                player.death_tally.merge(&imported.death_tally);
+                player.merge_personal_bests(imported.personal_bests);
+                player.xp = player.xp.saturating_add(imported.xp);
+                player.coins = player.coins.saturating_add(imported.coins);
+                player.entry_fee_paid |= imported.entry_fee_paid;
+                player.first_seen_at = match (player.first_seen_at, imported.first_seen_at) {
+                    (0, seen) | (seen, 0) => seen,
+                    (ours, theirs) => ours.min(theirs),
+                };
+                player.last_played_at = player.last_played_at.max(imported.last_played_at);
+                player.display_name = player.display_name.or(imported.display_name);
+                player
+            }
+        };
+
+        if player.replay_data.is_some() {
+            self.state.replay_holders.insert(&wallet, player.high_score)?;
+        }
+        self.set_leaderboard_entry(wallet, player.high_score).await?;
+        self.state.players.insert(&wallet, player)?;
+
+        let log = self.state.trophies.load_entry_mut(&wallet).await?;
+        for trophy in trophies {
+            log.push(trophy);
+        }
+
+        self.prune_excess_replays().await?;
+        self.evict_beyond_leaderboard_cap().await?;
+        Ok(())
+    }
+
+    /// Profile an owner plays as: the profile it was linked to, or its own.
+    /// An archived profile is restored first, so callers find it in `players`.
+    async fn player_account(&mut self, owner: PlayerKey) -> Result<PlayerKey, ContractError> {
+        let account = self.state.linked_owners.get(&owner).await?.unwrap_or(owner);
+        self.restore_archived_player(account).await?;
+        Ok(account)
+    }
+
+    /// Add `wallet` to the roster `RebuildIndexes` walks, unless it already is
+    async fn enroll_player(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        if !self.state.rostered_players.contains(&wallet).await? {
+            self.state.rostered_players.insert(&wallet)?;
+            self.state.player_roster.push(wallet);
+        }
+        Ok(())
+    }
+
+    /// Move `wallet` back from the archive, if it is there, and index its
+    /// high score again
+    async fn restore_archived_player(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        let Some(player) = self.state.archived_players.get(&wallet).await? else {
+            return Ok(());
+        };
+        self.state.archived_players.remove(&wallet)?;
+        let stats = self.state.archive_stats.get_mut();
+        stats.archived_players = stats.archived_players.saturating_sub(1);
+        stats.games_played = stats.games_played.saturating_sub(player.games_played as u64);
+        stats.total_score = stats.total_score.saturating_sub(player.total_score);
+        stats.restored += 1;
+
+        let high_score = player.high_score;
+        self.state.players.insert(&wallet, player)?;
+        // Players archived when the roster was first filled aren't on it
+        self.enroll_player(wallet).await?;
+        self.set_leaderboard_entry(wallet, high_score).await?;
+        self.evict_beyond_leaderboard_cap().await
+    }
+
+    /// Archive up to `batch_size` players inactive for `ARCHIVE_AFTER`,
+    /// keeping the players map and the indexes built from it small. Their
+    /// replays are pruned; players whose activity is unknown are kept.
+    async fn archive_inactive_players(&mut self, batch_size: usize) -> Result<(), ContractError> {
+        let now = self.now();
+        let roster_len = self.state.player_roster.count();
+        let start = (self.state.archive_stats.get().cursor as usize).min(roster_len);
+        let end = start.saturating_add(batch_size).min(roster_len);
+        // The walk starts over from the top once it reaches the end of the roster
+        self.state.archive_stats.get_mut().cursor = if end < roster_len { end as u64 } else { 0 };
+
+        let mut inactive = Vec::new();
+        for wallet in self.state.player_roster.read(start..end).await? {
+            if let Some(player) = self.state.players.get(&wallet).await? {
+                let last_active_at = player.last_active_at();
+                if last_active_at != 0 && now.saturating_sub(last_active_at) >= ARCHIVE_AFTER {
+                    inactive.push(wallet);
+                }
+            }
+        }
+
+        for wallet in inactive {
+            self.prune_replay(&wallet).await?;
+            let Some(player) = self.state.players.get(&wallet).await? else {
+                continue;
+            };
+            self.state.players.remove(&wallet)?;
+            self.remove_leaderboard_entry(wallet).await?;
+            let stats = self.state.archive_stats.get_mut();
+            stats.archived_players += 1;
+            stats.games_played += player.games_played as u64;
+            stats.total_score += player.total_score;
+            stats.last_archived_at = Some(now);
+            self.state.archived_players.insert(&wallet, player)?;
+        }
+        Ok(())
+    }
+
+    /// Profile a score submission is attributed to: a session key submits for
+    /// the wallet that authorized it, anyone else for their own profile
+    async fn submitting_account(&mut self, signer: PlayerKey) -> Result<PlayerKey, ContractError> {
+        let owner = match self.state.session_keys.get(&signer).await? {
+            Some(session) if self.now() >= session.expires_at => {
+                return Err(ContractError::SessionKeyExpired);
+            }
+            Some(session) => session.owner,
+            None => signer,
+        };
+        self.player_account(owner).await
+    }
+
+    /// Append a privileged operation to the admin audit log
+    fn record_audit_entry(&mut self, action: &str, operation: &Operation) {
+        let actor = self
+            .runtime
+            .authenticated_signer()
+            .map(|owner| owner.to_string())
+            .unwrap_or_default();
+        let entry = AdminAuditEntry {
+            id: self.state.admin_audit_log.count() as u64,
+            actor,
+            action: action.to_string(),
+            parameters: serde_json::to_string(operation).unwrap_or_default(),
+            recorded_at: self.now(),
+        };
+        self.state.admin_audit_log.push(entry);
+    }
+
+    /// Count a submission against the player's hourly limit
+    fn check_rate_limit(&mut self, player: &mut PlayerData) -> Result<(), ContractError> {
+        let now = self.now();
+        if now >= player.submission_window_start.saturating_add(RATE_LIMIT_WINDOW) {
+            player.submission_window_start = now;
+            player.submissions_in_window = 0;
+        }
+        if player.submissions_in_window >= self.state.config.get().max_submissions_per_hour {
+            return Err(ContractError::RateLimited);
+        }
+        player.submissions_in_window += 1;
+        Ok(())
+    }
+
+    /// Drop players below `leaderboard_cap` from the leaderboard index and
+    /// prune their replays. Their player data is kept, and a later high score
+    /// brings them back. Featured replays are kept.
+    async fn evict_beyond_leaderboard_cap(&mut self) -> Result<(), ContractError> {
+        let cap = self.state.config.get().leaderboard_cap as usize;
+        let top = self.state.leaderboard_top.get();
+        if top.len() <= cap {
+            return Ok(());
+        }
+
+        let evicted = top.iter().skip(cap).map(|(wallet, _)| *wallet).collect::<Vec<_>>();
+        for wallet in evicted {
+            self.remove_leaderboard_entry(wallet).await?;
+            if self.state.featured_replays.contains_key(&wallet).await? {
+                continue;
+            }
+            self.prune_replay(&wallet).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the all-time records a game by `wallet` may have broken.
+    /// Players the leaderboard rules keep off don't hold records.
+    fn update_records(&mut self, wallet: &PlayerKey, player: &PlayerData) {
+        let rules = LeaderboardRules::from_config(self.state.config.get());
+        if rules.check_indexed(player).is_err() {
+            return;
+        }
+        let set_at = self.now();
+        let holder = |value: u64| RecordHolder {
+            wallet_address: wallet.to_string(),
+            value,
+            set_at,
+        };
+        let beats = |record: &Option<RecordHolder>, value: u64| {
+            record.as_ref().map_or(true, |record| value > record.value)
+        };
+
+        let records = self.state.records.get_mut();
+        if beats(&records.most_games_in_a_day, player.games_on_play_day as u64) {
+            records.most_games_in_a_day = Some(holder(player.games_on_play_day as u64));
+        }
+        if beats(&records.longest_streak, player.day_streak as u64) {
+            records.longest_streak = Some(holder(player.day_streak as u64));
+        }
+    }
+
+    /// Whether the leaderboard rules that only change with player data let
+    /// `wallet` rank and hold records. Players without data yet pass.
+    async fn passes_index_rules(&mut self, wallet: &PlayerKey) -> Result<bool, ContractError> {
+        let rules = LeaderboardRules::from_config(self.state.config.get());
+        let player = self.state.players.get(wallet).await?;
+        Ok(player.map_or(true, |player| rules.check_indexed(&player).is_ok()))
+    }
+
+    /// Put a run's score in the score indexes besides the leaderboard: the
+    /// all-time record, the bests of its day, challenge, season and events.
+    /// A provisional high score is indexed once verification confirms it.
+    async fn index_run_score(
+        &mut self,
+        wallet: PlayerKey,
+        run: &ScoredRun,
+    ) -> Result<(), ContractError> {
+        let score = run.score;
+        let previous_top = self.state.records.get().highest_score.clone();
+        let holds_records = self.passes_index_rules(&wallet).await?;
+        if holds_records && previous_top.as_ref().map_or(true, |top| score as u64 > top.value) {
+            self.state.records.get_mut().highest_score = Some(RecordHolder {
+                wallet_address: wallet.to_string(),
+                value: score as u64,
+                set_at: self.now(),
+            });
+        }
+
+        // A record taken from someone else (or the first one) makes a new #1
+        let previous_leader = previous_top.map(|top| (top.wallet_address, top.value));
+        let is_new_leader = holds_records
+            && previous_leader.as_ref().map_or(true, |(holder, value)| {
+                score as u64 > *value && *holder != wallet.to_string()
+            });
+        if is_new_leader {
+            // Let the previous record holder know they lost the top spot
+            let previous_holder = previous_leader.map(|(holder, _)| holder);
+            let holder = previous_holder.as_deref().and_then(|h| h.parse::<PlayerKey>().ok());
+            if let Some(holder) = holder {
+                let kind = NotificationKind::Dethroned;
+                self.notify(&holder, kind, Some(wallet), None).await?;
+            }
+            let chain_id = self.runtime.chain_id();
+            self.forward_to_notification_hub(HubEvent::NewLeader {
+                chain_id,
+                wallet_address: wallet.to_string(),
+                score,
+                previous_leader: previous_holder,
+            });
+        }
+
+        // A day whose top was already archived keeps it as archived
+        let day = run.submitted_at / SECONDS_PER_DAY;
+        if !self.state.daily_tops.contains_key(&day).await? {
+            let daily_scores = self.state.daily_scores.load_entry_mut(&day).await?;
+            if daily_scores.get(&wallet).await?.map_or(true, |best| score > best) {
+                daily_scores.insert(&wallet, score)?;
+            }
+        }
+        self.state.global_stats.get_mut().record_day_score(day, wallet, score);
+
+        if let Some(modifier) = run.modifier {
+            let key = (modifier, wallet);
+            let best = self.state.modifier_scores.get(&key).await?.unwrap_or(0);
+            if score > best {
+                self.state.modifier_scores.insert(&key, score)?;
+            }
+        }
+
+        let scope = ScoreScope::new(GameMode::Classic, run.season);
+        self.state.raise_scoped_best(scope, &wallet, score).await?;
+        self.record_event_scores(&wallet, score, run.submitted_at, false).await
+    }
+
+    /// Ensure the cause of death reported with a run is the one its replay recorded.
+    /// Replays from clients that don't record a cause are not checked.
+    fn check_death_cause(
+        &mut self,
+        replay_json: &str,
+        run_stats: Option<&RunStats>,
+    ) -> Result<(), ContractError> {
+        let recording = Recording::from_json(replay_json).ok_or(ContractError::InvalidReplay)?;
+        let reported = run_stats.and_then(|run_stats| run_stats.death_cause);
+        match recording.death_cause {
+            Some(recorded) if reported != Some(recorded) => Err(ContractError::DeathCauseMismatch),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count a finished run towards today's progress on every quest
+    async fn advance_quests(
+        &mut self,
+        player: &PlayerKey,
+        score: u32,
+        run_stats: Option<&RunStats>,
+    ) -> Result<(), ContractError> {
+        let today = self.now() / SECONDS_PER_DAY;
+        let duration_ms = run_stats.map_or(0, |run_stats| run_stats.duration_ms);
+        let coins = run_stats.map_or(0, |run_stats| run_stats.coins);
+
+        for quest_id in self.state.quests.indices().await? {
+            let Some(quest) = self.state.quests.get(&quest_id).await? else {
+                continue;
+            };
+            let key = (*player, quest_id);
+            let mut progress = self
+                .state
+                .quest_progress
+                .get(&key)
+                .await?
+                .filter(|progress| progress.day == today)
+                .unwrap_or(QuestProgress {
+                    day: today,
+                    ..QuestProgress::default()
+                });
+
+            progress.progress = match quest.goal {
+                QuestGoal::GamesPlayed => progress.progress + 1,
+                QuestGoal::ScoreInOneRun => progress.progress.max(score as u64),
+                QuestGoal::TotalScore => progress.progress + score as u64,
+                QuestGoal::Playtime => progress.progress + duration_ms,
+                QuestGoal::CoinsCollected => progress.progress.saturating_add(coins),
+            };
+            self.state.quest_progress.insert(&key, progress)?;
+        }
+
+        Ok(())
+    }
+
+    /// Drop `wallet`'s undrawn loot box committed at `committed_at` and pay
+    /// its price back from the prize pool
+    async fn refund_loot_box(
+        &mut self,
+        wallet: PlayerKey,
+        committed_at: u64,
+    ) -> Result<(), ContractError> {
+        let Some(commit) = self.state.loot_box_commits.get(&wallet).await? else {
+            return Ok(());
+        };
+        if commit.committed_at != committed_at || commit.seed.is_some() {
+            return Ok(());
+        }
+        self.state.loot_box_commits.remove(&wallet)?;
+        let pool = self
+            .state
+            .prize_pool
+            .get()
+            .try_sub(commit.price)
+            .map_err(|_| ContractError::InsufficientPrizePool)?;
+        self.state.prize_pool.set(pool);
+        let destination = Account {
+            chain_id: self.runtime.chain_id(),
+            owner: Some(wallet.0),
+        };
+        self.runtime.transfer(None, destination, commit.price);
+        Ok(())
+    }
+
+    /// Draw the seed of `wallet`'s loot box committed at `committed_at`,
+    /// chained with every seed drawn before on this chain
+    fn draw_loot_seed(&mut self, wallet: PlayerKey, committed_at: u64) -> String {
+        let seed = hex_digest(&[
+            self.state.loot_entropy.get().as_bytes(),
+            wallet.to_string().as_bytes(),
+            &committed_at.to_be_bytes(),
+            &self.runtime.block_height().0.to_be_bytes(),
+            &self.runtime.system_time().micros().to_be_bytes(),
+        ]);
+        self.state.loot_entropy.set(seed.clone());
+        seed
+    }
+
+    /// Queue a new high score for verification, superseding the player's
+    /// previous item if it was still open. Proven runs are settled right away.
+    async fn queue_verification(
+        &mut self,
+        wallet: &PlayerKey,
+        score: u32,
+        proven: bool,
+    ) -> Result<u64, ContractError> {
+        if let Some(previous_id) = self.state.verification_by_wallet.get(wallet).await? {
+            if let Some(mut previous) = self.state.verification_queue.get(&previous_id).await? {
+                if previous.status.is_open() {
+                    previous.status = VerificationStatus::Superseded;
+                    previous.claimed_by = None;
+                    self.state.verification_queue.insert(&previous_id, previous)?;
+                }
+            }
+        }
+
+        let id = *self.state.next_verification_id.get();
+        self.state.next_verification_id.set(id + 1);
+        let item = VerificationItem {
+            id,
+            wallet: wallet.to_string(),
+            score,
+            status: if proven {
+                VerificationStatus::Verified
+            } else {
+                VerificationStatus::Pending
+            },
+            claimed_by: None,
+            claimed_at: 0,
+            verdicts: Vec::new(),
+            queued_at: self.now(),
+        };
+        self.state.verification_queue.insert(&id, item)?;
+        self.state.verification_by_wallet.insert(wallet, id)?;
+
+        if proven {
+            if let Some(mut player) = self.state.players.get(wallet).await? {
+                self.remember_verified_best(*wallet, &mut player, id, score).await?;
+                self.state.players.insert(wallet, player)?;
+            }
+        }
+        Ok(id)
+    }
+
+    /// Record a verified high score of `player` and, while it is still their
+    /// high score, a copy of its replay for `invalidate_score` to fall back on
+    async fn remember_verified_best(
+        &mut self,
+        wallet: PlayerKey,
+        player: &mut PlayerData,
+        verification_id: u64,
+        score: u32,
+    ) -> Result<(), ContractError> {
+        let verified_at = self.now();
+        let Some(dropped) = record_verified_best(player, verification_id, score, verified_at)
+        else {
+            return Ok(());
+        };
+        let replays = self.state.verified_best_replays.load_entry_mut(&wallet).await?;
+        for id in dropped {
+            replays.remove(&id)?;
+        }
+        if player.high_score == score && player.replay_data.is_some() {
+            replays.insert(&verification_id, player.high_score_snapshot())?;
+        }
+        Ok(())
+    }
+
+    /// Mark `wallet`'s new high score provisional until verification item
+    /// `verification_id` confirms it, keeping `previous` to roll back to and
+    /// `run` to index on confirmation. Without `previous` the score is final.
+    async fn track_provisional(
+        &mut self,
+        wallet: PlayerKey,
+        verification_id: u64,
+        previous: Option<HighScoreSnapshot>,
+        run: ScoredRun,
+    ) -> Result<(), ContractError> {
+        let Some(previous) = previous else {
+            self.state.provisional_scores.remove(&wallet)?;
+            return Ok(());
+        };
+        let Some(mut player) = self.state.players.get(&wallet).await? else {
+            return Ok(());
+        };
+
+        let window = self.state.config.get().provisional_window.unwrap_or(0);
+        let confirm_by_height = self.runtime.block_height().0.saturating_add(window);
+        player.provisional = Some(ProvisionalHighScore {
+            verification_id,
+            confirm_by_height,
+            previous,
+            run: Some(run),
+        });
+        self.state.players.insert(&wallet, player)?;
+        self.state.provisional_scores.insert(&wallet, confirm_by_height)?;
+        Ok(())
+    }
+
+    /// Apply a settled verification item to its player: remember a verified
+    /// score as a verified best, and confirm or roll back the provisional
+    /// high score the item was queued for
+    async fn settle_verification(&mut self, item: &VerificationItem) -> Result<(), ContractError> {
+        let confirmed = match item.status {
+            VerificationStatus::Verified => true,
+            VerificationStatus::Rejected => false,
+            _ => return Ok(()),
+        };
+        let wallet = validation::validate_wallet_address(&item.wallet)?;
+        let Some(mut player) = self.state.players.get(&wallet).await? else {
+            return Ok(());
+        };
+        let pending = player.provisional.as_ref().map(|provisional| provisional.verification_id);
+        if !confirmed {
+            if pending == Some(item.id) {
+                self.roll_back_high_score(wallet).await?;
+            }
+            return Ok(());
+        }
+
+        self.remember_verified_best(wallet, &mut player, item.id, item.score).await?;
+        let mut confirmed_run = None;
+        if pending == Some(item.id) {
+            confirmed_run = player.provisional.take().and_then(|provisional| provisional.run);
+            self.state.provisional_scores.remove(&wallet)?;
+        }
+        if let Some(run) = &confirmed_run {
+            player.record_personal_best(run);
+        }
+        self.state.players.insert(&wallet, player)?;
+        if let Some(run) = confirmed_run {
+            if self.leaderboard_frozen().await? {
+                self.state.held_runs.push_back((wallet, run));
+            } else {
+                self.index_run_score(wallet, &run).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Revert `wallet`'s high score to their previous verified best, drop its
+    /// replay and log the invalidation
+    async fn invalidate_score(
+        &mut self,
+        wallet: PlayerKey,
+        reason: String,
+    ) -> Result<(), ContractError> {
+        let mut player = self
+            .state
+            .players
+            .get(&wallet)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)?;
+        let score = player.high_score;
+        if score == 0 {
+            return Err(ContractError::NoScoreToInvalidate);
+        }
+        let removed = player.high_score_snapshot();
+
+        // Fall back on the best verified score below it, with its replay if
+        // one was kept
+        let (removed_bests, kept_bests) = std::mem::take(&mut player.verified_bests)
+            .into_iter()
+            .partition::<Vec<_>, _>(|best| best.score >= score);
+        player.verified_bests = kept_bests;
+        let restored = player.verified_bests.last().cloned();
+        let restored_score = restored.as_ref().map_or(0, |best| best.score);
+        let replays = self.state.verified_best_replays.load_entry_mut(&wallet).await?;
+        for best in removed_bests {
+            replays.remove(&best.verification_id)?;
+        }
+        let kept_replay = match &restored {
+            Some(best) => replays.get(&best.verification_id).await?,
+            None => None,
+        };
+        let snapshot = kept_replay.unwrap_or(HighScoreSnapshot {
+            high_score: restored_score,
+            replay_data: None,
+            replay_compression: ReplayCompression::None,
+            replay_meta: None,
+            replay_format_version: 0,
+            replay_summary: None,
+            replay_origin: None,
+        });
+        let has_replay = snapshot.replay_data.is_some();
+        player.restore_high_score(snapshot);
+        player.provisional = None;
+        player.personal_bests.retain(|best| best.score != score);
+        self.state.players.insert(&wallet, player)?;
+        self.state.provisional_scores.remove(&wallet)?;
+        if has_replay {
+            self.state.replay_holders.insert(&wallet, restored_score)?;
+        } else {
+            self.state.replay_holders.remove(&wallet)?;
+        }
+
+        // The score's open verification has nothing left to check
+        if let Some(id) = self.state.verification_by_wallet.get(&wallet).await? {
+            if let Some(mut item) = self.state.verification_queue.get(&id).await? {
+                if item.score == score && item.status.is_open() {
+                    item.status = VerificationStatus::Rejected;
+                    item.claimed_by = None;
+                    self.state.verification_queue.insert(&id, item)?;
+                }
+            }
+        }
+
+        if restored_score > 0 {
+            self.set_leaderboard_entry(wallet, restored_score).await?;
+        } else {
+            self.remove_leaderboard_entry(wallet).await?;
+        }
+        self.publish_shard_top(Some(wallet)).await?;
+
+        let id = self.state.score_invalidations.count() as u64;
+        let invalidation = ScoreInvalidation {
+            id,
+            wallet_address: wallet,
+            score,
+            restored_score,
+            reason,
+            invalidated_by: self.signer()?.to_string(),
+            invalidated_at: self.now(),
+        };
+        self.state.score_invalidations.push(invalidation);
+        self.state.invalidated_high_scores.insert(&id, removed)?;
+        // Older ids go first, in case the migration hasn't reached the wallet
+        self.migrate_wallet_invalidations(wallet).await?;
+        self.state.wallet_invalidations.load_entry_mut(&wallet).await?.push(id);
+        Ok(())
+    }
+
+    /// Give `wallet` back a high score removed by an invalidation, unless
+    /// they have since beaten it
+    async fn restore_invalidated_score(
+        &mut self,
+        wallet: PlayerKey,
+        snapshot: HighScoreSnapshot,
+    ) -> Result<(), ContractError> {
+        let Some(mut player) = self.state.players.get(&wallet).await? else {
+            return Ok(());
+        };
+        if snapshot.high_score <= player.high_score {
+            return Ok(());
+        }
+
+        let high_score = snapshot.high_score;
+        let has_replay = snapshot.replay_data.is_some();
+        player.restore_high_score(snapshot);
+        player.provisional = None;
+        self.state.players.insert(&wallet, player)?;
+        self.state.provisional_scores.remove(&wallet)?;
+
+        if has_replay {
+            self.state.replay_holders.insert(&wallet, high_score)?;
+        }
+        self.set_leaderboard_entry(wallet, high_score).await?;
+        self.evict_beyond_leaderboard_cap().await?;
+        self.publish_shard_top(Some(wallet)).await
+    }
+
+    /// Roll back up to `MAX_PROVISIONAL_EXPIRED_PER_RUN` provisional high
+    /// scores whose confirmation deadline passed. Returns whether expired
+    /// ones remain.
+    async fn expire_provisional_scores(&mut self) -> Result<bool, ContractError> {
+        let height = self.runtime.block_height().0;
+        let mut expired = Vec::new();
+        for wallet in self.state.provisional_scores.indices().await? {
+            let deadline = self.state.provisional_scores.get(&wallet).await?;
+            if deadline.is_some_and(|deadline| deadline < height) {
+                if expired.len() == MAX_PROVISIONAL_EXPIRED_PER_RUN {
+                    break;
+                }
+                expired.push(wallet);
+            }
+        }
+        let more = expired.len() == MAX_PROVISIONAL_EXPIRED_PER_RUN;
+        for wallet in expired {
+            self.roll_back_high_score(wallet).await?;
+        }
+        Ok(more)
+    }
+
+    /// Restore `wallet`'s last confirmed high score and replay in place of
+    /// their provisional one, and move them back on the leaderboard
+    async fn roll_back_high_score(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        self.state.provisional_scores.remove(&wallet)?;
+        let Some(mut player) = self.state.players.get(&wallet).await? else {
+            return Ok(());
+        };
+        let Some(provisional) = player.provisional.take() else {
+            return Ok(());
+        };
+
+        player.restore_high_score(provisional.previous);
+        let high_score = player.high_score;
+        let has_replay = player.replay_data.is_some();
+        self.state.players.insert(&wallet, player)?;
+
+        if has_replay {
+            self.state.replay_holders.insert(&wallet, high_score)?;
+        } else {
+            self.state.replay_holders.remove(&wallet)?;
+        }
+        if high_score > 0 {
+            self.set_leaderboard_entry(wallet, high_score).await?;
+        } else {
+            self.remove_leaderboard_entry(wallet).await?;
+        }
+        self.publish_shard_top(Some(wallet)).await
+    }
+
+    /// Check a run proof against the replay and the current game config.
+    /// Returns false when no proof system is configured, so the run is
+    /// verified the usual way; a bad proof rejects the submission.
+    fn check_run_proof(
+        &mut self,
+        replay_json: &str,
+        score: u32,
+        proof: &str,
+    ) -> Result<bool, ContractError> {
+        let bytes = proof::decode_proof(proof)?;
+        let statement = ProofStatement {
+            replay_digest: hex_digest(&[replay_json.as_bytes()]),
+            score,
+            config_version: *self.state.config_version.get(),
+        };
+        match proof::active_verifier().verify(&bytes, &statement) {
+            Ok(()) => Ok(true),
+            Err(ProofError::Unsupported) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Block the score being handled was submitted in: the origin block of a
+    /// forwarded message, or the block executing the operation
+    fn replay_origin(&mut self) -> ReplayOrigin {
+        let (chain_id, block_height) = match self.runtime.message_id() {
+            Some(message_id) => (message_id.chain_id, message_id.height.0),
+            None => (self.runtime.chain_id(), self.runtime.block_height().0),
+        };
+        ReplayOrigin {
+            chain_id,
+            block_height,
+            signer: self.runtime.authenticated_signer().map(|owner| owner.to_string()),
+        }
+    }
+
+    /// Index `wallet`'s high score on the leaderboard, logging the change.
+    /// A player the leaderboard rules keep off is dropped from the index
+    /// instead.
+    async fn set_leaderboard_entry(
+        &mut self,
+        wallet: PlayerKey,
+        high_score: u32,
+    ) -> Result<(), ContractError> {
+        if let Some(player) = self.state.players.get(&wallet).await? {
+            let rules = LeaderboardRules::from_config(self.state.config.get());
+            if rules.check_indexed(&player).is_err() {
+                return self.remove_leaderboard_entry(wallet).await;
+            }
+        }
+        let kind = match self.state.leaderboard_index.get(&wallet).await? {
+            Some(indexed) if indexed == high_score => return Ok(()),
+            Some(_) => LeaderboardChangeKind::Updated,
+            None => LeaderboardChangeKind::Added,
+        };
+        self.state.leaderboard_index.insert(&wallet, high_score)?;
+        if self.place_in_leaderboard_top(wallet, Some(high_score)) {
+            self.update_leaderboard_commitment();
+        }
+        self.log_leaderboard_change(kind, wallet, Some(high_score));
+        self.publish_leaderboard_delta(wallet).await
+    }
+
+    /// Apply the leaderboard rules to `wallet`'s index entry after their
+    /// eligibility changed. Players who never scored stay unindexed.
+    async fn reindex_player(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        let Some(player) = self.state.players.get(&wallet).await? else {
+            return Ok(());
+        };
+        if player.games_played == 0 {
+            return Ok(());
+        }
+        self.set_leaderboard_entry(wallet, player.high_score).await?;
+        self.evict_beyond_leaderboard_cap().await
+    }
+
+    /// Drop `wallet` from the leaderboard index, logging the change
+    async fn remove_leaderboard_entry(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        if self.state.leaderboard_index.contains_key(&wallet).await? {
+            self.state.leaderboard_index.remove(&wallet)?;
+            if self.place_in_leaderboard_top(wallet, None) {
+                self.update_leaderboard_commitment();
+            }
+            self.log_leaderboard_change(LeaderboardChangeKind::Removed, wallet, None);
+            self.publish_leaderboard_delta(wallet).await?;
+        }
+        Ok(())
+    }
+
+    /// Move `wallet` to the place of `high_score` in the sorted leaderboard
+    /// top, or out of it. Returns whether the committed entries changed.
+    fn place_in_leaderboard_top(&mut self, wallet: PlayerKey, high_score: Option<u32>) -> bool {
+        let top = self.state.leaderboard_top.get_mut();
+        let previous = top.iter().position(|(entry, _)| *entry == wallet);
+        if let Some(previous) = previous {
+            top.remove(previous);
+        }
+        let placed = high_score.map(|high_score| {
+            let at = top.partition_point(|(entry, score)| {
+                (Reverse(*score), *entry) < (Reverse(high_score), wallet)
+            });
+            top.insert(at, (wallet, high_score));
+            at
+        });
+        [previous, placed].into_iter().flatten().any(|rank| rank < COMMITMENT_SIZE)
+    }
+
+    /// Append to the leaderboard change log read by `leaderboardChanges`
+    fn log_leaderboard_change(
+        &mut self,
+        kind: LeaderboardChangeKind,
+        wallet: PlayerKey,
+        high_score: Option<u32>,
+    ) {
+        let change = LeaderboardChange {
+            seq: self.state.leaderboard_changes.count() as u64,
+            kind,
+            wallet_address: wallet,
+            high_score,
+            changed_at: self.now(),
+        };
+        self.state.leaderboard_changes.push(change);
+    }
+
+    /// Add a notification to `wallet`'s inbox, dropping the oldest past
+    /// `MAX_NOTIFICATIONS`
+    async fn notify(
+        &mut self,
+        wallet: &PlayerKey,
+        kind: NotificationKind,
+        other_player: Option<PlayerKey>,
+        subject_id: Option<u64>,
+    ) -> Result<(), ContractError> {
+        let id = *self.state.next_notification_id.get();
+        self.state.next_notification_id.set(id + 1);
+        let notification = Notification {
+            id,
+            kind,
+            other_player,
+            subject_id,
+            created_at: self.now(),
+        };
+
+        let inbox = self.state.notifications.load_entry_mut(wallet).await?;
+        inbox.push_back(notification);
+        while inbox.count() > MAX_NOTIFICATIONS {
+            inbox.delete_front();
+        }
+        Ok(())
+    }
+
+    /// Forward a notable event to the notification hub application, if one is
+    /// configured. The event is sent as a message to the chain the hub was
+    /// created on, which passes it on in a block of its own, so a failing or
+    /// slow hub never holds up the operation that caused the event.
+    fn forward_to_notification_hub(&mut self, event: HubEvent) {
+        let Some(hub) = self.state.config.get().notification_hub else {
+            return;
+        };
+        self.runtime
+            .prepare_message(Message::NotifyHub { event })
+            .send_to(hub.creation.chain_id);
+    }
+
+    /// Reject a score or account routed to this hub under another shard
+    /// assignment, or meant for another shard. A rejected score bounces back
+    /// and is resent under the sending chain's current assignment.
+    fn check_shard(&mut self, wallet: &PlayerKey, shard_epoch: u32) -> Result<(), ContractError> {
+        let config = self.state.config.get();
+        if config.hub_shards.is_empty() {
+            return Ok(());
+        }
+        if shard_epoch != config.shard_epoch
+            || config.hub_for(wallet) != Some(self.runtime.chain_id())
+        {
+            return Err(ContractError::WrongShard);
+        }
+        Ok(())
+    }
+
+    /// Hand up to `batch_size` accounts this shard hub no longer holds to
+    /// their new shard. Handed-off accounts leave `players`, so the next
+    /// batch picks up the rest.
+    async fn rebalance_shard(&mut self, batch_size: usize) -> Result<(), ContractError> {
+        if self.state.config.get().hub_shards.is_empty() {
+            return Ok(());
+        }
+
+        let chain_id = self.runtime.chain_id();
+        let roster_len = self.state.player_roster.count();
+        let cursor = self.state.rebalance_cursor.get().unwrap_or(0);
+        let start = (cursor as usize).min(roster_len);
+        let end = start.saturating_add(batch_size).min(roster_len);
+        self.state.rebalance_cursor.set((end < roster_len).then_some(end as u64));
+
+        let mut moves = Vec::new();
+        for wallet in self.state.player_roster.read(start..end).await? {
+            // The roster keeps the wallets already handed off
+            if !self.state.players.contains_key(&wallet).await? {
+                continue;
+            }
+            match self.state.config.get().hub_for(&wallet) {
+                Some(shard) if shard != chain_id => moves.push((wallet, shard)),
+                _ => {}
+            }
+        }
+
+        let shard_epoch = self.state.config.get().shard_epoch;
+        for (wallet, shard) in moves {
+            let (player, trophies) = self.take_account(wallet).await?;
+            let migration_id = *self.state.next_migration_id.get();
+            self.state.next_migration_id.set(migration_id + 1);
+            self.runtime
+                .prepare_message(Message::ShardHandoff {
+                    migration_id,
+                    shard_epoch,
+                    wallet,
+                    player,
+                    trophies,
+                })
+                .with_tracking()
+                .send_to(shard);
+        }
+
+        self.publish_shard_top(None).await
+    }
+
+    /// Report this shard hub's top list to the coordinator. With `changed`,
+    /// only when that wallet made the list.
+    async fn publish_shard_top(&mut self, changed: Option<PlayerKey>) -> Result<(), ContractError> {
+        let chain_id = self.runtime.chain_id();
+        let config = self.state.config.get();
+        let Some(coordinator) = config.shard_coordinator else {
+            return Ok(());
+        };
+        if !config.hub_shards.contains(&chain_id) {
+            return Ok(());
+        }
+        let shard_epoch = config.shard_epoch;
+
+        let entries = self
+            .state
+            .leaderboard_top
+            .get()
+            .iter()
+            .take(SHARD_TOP_N)
+            .map(|(wallet, high_score)| ShardTopEntry {
+                wallet_address: *wallet,
+                high_score: *high_score,
+            })
+            .collect::<Vec<_>>();
+        if let Some(changed) = changed {
+            if !entries.iter().any(|entry| entry.wallet_address == changed) {
+                return Ok(());
+            }
+        }
+
+        self.runtime
+            .prepare_message(Message::ShardTop {
+                shard_epoch,
+                entries,
+            })
+            .send_to(coordinator);
+        Ok(())
+    }
+
+    /// Send `wallet`'s current leaderboard data to the chains mirroring this one
+    async fn publish_leaderboard_delta(&mut self, wallet: PlayerKey) -> Result<(), ContractError> {
+        let player = if self.state.leaderboard_index.contains_key(&wallet).await? {
+            self.state.players.get(&wallet).await?.as_ref().map(MirroredPlayer::from)
+        } else {
+            None
+        };
+        let channel = ChannelName::from(MIRROR_CHANNEL.to_vec());
+        self.runtime
+            .prepare_message(Message::LeaderboardDelta { wallet, player })
+            .send_to(Destination::Subscribers(channel));
+        Ok(())
+    }
+
+    /// Attest that `wallet`'s score was accepted in the current block, and
+    /// emit the attestation on the score attestation stream
+    fn attest_score(
+        &mut self,
+        wallet: PlayerKey,
+        score: u32,
+        timestamp: u64,
+        submission_id: String,
+        replay_digest: Option<String>,
+        replay_quota_remaining: u64,
+    ) -> ScoreAttestation {
+        let chain_id = self.runtime.chain_id();
+        let block_height = self.runtime.block_height().0;
+        let digest = hex_digest(&[
+            chain_id.to_string().as_bytes(),
+            &block_height.to_be_bytes(),
+            wallet.to_string().as_bytes(),
+            &score.to_be_bytes(),
+            &timestamp.to_be_bytes(),
+            replay_digest.as_deref().unwrap_or_default().as_bytes(),
+            submission_id.as_bytes(),
+        ]);
+        let attestation = ScoreAttestation {
+            chain_id,
+            block_height,
+            wallet_address: wallet,
+            score,
+            timestamp,
+            submission_id,
+            replay_digest,
+            digest,
+            replay_quota_remaining,
+        };
+
+        let event = serde_json::to_vec(&attestation).unwrap_or_default();
+        self.runtime.emit(
+            StreamName(SCORE_ATTESTATION_STREAM.to_vec()),
+            attestation.digest.as_bytes(),
+            &event,
+        );
+        attestation
+    }
+
+    /// Recompute the commitment from the first `COMMITMENT_SIZE` entries of
+    /// the sorted leaderboard top
+    fn update_leaderboard_commitment(&mut self) {
+        let top = self.state.leaderboard_top.get();
+        let entries = top.iter().take(COMMITMENT_SIZE).copied().collect::<Vec<_>>();
+        let root = commitment::to_hex(&commitment::merkle_root(&commitment::leaves(&entries)));
+        if self.state.leaderboard_commitment.get().root == root {
+            return;
+        }
+
+        let commitment = LeaderboardCommitment {
+            root,
+            size: entries.len() as u32,
+            block_height: self.runtime.block_height().0,
+            updated_at: self.now(),
+        };
+        self.state.leaderboard_commitment.set(commitment);
+    }
+
+    /// Rebuild the derived indexes of the next `batch_size` players of the
+    /// roster, from the saved cursor. The batch that reaches the end drops
+    /// index entries of players that no longer exist and replaces the death
+    /// tally.
+    async fn rebuild_indexes(&mut self, batch_size: usize) -> Result<(), ContractError> {
+        let mut progress = match self.state.index_rebuild.get().clone() {
+            Some(progress) => progress,
+            None => IndexRebuild {
+                started_at: self.now(),
+                ..IndexRebuild::default()
+            },
+        };
+
+        let roster_len = self.state.player_roster.count();
+        let start = (progress.cursor as usize).min(roster_len);
+        let end = start.saturating_add(batch_size).min(roster_len);
+        let batch = self.state.player_roster.read(start..end).await?;
+
+        for wallet in &batch {
+            let Some(mut player) = self.state.players.get(wallet).await? else {
+                continue;
+            };
+            // Scores verified before verified bests were kept only show in the queue
+            if player.verified_bests.is_empty() {
+                if let Some(id) = self.state.verification_by_wallet.get(wallet).await? {
+                    let item = self.state.verification_queue.get(&id).await?;
+                    if let Some(item) = item.filter(|item| {
+                        item.status == VerificationStatus::Verified
+                            && item.score == player.high_score
+                    }) {
+                        self.remember_verified_best(*wallet, &mut player, id, item.score).await?;
+                        self.state.players.insert(wallet, player.clone())?;
+                    }
+                }
+            }
+            self.set_leaderboard_entry(*wallet, player.high_score).await?;
+            if player.replay_data.is_some() {
+                self.state.replay_holders.insert(wallet, player.high_score)?;
+            }
+            progress.death_tally.merge(&player.death_tally);
+            progress.games_played += player.games_played as u64;
+            progress.total_score += player.total_score;
+            progress.processed += 1;
+        }
+        progress.cursor = end as u64;
+
+        if end == roster_len {
+            for wallet in self.state.leaderboard_index.indices().await? {
+                if !self.state.players.contains_key(&wallet).await? {
+                    self.remove_leaderboard_entry(wallet).await?;
+                }
+            }
+            for wallet in self.state.replay_holders.indices().await? {
+                if !self.state.players.contains_key(&wallet).await? {
+                    self.state.replay_holders.remove(&wallet)?;
+                }
+            }
+            self.state.death_tally.set(progress.death_tally);
+            // Coins collected aren't kept per player, so that total stays as
+            // counted; archived players are added from the archive's totals
+            let archived = self.state.archive_stats.get().clone();
+            let stats = self.state.global_stats.get_mut();
+            stats.total_players = progress.processed + archived.archived_players;
+            stats.total_runs = progress.games_played + archived.games_played;
+            stats.total_distance = progress.total_score + archived.total_score;
+            self.evict_beyond_leaderboard_cap().await?;
+            self.state.index_rebuild.set(None);
+        } else {
+            self.state.index_rebuild.set(Some(progress));
+        }
+        Ok(())
+    }
+
+    /// Bring state stored by an older version up to `STATE_SCHEMA_VERSION`,
+    /// `MIGRATION_BATCH` players per block
+    async fn migrate_state(&mut self) -> Result<(), ContractError> {
+        let version = *self.state.schema_version.get();
+        if version >= STATE_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        if version < 2 {
+            // The roster is filled from the players map once; it is kept up
+            // to date from then on
+            if self.state.player_roster.count() == 0 {
+                for wallet in self.state.players.indices().await? {
+                    self.enroll_player(wallet).await?;
+                }
+            }
+            // The sorted top starts from what the index holds, then players
+            // who scored before the index existed are indexed from their data
+            if self.state.leaderboard_top.get().is_empty() {
+                let mut top = Vec::new();
+                for wallet in self.state.leaderboard_index.indices().await? {
+                    if let Some(high_score) = self.state.leaderboard_index.get(&wallet).await? {
+                        top.push((wallet, high_score));
+                    }
+                }
+                top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                self.state.leaderboard_top.set(top);
+            }
+            self.rebuild_indexes(MIGRATION_BATCH).await?;
+            if self.state.index_rebuild.get().is_some() {
+                return Ok(());
+            }
+        }
+
+        if version < 3 && self.migrate_replay_stats(MIGRATION_BATCH).await? {
+            return Ok(());
+        }
+
+        if version < 4 && self.migrate_invalidations(MIGRATION_BATCH).await? {
+            return Ok(());
+        }
+
+        if version < 5 && self.migrate_rewards(MIGRATION_BATCH).await? {
+            return Ok(());
+        }
+
+        if version < 6 && self.migrate_inventory(MIGRATION_BATCH).await? {
+            return Ok(());
+        }
+
+        if version < 7 && self.migrate_replay_reactions(MIGRATION_BATCH).await? {
+            return Ok(());
+        }
+
+        self.state.schema_version.set(STATE_SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Move up to `batch_size` replay counters keyed by the wallet as it was
+    /// sent to their wallet's key. Counters of the same wallet written
+    /// differently are merged. Returns whether some are left.
+    async fn migrate_replay_stats(&mut self, batch_size: usize) -> Result<bool, ContractError> {
+        let wallets = self.state.legacy_replay_stats.indices().await?;
+        let remaining = wallets.len().saturating_sub(batch_size);
+        for wallet in wallets.into_iter().take(batch_size) {
+            let stats = self.state.legacy_replay_stats.get(&wallet).await?;
+            self.state.legacy_replay_stats.remove(&wallet)?;
+            let (Some(stats), Ok(key)) = (stats, wallet.parse::<PlayerKey>()) else {
+                continue;
+            };
+            // Only counters of the latest replay are kept, as likes and views reset with it
+            let merged = match self.state.replay_stats.get(&key).await? {
+                Some(current) if current.replay_score == stats.replay_score => ReplayStats {
+                    replay_score: current.replay_score,
+                    likes: current.likes + stats.likes,
+                    views: current.views + stats.views,
+                },
+                Some(current) if current.replay_score > stats.replay_score => current,
+                _ => stats,
+            };
+            self.state.replay_stats.insert(&key, merged)?;
+        }
+        Ok(remaining > 0)
+    }
+
+    /// Queue the appeals filed before `appeal_log` existed, oldest first, then
+    /// move up to `batch_size` wallets' invalidation ids to their logs.
+    /// Returns whether wallets are left to move.
+    async fn migrate_invalidations(&mut self, batch_size: usize) -> Result<bool, ContractError> {
+        if self.state.appeal_log.count() == 0 {
+            let mut filed = Vec::new();
+            for score_id in self.state.appeals.indices().await? {
+                if let Some(appeal) = self.state.appeals.get(&score_id).await? {
+                    filed.push((appeal.filed_at, score_id));
+                }
+            }
+            filed.sort_unstable();
+            for (_, score_id) in filed {
+                self.state.appeal_log.push(score_id);
+            }
+            self.skip_resolved_appeals().await?;
+        }
+
+        let wallets = self.state.legacy_invalidations_by_wallet.indices().await?;
+        let remaining = wallets.len().saturating_sub(batch_size);
+        for wallet in wallets.into_iter().take(batch_size) {
+            self.migrate_wallet_invalidations(wallet).await?;
+        }
+        Ok(remaining > 0)
+    }
+
+    /// Move up to `batch_size` rewards naming the wallet as it was sent to
+    /// `rewards`, keyed by the wallet's key. A reward whose wallet isn't valid
+    /// could never be claimed and goes back to the prize pool. Returns whether
+    /// some are left.
+    async fn migrate_rewards(&mut self, batch_size: usize) -> Result<bool, ContractError> {
+        let reward_ids = self.state.legacy_rewards.indices().await?;
+        let remaining = reward_ids.len().saturating_sub(batch_size);
+        for reward_id in reward_ids.into_iter().take(batch_size) {
+            let Some(reward) = self.state.legacy_rewards.get(&reward_id).await? else {
+                continue;
+            };
+            self.state.legacy_rewards.remove(&reward_id)?;
+            let Ok(wallet) = validation::validate_wallet_address(&reward.wallet_address) else {
+                let pool = self.state.prize_pool.get().saturating_add(reward.amount);
+                self.state.prize_pool.set(pool);
+                continue;
+            };
+            let reward = ClaimableReward {
+                wallet_address: wallet,
+                amount: reward.amount,
+                source: reward.source,
+                source_id: reward.source_id,
+                granted_at: reward.granted_at,
+                expires_at: reward.expires_at,
+            };
+            self.state.rewards.insert(&reward_id, reward)?;
+            let mut reward_ids = self.state.wallet_rewards.get(&wallet).await?.unwrap_or_default();
+            reward_ids.push(reward_id);
+            self.state.wallet_rewards.insert(&wallet, reward_ids)?;
+        }
+        Ok(remaining > 0)
+    }
+
+    /// Move up to `batch_size` inventory counts keyed by (player, item) to the
+    /// player's own map in `inventory`. Returns whether some are left.
+    async fn migrate_inventory(&mut self, batch_size: usize) -> Result<bool, ContractError> {
+        let keys = self.state.legacy_inventory.indices().await?;
+        let remaining = keys.len().saturating_sub(batch_size);
+        for key in keys.into_iter().take(batch_size) {
+            let count = self.state.legacy_inventory.get(&key).await?.unwrap_or(0);
+            self.state.legacy_inventory.remove(&key)?;
+            let (player, item) = key;
+            let items = self.state.inventory.load_entry_mut(&player).await?;
+            let owned = items.get(&item).await?.unwrap_or(0);
+            items.insert(&item, owned.saturating_add(count))?;
+        }
+        Ok(remaining > 0)
+    }
+
+    /// Move up to `batch_size` replay likes, then views, keyed by the wallets
+    /// as they were sent to their wallets' keys. Entries naming an invalid
+    /// wallet are dropped. Returns whether some are left.
+    async fn migrate_replay_reactions(&mut self, batch_size: usize) -> Result<bool, ContractError> {
+        let likes = self.state.legacy_replay_likes.indices().await?;
+        let views = self.state.legacy_replay_views.indices().await?;
+        let remaining = (likes.len() + views.len()).saturating_sub(batch_size);
+
+        let mut budget = batch_size;
+        for like in likes.into_iter().take(budget) {
+            self.state.legacy_replay_likes.remove(&like)?;
+            budget -= 1;
+            if let Some(like) = reaction_key(&like) {
+                self.state.replay_likes.insert(&like)?;
+            }
+        }
+        for view in views.into_iter().take(budget) {
+            self.state.legacy_replay_views.remove(&view)?;
+            if let Some(view) = reaction_key(&view) {
+                self.state.replay_views.insert(&view)?;
+            }
+        }
+        Ok(remaining > 0)
+    }
+
+    /// Move `wallet`'s invalidation ids stored before schema version 4 to
+    /// its log in `wallet_invalidations`
+    async fn migrate_wallet_invalidations(
+        &mut self,
+        wallet: PlayerKey,
+    ) -> Result<(), ContractError> {
+        let Some(ids) = self.state.legacy_invalidations_by_wallet.get(&wallet).await? else {
+            return Ok(());
+        };
+        self.state.legacy_invalidations_by_wallet.remove(&wallet)?;
+        let log = self.state.wallet_invalidations.load_entry_mut(&wallet).await?;
+        for id in ids {
+            log.push(id);
+        }
+        Ok(())
+    }
+
+    /// Move `first_pending_appeal` past the appeals resolved since, so the
+    /// pending queue starts at the oldest appeal still waiting
+    async fn skip_resolved_appeals(&mut self) -> Result<(), ContractError> {
+        let mut position = *self.state.first_pending_appeal.get();
+        while let Some(score_id) = self.state.appeal_log.get(position as usize).await? {
+            let appeal = self.state.appeals.get(&score_id).await?;
+            if appeal.is_some_and(|appeal| appeal.status == AppealStatus::Pending) {
+                break;
+            }
+            position += 1;
+        }
+        self.state.first_pending_appeal.set(position);
+        Ok(())
+    }
+
+    /// Add an executed operation to the metrics of its type. Measuring the
+    /// storage entries it touched means flushing a copy of the pending state
+    /// into a batch that is never written, which costs about as much as the
+    /// operation, so only one in `op_metrics_sample_interval` executions is
+    /// measured, and only when it is the first operation of its block, whose
+    /// pending changes are its own.
+    async fn record_op_metrics(
+        &mut self,
+        name: &'static str,
+        replay_bytes: u64,
+    ) -> Result<(), ContractError> {
+        let block_height = self.runtime.block_height().0;
+        let first_in_block = *self.state.last_operation_height.get() != Some(block_height);
+        self.state.last_operation_height.set(Some(block_height));
+
+        let mut metrics = self.state.op_metrics.get(name).await?.unwrap_or_default();
+        let interval = self.state.config.get().op_metrics_sample_interval;
+        let sampled = first_in_block && interval > 0 && metrics.executions % interval == 0;
+        let entries_touched = if sampled {
+            let mut pending = self.state.clone_unchecked()?;
+            let mut batch = Batch::new();
+            pending.flush(&mut batch)?;
+            Some(batch.operations.len() as u64)
+        } else {
+            None
+        };
+        metrics.record(replay_bytes, entries_touched, block_height);
+        self.state.op_metrics.insert(name, metrics)?;
+        Ok(())
+    }
+
+    /// Move the submission fee, if any, from `payer` into the prize pool of
+    /// `wallet`'s hub, which pays the rewards. A payer without the funds fails
+    /// the transfer, and with it the block.
+    fn charge_submission_fee(&mut self, payer: Owner, wallet: PlayerKey) {
+        let fee = self.state.config.get().submission_fee;
+        if fee == Amount::ZERO {
+            return;
+        }
+        let chain_id = self.runtime.chain_id();
+        let hub = self.state.config.get().hub_for(&wallet).unwrap_or(chain_id);
+        let application_account = Account {
+            chain_id: hub,
+            owner: None,
+        };
+        self.runtime.transfer(Some(payer), application_account, fee);
+        if hub == chain_id {
+            let pool = self.state.prize_pool.get().saturating_add(fee);
+            self.state.prize_pool.set(pool);
+        } else {
+            self.runtime.prepare_message(Message::SubmissionFee { amount: fee }).send_to(hub);
+        }
+    }
+
+    /// Check a relayed run of `player` is submitted in time, on the chain it
+    /// was signed for, and that the chain is the player's hub. Relayed runs
+    /// are not forwarded, as hub messages carry the relayer's authentication.
+    fn check_relayed_run(
+        &mut self,
+        player: PlayerKey,
+        run: &RelayedRun,
+    ) -> Result<(), ContractError> {
+        let now = self.now();
+        if now > run.expires_at || run.expires_at > now.saturating_add(MAX_RELAYED_RUN_LIFETIME) {
+            return Err(ContractError::RelayedRunExpired);
+        }
+        let chain_id = self.runtime.chain_id();
+        if run.chain_id != chain_id {
+            return Err(ContractError::WrongRelayChain);
+        }
+        if self.state.config.get().hub_for(&player).is_some_and(|hub| hub != chain_id) {
+            return Err(ContractError::RelayOffHub);
+        }
+        Ok(())
+    }
+
+    /// Whether accepted scores are held back from the leaderboard and the
+    /// score indexes: while a freeze is active, and after it until every
+    /// update it held back is applied, so they keep their order. A freeze
+    /// whose window has passed is lifted here, so the scores it held back
+    /// don't wait for `UnfreezeLeaderboard`.
+    async fn leaderboard_frozen(&mut self) -> Result<bool, ContractError> {
+        let now = self.now();
+        if let Some(freeze) = self.state.leaderboard_freeze.get() {
+            if freeze.is_active(now) {
+                return Ok(true);
+            }
+            self.state.leaderboard_freeze.set(None);
+        }
+        self.apply_held_updates().await
+    }
+
+    /// Lift the leaderboard freeze and start applying the updates it held back
+    async fn unfreeze_leaderboard(&mut self) -> Result<(), ContractError> {
+        self.state.leaderboard_freeze.set(None);
+        self.apply_held_updates().await?;
+        Ok(())
+    }
+
+    /// Whether updates held back by a freeze are waiting to be applied
+    async fn held_updates_pending(&self) -> Result<bool, ContractError> {
+        Ok(self.state.held_runs.count() > 0
+            || self.state.frozen_leaderboard_updates.count().await? > 0)
+    }
+
+    /// Apply up to `MAX_HELD_UPDATES_PER_BLOCK` updates held back by a lifted
+    /// freeze, once per block: the held runs first, oldest first, then the
+    /// leaderboard entries at the players' current high scores. Returns
+    /// whether some are left.
+    async fn apply_held_updates(&mut self) -> Result<bool, ContractError> {
+        let height = self.runtime.block_height().0;
+        if *self.state.held_updates_height.get() == Some(height) {
+            return self.held_updates_pending().await;
+        }
+        if !self.held_updates_pending().await? {
+            return Ok(false);
+        }
+        self.state.held_updates_height.set(Some(height));
+
+        let mut budget = MAX_HELD_UPDATES_PER_BLOCK;
+        while budget > 0 {
+            let Some((wallet, run)) = self.state.held_runs.front().await? else {
+                break;
+            };
+            self.state.held_runs.delete_front();
+            budget -= 1;
+            // A run invalidated during the freeze is above the restored high score
+            let player = self.state.players.get(&wallet).await?;
+            if run.score <= player.map_or(0, |player| player.high_score) {
+                self.index_run_score(wallet, &run).await?;
+            }
+        }
+
+        let wallets = self.state.frozen_leaderboard_updates.indices().await?;
+        for wallet in wallets.into_iter().take(budget) {
+            self.state.frozen_leaderboard_updates.remove(&wallet)?;
+            let Some(player) = self.state.players.get(&wallet).await? else {
+                continue;
+            };
+            if self.state.leaderboard_index.get(&wallet).await? == Some(player.high_score) {
+                // Games played and lifetime stats changed without a new high score
+                self.publish_leaderboard_delta(wallet).await?;
+            } else if player.high_score > 0 {
+                self.set_leaderboard_entry(wallet, player.high_score).await?;
+            }
+        }
+        self.evict_beyond_leaderboard_cap().await?;
+        self.publish_shard_top(None).await?;
+        self.held_updates_pending().await
+    }
+
+    /// When the current season can be closed (UNIX timestamp)
+    fn season_ends_at(&self) -> u64 {
+        self.state
+            .season_started_at
+            .get()
+            .saturating_add(self.state.config.get().season_length)
+    }
+
+    /// Freeze the results of `event`, whose window has closed
+    async fn end_event(&mut self, mut event: CommunityEvent) -> Result<(), ContractError> {
+        let tag = event.tag.clone();
+        match event.kind {
+            EventKind::Regional => {
+                let rollup = self.state.event_rollup(&tag).await?;
+                self.state.event_results.insert(&tag, rollup)?;
+            }
+            EventKind::TeamRelay => {
+                let standings = self.state.team_standings(&tag).await?;
+                self.state.team_results.insert(&tag, standings)?;
+            }
+        }
+        event.frozen_at = Some(self.now());
+        self.state.events.insert(&tag, event)?;
+        Ok(())
+    }
+
+    /// Register an action to fire once `due_at` has passed
+    async fn schedule_action(
+        &mut self,
+        kind: ScheduledActionKind,
+        event_tag: Option<String>,
+        calendar_entry_id: Option<u64>,
+        due_at: u64,
+        scheduled_by: String,
+    ) -> Result<(), ContractError> {
+        if self.state.scheduled_actions.count().await? >= MAX_SCHEDULED_ACTIONS {
+            return Err(ContractError::TooManyScheduledActions);
+        }
+        let id = *self.state.next_scheduled_action_id.get();
+        self.state.next_scheduled_action_id.set(id + 1);
+        let action = ScheduledAction {
+            id,
+            kind,
+            event_tag,
+            calendar_entry_id,
+            due_at,
+            scheduled_by,
+        };
+        self.state.scheduled_actions.insert(&id, action)?;
+        Ok(())
+    }
+
+    /// Fire the scheduled action that is due first, if this block hasn't
+    /// fired one yet. An action whose transition no longer applies is
+    /// dropped; one that fails is parked in `failed_actions` instead of
+    /// failing the operation that happened to trigger it.
+    async fn run_due_actions(&mut self) -> Result<(), ContractError> {
+        let height = self.runtime.block_height().0;
+        if *self.state.last_action_height.get() == Some(height) {
+            return Ok(());
+        }
+        let now = self.now();
+        let mut next: Option<ScheduledAction> = None;
+        for id in self.state.scheduled_actions.indices().await? {
+            if let Some(action) = self.state.scheduled_actions.get(&id).await? {
+                let earlier = next
+                    .as_ref()
+                    .map_or(true, |next| (action.due_at, action.id) < (next.due_at, next.id));
+                if action.due_at <= now && earlier {
+                    next = Some(action);
+                }
+            }
+        }
+        let Some(action) = next else {
+            return Ok(());
+        };
+
+        self.state.last_action_height.set(Some(height));
+        self.state.scheduled_actions.remove(&action.id)?;
+        if let Err(error) = self.fire_action(action.clone(), now).await {
+            let failed = FailedAction {
+                action,
+                error: error.to_string(),
+                failed_at: now,
+            };
+            self.state.failed_actions.insert(&failed.action.id, failed)?;
+        }
+        Ok(())
+    }
+
+    /// Perform one due scheduled action. Actions that should fire again are
+    /// put back in `scheduled_actions` with their next due time.
+    async fn fire_action(
+        &mut self,
+        action: ScheduledAction,
+        now: u64,
+    ) -> Result<(), ContractError> {
+        match action.kind {
+            ScheduledActionKind::CloseSeason => {
+                let ends_at = self.season_ends_at();
+                if now >= ends_at {
+                    self.close_season().await?;
+                }
+                // Seasons keep closing until the action is cancelled
+                let next = self.season_ends_at();
+                self.state.scheduled_actions.insert(
+                    &action.id,
+                    ScheduledAction {
+                        due_at: next,
+                        ..action
+                    },
+                )?;
+            }
+            ScheduledActionKind::EndEvent => {
+                let tag = action.event_tag.clone().unwrap_or_default();
+                let Some(event) = self.state.events.get(&tag).await? else {
+                    return Ok(());
+                };
+                if event.frozen_at.is_some() {
+                    return Ok(());
+                }
+                if now < event.ends_at {
+                    // The event was moved; fire when its window closes
+                    let due_at = event.ends_at;
+                    self.state
+                        .scheduled_actions
+                        .insert(&action.id, ScheduledAction { due_at, ..action })?;
+                    return Ok(());
+                }
+                self.end_event(event).await?;
+            }
+            ScheduledActionKind::ExpireRewards => {
+                if self.expire_rewards().await? {
+                    // Come back next block for the rest
+                    self.state.scheduled_actions.insert(&action.id, action)?;
+                }
+            }
+            ScheduledActionKind::UnfreezeLeaderboard => {
+                self.state.leaderboard_freeze.set(None);
+                if self.apply_held_updates().await? {
+                    // Come back next block for the rest
+                    self.state.scheduled_actions.insert(&action.id, action)?;
+                }
+            }
+            ScheduledActionKind::ExpireProvisionalScores => {
+                // Come back next block while expired scores remain, and keep
+                // checking while scores can be provisional
+                let due_at = if self.expire_provisional_scores().await? {
+                    now
+                } else if self.state.config.get().provisional_window.is_some() {
+                    now + PROVISIONAL_EXPIRY_INTERVAL
+                } else {
+                    return Ok(());
+                };
+                self.state
+                    .scheduled_actions
+                    .insert(&action.id, ScheduledAction { due_at, ..action })?;
+            }
+            ScheduledActionKind::AnnounceCalendarEntry => {
+                let id = action.calendar_entry_id.unwrap_or_default();
+                if let Some(entry) = self.state.calendar.get(&id).await? {
+                    self.publish_calendar_entry(entry);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// On the first run of `today`, archive the top of the days before it and
+    /// drop the full score indexes that are past their retention
+    async fn roll_daily_indexes(&mut self, today: u64) -> Result<(), ContractError> {
+        for day in self.state.daily_scores.indices().await? {
+            if day >= today {
+                continue;
+            }
+            if !self.state.daily_tops.contains_key(&day).await? {
+                let mut standings = self.state.daily_standings(day).await?;
+                standings.truncate(DAILY_TOP_N);
+                self.state.daily_tops.insert(&day, standings)?;
+            }
+            if day + DAILY_INDEX_RETENTION_DAYS < today {
+                self.state.daily_scores.remove_entry(&day)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Take `amount` coins out of the hold `application` has for `wallet`,
+    /// returning what is left of it. An expired hold belongs to the player
+    /// again, so the application can't settle it any more.
+    async fn take_from_coin_hold(
+        &mut self,
+        application: ApplicationId,
+        wallet: PlayerKey,
+        amount: u64,
+    ) -> Result<u64, ContractError> {
+        let key = (application, wallet);
+        let mut hold = self
+            .state
+            .partner_coin_holds
+            .get(&key)
+            .await?
+            .ok_or(ContractError::CoinHoldNotFound)?;
+        if hold.expires_at <= self.now() {
+            return Err(ContractError::CoinHoldExpired);
+        }
+        hold.amount = hold
+            .amount
+            .checked_sub(amount)
+            .ok_or(ContractError::InsufficientCoins)?;
+        let reserved = hold.amount;
+        if reserved == 0 {
+            self.state.partner_coin_holds.remove(&key)?;
+        } else {
+            self.state.partner_coin_holds.insert(&key, hold)?;
+        }
+        Ok(reserved)
+    }
+
+    /// Add `amount` coins to `wallet`'s balance, returning the new balance
+    async fn credit_coins(&mut self, wallet: PlayerKey, amount: u64) -> Result<u64, ContractError> {
+        let mut player = self
+            .state
+            .players
+            .get(&wallet)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)?;
+        player.coins = player.coins.saturating_add(amount);
+        let balance = player.coins;
+        self.state.players.insert(&wallet, player)?;
+        Ok(balance)
+    }
+
+    /// Answer a request from `caller`, a partner application holding the
+    /// request's capability
+    async fn handle_partner_request(
+        &mut self,
+        caller: ApplicationId,
+        request: PartnerRequest,
+    ) -> Result<PartnerResponse, ContractError> {
+        match request {
+            PartnerRequest::CheckPlayerProgress { wallet } => {
+                let wallet = self.player_account(wallet).await?;
+                let player = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                let season = *self.state.current_season.get();
+                let scope = ScoreScope::new(GameMode::Classic, season);
+                let decay = self.state.config.get().season_decay;
+                let season_score = self
+                    .state
+                    .scoped_score(scope, &wallet)
+                    .await?
+                    .standing(decay, self.now());
+                let onboarded = self
+                    .state
+                    .onboarding
+                    .get(&wallet)
+                    .await?
+                    .is_some_and(|onboarding| onboarding.completed_at.is_some());
+                Ok(PartnerResponse::Progress(PlayerProgress {
+                    high_score: player.high_score,
+                    games_played: player.games_played,
+                    day_streak: player.day_streak,
+                    coins: player.coins,
+                    xp: player.xp,
+                    season_score,
+                    level_bracket: player.level_bracket,
+                    onboarded,
+                }))
+            }
+            PartnerRequest::ReserveCoins { wallet, amount } => {
+                // Only the player can hand their coins to a partner
+                let wallet = self.player_account(wallet).await?;
+                let signer = self.player_account(PlayerKey::from(self.signer()?)).await?;
+                if signer != wallet {
+                    return Err(ContractError::Unauthorized);
+                }
+                let mut player = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                if player.coins < amount {
+                    return Err(ContractError::InsufficientCoins);
+                }
+                player.coins -= amount;
+                let balance = player.coins;
+                self.state.players.insert(&wallet, player)?;
+
+                let key = (caller, wallet);
+                let mut hold = self.state.partner_coin_holds.get(&key).await?.unwrap_or_default();
+                hold.amount = hold.amount.saturating_add(amount);
+                hold.expires_at = self.now().saturating_add(COIN_HOLD_LIFETIME);
+                let reserved = hold.amount;
+                self.state.partner_coin_holds.insert(&key, hold)?;
+                Ok(PartnerResponse::CoinsReserved { reserved, balance })
+            }
+            PartnerRequest::ReleaseCoins { wallet, amount } => {
+                let wallet = self.player_account(wallet).await?;
+                let reserved = self.take_from_coin_hold(caller, wallet, amount).await?;
+                let balance = self.credit_coins(wallet, amount).await?;
+                Ok(PartnerResponse::CoinsSettled { reserved, balance })
+            }
+            PartnerRequest::ConsumeCoins { wallet, amount } => {
+                let wallet = self.player_account(wallet).await?;
+                let reserved = self.take_from_coin_hold(caller, wallet, amount).await?;
+                let player = self.state.players.get(&wallet).await?;
+                let balance = player.map_or(0, |player| player.coins);
+                Ok(PartnerResponse::CoinsSettled { reserved, balance })
+            }
+            PartnerRequest::GrantXp { wallet, amount } => {
+                if amount > MAX_XP_GRANT {
+                    return Err(ContractError::XpGrantTooLarge);
+                }
+                let wallet = self.player_account(wallet).await?;
+                let mut player = self
+                    .state
+                    .players
+                    .get(&wallet)
+                    .await?
+                    .ok_or(ContractError::PlayerNotFound)?;
+                player.xp = player.xp.saturating_add(amount);
+                let xp = player.xp;
+                self.state.players.insert(&wallet, player)?;
+                Ok(PartnerResponse::XpGranted { xp })
+            }
+        }
+    }
+}
+
+impl WithContractAbi for CrossyChainContract {
+    type Abi = crate::CrossyChainAbi;
+}
+
+/// Digest of a score attestation, as documented on `OracleAttestation`
+fn oracle_digest(
+    chain_id: ChainId,
+    wallet: &PlayerKey,
+    high_score: u32,
+    season: u32,
+    issued_at: u64,
+) -> String {
+    hex_digest(&[
+        chain_id.to_string().as_bytes(),
+        wallet.to_string().as_bytes(),
+        &high_score.to_be_bytes(),
+        &season.to_be_bytes(),
+        &issued_at.to_be_bytes(),
+    ])
+}
+
+/// Lowercase hex SHA-256 of the concatenated parts
+fn hex_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Remember a verified high score, keeping the `MAX_VERIFIED_BESTS` highest.
+/// Returns the verification ids of the bests it pushed out, or None if the
+/// score wasn't higher than the ones kept.
+fn record_verified_best(
+    player: &mut PlayerData,
+    verification_id: u64,
+    score: u32,
+    at: u64,
+) -> Option<Vec<u64>> {
+    if player.verified_bests.iter().any(|best| best.score >= score) {
+        return None;
+    }
+    player.verified_bests.push(VerifiedBest {
+        score,
+        verification_id,
+        verified_at: at,
+    });
+    let excess = player.verified_bests.len().saturating_sub(MAX_VERIFIED_BESTS);
+    Some(player.verified_bests.drain(..excess).map(|best| best.verification_id).collect())
+}
+
+/// Key of a replay like or view stored before schema version 7, or None if
+/// either wallet isn't valid
+fn reaction_key(
+    (owner, score, reactor): &(String, u32, String),
+) -> Option<(PlayerKey, u32, PlayerKey)> {
+    let owner = validation::validate_wallet_address(owner).ok()?;
+    let reactor = validation::validate_wallet_address(reactor).ok()?;
+    Some((owner, *score, reactor))
+}
+
+/// Pick a loot table item from the revealed secret and the seed the hub drew
+/// after the secret was committed to
+fn loot_drop(secret: &str, seed: &str) -> &'static str {
+    let digest = Sha256::digest([secret.as_bytes(), seed.as_bytes()].concat());
+    let total: u64 = LOOT_TABLE.iter().map(|(_, weight)| weight).sum();
+    let mut roll = digest_prefix_u64(&digest).checked_rem(total).unwrap_or(0);
+    for (item, weight) in LOOT_TABLE {
+        if roll < weight {
+            return item;
+        }
+        roll -= weight;
+    }
+    LOOT_TABLE[0].0
+}
+
+/// Little-endian `u64` from the first 8 bytes of a digest
+fn digest_prefix_u64(digest: &[u8]) -> u64 {
+    let mut prefix = [0u8; 8];
+    for (byte, digest_byte) in prefix.iter_mut().zip(digest) {
+        *byte = *digest_byte;
+    }
+    u64::from_le_bytes(prefix)
 }
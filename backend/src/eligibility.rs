@@ -0,0 +1,81 @@
+use crate::state::{AppConfig, PlayerData};
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+/// Why a player doesn't get a rank on the public leaderboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Ineligibility {
+    /// A moderator banned the player from the leaderboard
+    Banned,
+    /// The player chose to stay off the leaderboard
+    Hidden,
+    /// Only verified players are ranked, and the player isn't verified
+    Unverified,
+    /// The player hasn't played `min_games` games yet
+    TooFewGames,
+    /// The account isn't `min_account_age` old yet
+    AccountTooNew,
+}
+
+/// The rules deciding who ranks on the public leaderboard, as configured.
+/// The contract applies the lasting ones when indexing scores; the service
+/// applies them all when serving rankings, as the age requirement is met by
+/// time passing alone.
+///
+/// A player who paid the leaderboard entry fee skips the games and age
+/// requirements, but not the others.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardRules {
+    /// Games a player must have played
+    pub min_games: u32,
+    /// Age the account must have reached (seconds)
+    pub min_account_age: u64,
+    /// Whether only verified players are ranked
+    pub require_verified: bool,
+}
+
+impl LeaderboardRules {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            min_games: config.leaderboard_min_games,
+            min_account_age: config.leaderboard_min_account_age,
+            require_verified: config.leaderboard_require_verified,
+        }
+    }
+
+    /// Whether every indexed player is ranked, so rankings can be served
+    /// without loading player data
+    pub fn ranks_every_indexed(&self) -> bool {
+        self.min_games == 0 && self.min_account_age == 0 && !self.require_verified
+    }
+
+    /// Check the rules that only change with the player's data or the
+    /// config, which decide whether the player's score is indexed at all
+    pub fn check_indexed(&self, player: &PlayerData) -> Result<(), Ineligibility> {
+        if player.banned {
+            return Err(Ineligibility::Banned);
+        }
+        if player.hidden {
+            return Err(Ineligibility::Hidden);
+        }
+        if self.require_verified && !player.verified {
+            return Err(Ineligibility::Unverified);
+        }
+        Ok(())
+    }
+
+    /// Check every rule for `player` as of `now`
+    pub fn check(&self, player: &PlayerData, now: u64) -> Result<(), Ineligibility> {
+        self.check_indexed(player)?;
+        if player.entry_fee_paid {
+            return Ok(());
+        }
+        if player.games_played < self.min_games {
+            return Err(Ineligibility::TooFewGames);
+        }
+        if player.account_age(now) < self.min_account_age {
+            return Err(Ineligibility::AccountTooNew);
+        }
+        Ok(())
+    }
+}
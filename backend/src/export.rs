@@ -0,0 +1,65 @@
+use crate::replay::{ReplayMetadata, ReplaySummary};
+use crate::state::{GameConfig, ReplayOrigin};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Magic header that starts every exported replay container
+pub const REPLAY_CONTAINER_MAGIC: &[u8; 8] = b"CRSYRPL\0";
+
+/// Version of the container layout after the magic header
+pub const REPLAY_CONTAINER_VERSION: u32 = 1;
+
+/// A replay with everything needed to re-verify it outside the application:
+/// the recording, the game config it was played under, and where the player
+/// signed the block that submitted it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayContainer {
+    pub container_version: u32,
+    pub wallet_address: String,
+    pub display_name: Option<String>,
+    pub score: u32,
+    pub metadata: Option<ReplayMetadata>,
+    pub summary: Option<ReplaySummary>,
+    /// Game config version the run was played under
+    pub config_version: u32,
+    /// That game config, if this chain still knows it
+    pub game_config: Option<GameConfig>,
+    /// Block that submitted the replay; its certificate carries the player's signature
+    pub origin: Option<ReplayOrigin>,
+    /// The recording, as submitted
+    pub replay: String,
+}
+
+/// Why a replay container could not be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ExportError {
+    #[error("Not a replay container: the magic header is missing")]
+    MissingMagic,
+
+    #[error("Malformed container: the CBOR payload could not be decoded")]
+    Malformed,
+
+    #[error("Unsupported container: version {0} is newer than this application supports")]
+    UnsupportedVersion(u32),
+}
+
+impl ReplayContainer {
+    /// Encode as the magic header followed by the CBOR-encoded container
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = REPLAY_CONTAINER_MAGIC.to_vec();
+        ciborium::into_writer(self, &mut bytes).expect("writing CBOR to a Vec cannot fail");
+        bytes
+    }
+
+    /// Decode a container produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self, ExportError> {
+        let payload = bytes
+            .strip_prefix(REPLAY_CONTAINER_MAGIC.as_slice())
+            .ok_or(ExportError::MissingMagic)?;
+        let container: Self = ciborium::from_reader(payload).map_err(|_| ExportError::Malformed)?;
+        if container.container_version > REPLAY_CONTAINER_VERSION {
+            return Err(ExportError::UnsupportedVersion(container.container_version));
+        }
+        Ok(container)
+    }
+}
@@ -0,0 +1,19 @@
+use linera_sdk::base::{Amount, ContractAbi, Owner};
+use serde::{Deserialize, Serialize};
+
+/// Request to the external faucet application configured with `SetWelcomeBonus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FaucetRequest {
+    /// Credit `amount` of the faucet's token to `owner`
+    Grant { owner: Owner, amount: Amount },
+}
+
+/// ABI the faucet application implements: it accepts `FaucetRequest`s as
+/// operations called by this application on the faucet's chain, and fails
+/// the call (bouncing the grant message) when it can't grant
+pub struct FaucetAbi;
+
+impl ContractAbi for FaucetAbi {
+    type Operation = FaucetRequest;
+    type Response = ();
+}
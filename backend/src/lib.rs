@@ -1,10 +1,66 @@
+mod commitment;
+mod compression;
 mod contract;
+mod eligibility;
+mod export;
+mod faucet;
+mod notification_hub;
+mod oracle;
+mod partner;
+mod proof;
+mod rating;
+mod replay;
+mod scalars;
 mod service;
 mod state;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod validation;
 
-pub use contract::{CrossyChainContract, Message, Operation};
-pub use service::CrossyChainService;
-pub use state::{CrossyChainState, PlayerData};
+pub use commitment::{
+    leaf_hash, merkle_root, LeaderboardCommitment, LeaderboardProof, MerkleStep, COMMITMENT_SIZE,
+};
+pub use compression::{decompress_replay, CompressionError, ReplayCompression};
+pub use contract::{
+    ContractError, CrossyChainContract, Message, Operation, OperationResponse, RelayedRun,
+};
+pub use eligibility::{Ineligibility, LeaderboardRules};
+pub use export::{ExportError, ReplayContainer, REPLAY_CONTAINER_MAGIC, REPLAY_CONTAINER_VERSION};
+pub use faucet::{FaucetAbi, FaucetRequest};
+pub use notification_hub::{HubEvent, NotificationHubAbi};
+pub use oracle::{OracleAttestation, ScoreOracleAbi};
+pub use partner::{
+    CoinHold, PartnerCapability, PartnerRequest, PartnerResponse, PlayerProgress,
+    COIN_HOLD_LIFETIME, MAX_XP_GRANT,
+};
+pub use proof::{NoProofSystem, ProofError, ProofStatement, ProofVerifier};
+pub use rating::{
+    expected_permille, rated, HeadToHeadMode, MatchOutcome, PlayerRating, RatingChange,
+    INITIAL_RATING, RATING_FLOOR, RATING_K_FACTOR,
+};
+pub use replay::{
+    ghost_trace, summarize, verify_replay, ClientVersion, DeathCause, GhostPoint, Platform,
+    Recording, ReplayError, ReplayFormatError, ReplayMetadata, ReplaySummary, Score,
+};
+pub use scalars::DateTime;
+pub use service::{CrossyChainService, ServiceError};
+pub use state::{
+    calibration_rating, challenge_week, shard_index, AdminAuditEntry, AllTimeRecords, AppConfig,
+    Appeal, AppealStatus, ChallengeModifier, ClaimableReward, CommunityEvent, ConfigField,
+    CrossyChainState, DeathTally, DifficultyStep, EVENT_REGION_TOP_N, EventEntry, EventKind,
+    EventRollup, EventStanding, EventTeam, FeaturedReplay, GameConfig, GameMode, GhostRace,
+    GhostRaceRecord, GiftRecord, HighScoreSnapshot, IndexRebuild, InventoryItem, LaneWeights,
+    LeaderboardChange, LeaderboardChangeKind, LevelBracket, LootBoxCommit, MAX_NOTIFICATIONS,
+    MAX_VERIFIED_BESTS, MirroredPlayer, Notification, NotificationKind, OpMetrics, PLACEMENT_RUNS,
+    PendingHubScore, PersonalBest, PlayerData, PlayerKey, ProvisionalHighScore, PruningStats, Quest,
+    QuestGoal, QuestProgress, QueuedRacer, RACE_BRACKET_WIDTH, Race, RaceChainInfo, RaceMatch,
+    RaceSnapshot, RecordHolder, RegionStanding, ReplayOrigin, ReplayStats, RewardSource, Role,
+    RunStats, SECONDS_PER_WEEK, SHARD_TOP_N, STATE_SCHEMA_VERSION, ScopedScore, ScoreAttestation,
+    ScoreInvalidation, ScoreScope, SeasonDecay, SeasonRecord, SeasonStanding, SessionKey, ShardTop,
+    ShardTopEntry, TEAM_SIZE, TeamMember, TeamStanding, Tip, Trophy, VerificationItem,
+    VerificationStatus, VerificationVerdict, VerifiedBest,
+};
+pub use validation::ValidationError;
 
 use async_graphql::{EmptySubscription, Object, Schema};
 use linera_sdk::base::{ContractAbi, ServiceAbi};
@@ -14,7 +70,7 @@ pub struct CrossyChainAbi;
 
 impl ContractAbi for CrossyChainAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = OperationResponse;
 }
 
 impl ServiceAbi for CrossyChainAbi {
@@ -0,0 +1,40 @@
+use async_graphql::{Request, Response};
+use linera_sdk::base::{ChainId, ContractAbi, ServiceAbi};
+use serde::{Deserialize, Serialize};
+
+/// Notable event forwarded to the external notification hub application,
+/// which fans it out to the players who follow it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HubEvent {
+    /// A player took the all-time highest score
+    NewLeader {
+        /// Chain the score was recorded on
+        chain_id: ChainId,
+        wallet_address: String,
+        score: u32,
+        /// Holder of the record before, if any
+        previous_leader: Option<String>,
+    },
+    /// A season was closed
+    SeasonEnded {
+        chain_id: ChainId,
+        season: u32,
+        /// Top three of the final standings, first place first
+        winners: Vec<String>,
+        player_count: u32,
+    },
+}
+
+/// ABI the notification hub application implements: it accepts `HubEvent`s
+/// as operations called by this application
+pub struct NotificationHubAbi;
+
+impl ContractAbi for NotificationHubAbi {
+    type Operation = HubEvent;
+    type Response = ();
+}
+
+impl ServiceAbi for NotificationHubAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
@@ -0,0 +1,34 @@
+use crate::state::PlayerKey;
+use async_graphql::SimpleObject;
+use linera_sdk::base::{ChainId, ContractAbi};
+use serde::{Deserialize, Serialize};
+
+/// A player's standing as attested by the chain holding their scores, for
+/// other on-chain games to grant perks on. It is only passed on after the
+/// receiving instance checked that `source_chain`, the authenticated origin
+/// of the message, is the wallet's hub in its own config and that `digest`
+/// matches; receivers should accept it only when called by this application.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct OracleAttestation {
+    /// Chain that issued the attestation
+    pub source_chain: ChainId,
+    pub wallet: PlayerKey,
+    pub high_score: u32,
+    /// Season running when the attestation was issued
+    pub season: u32,
+    /// When the attestation was issued (UNIX timestamp)
+    pub issued_at: u64,
+    /// Hex SHA-256 of the source chain, wallet, high score, season and
+    /// issue time, concatenated in that order with numbers big-endian
+    pub digest: String,
+}
+
+/// ABI an application receiving score attestations implements: it accepts
+/// `OracleAttestation`s as operations called by this application on the
+/// chain the attestation was sent to
+pub struct ScoreOracleAbi;
+
+impl ContractAbi for ScoreOracleAbi {
+    type Operation = OracleAttestation;
+    type Response = ();
+}
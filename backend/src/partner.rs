@@ -0,0 +1,96 @@
+use crate::state::{LevelBracket, PlayerKey};
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+/// Most experience points one `GrantXp` request can grant
+pub const MAX_XP_GRANT: u64 = 10_000;
+
+/// How long a coin hold lasts after its last reservation before the player
+/// can reclaim it (seconds)
+pub const COIN_HOLD_LIFETIME: u64 = 7 * 24 * 60 * 60;
+
+/// Coins a partner application holds for a player
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct CoinHold {
+    pub amount: u64,
+    /// When the player can reclaim what is left (UNIX timestamp); each
+    /// reservation pushes it back to `COIN_HOLD_LIFETIME` from then
+    pub expires_at: u64,
+}
+
+/// Privileged request a partner application is allowlisted for with
+/// `SetPartnerCapabilities`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum)]
+pub enum PartnerCapability {
+    /// Hold players' coins with `ReserveCoins`, and settle the holds with
+    /// `ReleaseCoins` and `ConsumeCoins`
+    ReserveCoins,
+    /// Grant experience points with `GrantXp`
+    GrantXp,
+}
+
+/// Request from a partner application. Partners send it with
+/// `call_application` as `Operation::Partner`. Any application can read
+/// progress; for the privileged requests, the calling application must be
+/// allowlisted for the request's capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PartnerRequest {
+    /// Read a player's progress; fails with `PlayerNotFound` for a wallet
+    /// that never played
+    CheckPlayerProgress { wallet: PlayerKey },
+    /// Take `amount` coins from a player's balance and hold them for the
+    /// calling application. The call must be authenticated by the player.
+    ReserveCoins { wallet: PlayerKey, amount: u64 },
+    /// Give `amount` held coins back to the player
+    ReleaseCoins { wallet: PlayerKey, amount: u64 },
+    /// Spend `amount` held coins on what the player reserved them for; they
+    /// leave the player's hands for good
+    ConsumeCoins { wallet: PlayerKey, amount: u64 },
+    /// Grant a player `amount` experience points, at most `MAX_XP_GRANT`
+    GrantXp { wallet: PlayerKey, amount: u64 },
+}
+
+impl PartnerRequest {
+    /// Capability the calling application must be allowlisted for, or None
+    /// for requests any application can make
+    pub fn capability(&self) -> Option<PartnerCapability> {
+        match self {
+            PartnerRequest::CheckPlayerProgress { .. } => None,
+            PartnerRequest::ReserveCoins { .. }
+            | PartnerRequest::ReleaseCoins { .. }
+            | PartnerRequest::ConsumeCoins { .. } => Some(PartnerCapability::ReserveCoins),
+            PartnerRequest::GrantXp { .. } => Some(PartnerCapability::GrantXp),
+        }
+    }
+}
+
+/// A player's progress, as read by partner applications
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerProgress {
+    pub high_score: u32,
+    pub games_played: u32,
+    /// Consecutive days played, up to the last game
+    pub day_streak: u32,
+    pub coins: u64,
+    pub xp: u64,
+    /// Best score of the current season
+    pub season_score: u32,
+    pub level_bracket: Option<LevelBracket>,
+    /// Whether the player finished onboarding
+    pub onboarded: bool,
+}
+
+/// Answer to a `PartnerRequest`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartnerResponse {
+    /// Progress of the requested player; zeroed for unknown players
+    Progress(PlayerProgress),
+    /// Coins the calling application now holds for the player, and the
+    /// player's remaining balance
+    CoinsReserved { reserved: u64, balance: u64 },
+    /// Coins the calling application still holds for the player, and the
+    /// player's balance, after a release or a consumption
+    CoinsSettled { reserved: u64, balance: u64 },
+    /// The player's experience points after the grant
+    XpGranted { xp: u64 },
+}
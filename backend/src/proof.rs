@@ -0,0 +1,60 @@
+use thiserror::Error;
+
+/// Largest accepted run proof (bytes, after hex decoding)
+pub const MAX_PROOF_SIZE: usize = 64 * 1024;
+
+/// What a run proof attests: replaying `replay_digest` under game config
+/// `config_version` yields `score`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStatement {
+    /// Hex SHA-256 of the replay JSON
+    pub replay_digest: String,
+    pub score: u32,
+    pub config_version: u32,
+}
+
+/// Why a run proof was not accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ProofError {
+    #[error("Malformed proof: proofs must be hex-encoded and at most 64KiB")]
+    Malformed,
+
+    #[error("Invalid proof: the proof does not show the replay yields the score")]
+    Invalid,
+
+    #[error("Unsupported proof: no proof system is configured")]
+    Unsupported,
+}
+
+/// A succinct proof system able to check run proofs without re-simulating
+/// the replay
+pub trait ProofVerifier {
+    fn verify(&self, proof: &[u8], statement: &ProofStatement) -> Result<(), ProofError>;
+}
+
+/// Placeholder used until a proof system is plugged in: every proof is
+/// unsupported, so proven runs go through the verifier queue like any other
+pub struct NoProofSystem;
+
+impl ProofVerifier for NoProofSystem {
+    fn verify(&self, _proof: &[u8], _statement: &ProofStatement) -> Result<(), ProofError> {
+        Err(ProofError::Unsupported)
+    }
+}
+
+/// The proof system the contract checks run proofs with
+pub fn active_verifier() -> &'static dyn ProofVerifier {
+    &NoProofSystem
+}
+
+/// Decode a hex-encoded proof, bounding its size
+pub fn decode_proof(proof: &str) -> Result<Vec<u8>, ProofError> {
+    let proof = proof.trim().trim_start_matches("0x");
+    if !proof.is_ascii() || proof.len() % 2 != 0 || proof.len() / 2 > MAX_PROOF_SIZE {
+        return Err(ProofError::Malformed);
+    }
+    (0..proof.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&proof[i..i + 2], 16).map_err(|_| ProofError::Malformed))
+        .collect()
+}
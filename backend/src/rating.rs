@@ -0,0 +1,134 @@
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+/// Rating every player starts head-to-head play with
+pub const INITIAL_RATING: u32 = 1_200;
+
+/// Lowest rating a player can drop to
+pub const RATING_FLOOR: u32 = 100;
+
+/// Most rating points one match can move
+pub const RATING_K_FACTOR: i64 = 32;
+
+/// Expected score (per mille) of the higher rated player, for rating gaps of
+/// 0, 25, 50, ... 400 points. Ratings are integers so every validator gets
+/// the same result without floating point; gaps past 400 count as 400.
+const EXPECTED_PERMILLE: [i64; 17] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+];
+
+/// Width of a step in `EXPECTED_PERMILLE`, in rating points
+const EXPECTED_STEP: i64 = 25;
+
+/// Head-to-head mode a rated match was played in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum HeadToHeadMode {
+    GhostRace,
+    LiveRace,
+}
+
+/// How a rated match ended, for one side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MatchOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl MatchOutcome {
+    /// The same match seen from the other side
+    pub fn reversed(self) -> Self {
+        match self {
+            MatchOutcome::Win => MatchOutcome::Loss,
+            MatchOutcome::Loss => MatchOutcome::Win,
+            MatchOutcome::Draw => MatchOutcome::Draw,
+        }
+    }
+
+    /// Points scored (per mille)
+    fn permille(self) -> i64 {
+        match self {
+            MatchOutcome::Win => 1_000,
+            MatchOutcome::Loss => 0,
+            MatchOutcome::Draw => 500,
+        }
+    }
+}
+
+/// A player's head-to-head rating
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerRating {
+    pub rating: u32,
+    /// Highest rating reached
+    pub peak: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Default for PlayerRating {
+    fn default() -> Self {
+        PlayerRating {
+            rating: INITIAL_RATING,
+            peak: INITIAL_RATING,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+        }
+    }
+}
+
+impl PlayerRating {
+    /// Rated matches played
+    pub fn matches(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Apply the result of a match against a player rated `opponent_rating`
+    pub fn record(&mut self, opponent_rating: u32, outcome: MatchOutcome) {
+        self.rating = rated(self.rating, opponent_rating, outcome);
+        self.peak = self.peak.max(self.rating);
+        match outcome {
+            MatchOutcome::Win => self.wins += 1,
+            MatchOutcome::Loss => self.losses += 1,
+            MatchOutcome::Draw => self.draws += 1,
+        }
+    }
+}
+
+/// A rating change, kept per player for the profile graph
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RatingChange {
+    pub mode: HeadToHeadMode,
+    pub opponent: String,
+    pub outcome: MatchOutcome,
+    pub rating_before: u32,
+    pub rating_after: u32,
+    /// When the match was settled (UNIX timestamp)
+    pub recorded_at: u64,
+}
+
+/// Expected score (per mille) of a player rated `rating` against one rated
+/// `opponent_rating`, interpolated between the table steps
+pub fn expected_permille(rating: u32, opponent_rating: u32) -> i64 {
+    let gap = i64::from(rating) - i64::from(opponent_rating);
+    let distance = gap.abs().min(EXPECTED_STEP * 16);
+    let step = (distance / EXPECTED_STEP) as usize;
+    let low = EXPECTED_PERMILLE.get(step).copied().unwrap_or_default();
+    let high = EXPECTED_PERMILLE.get(step + 1).copied().unwrap_or(low);
+    let expected = low + (high - low) * (distance % EXPECTED_STEP) / EXPECTED_STEP;
+    if gap < 0 {
+        1_000 - expected
+    } else {
+        expected
+    }
+}
+
+/// Rating of a player rated `rating` after a match against one rated
+/// `opponent_rating`, rounded to the nearest point
+pub fn rated(rating: u32, opponent_rating: u32, outcome: MatchOutcome) -> u32 {
+    let swing = RATING_K_FACTOR * (outcome.permille() - expected_permille(rating, opponent_rating));
+    let delta = (swing + swing.signum() * 500) / 1_000;
+    let rating = (i64::from(rating) + delta).max(i64::from(RATING_FLOOR));
+    u32::try_from(rating).unwrap_or(u32::MAX)
+}
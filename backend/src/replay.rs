@@ -0,0 +1,325 @@
+use crate::state::GameConfig;
+use async_graphql::{Enum, InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Number of columns on the game board (matches the client)
+pub const BOARD_COLUMNS: i32 = 17;
+
+/// Newest replay format version the contract understands
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Converters bringing a recording up one format version: `CONVERTERS[v]`
+/// turns a version `v` recording into a version `v + 1` one. Recordings
+/// without a `formatVersion` predate versioning and count as version 0.
+const CONVERTERS: &[fn(&mut Value)] = &[upgrade_v0_to_v1];
+
+/// Why a stored or submitted recording could not be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ReplayFormatError {
+    #[error("Malformed replay: the recording is not valid JSON for its format version")]
+    Malformed,
+
+    #[error("Unknown replay format: version {0} is newer than this application supports")]
+    UnknownVersion(u32),
+}
+
+/// Score a verified replay is credited with
+pub type Score = u32;
+
+/// Why a replay was refused by `verify_replay`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReplayError {
+    #[error("Invalid replay encoding: the recording is not UTF-8")]
+    NotUtf8,
+
+    #[error(transparent)]
+    Format(#[from] ReplayFormatError),
+
+    #[error("Invalid replay: input {index} is timestamped before the one preceding it")]
+    InputsOutOfOrder { index: usize },
+
+    #[error("Invalid replay: input {index} is timestamped after the end of the run")]
+    InputAfterEnd { index: usize },
+
+    #[error("Unreachable score: the replay claims {claimed} but its inputs reach at most {reachable}")]
+    ScoreUnreachable { claimed: Score, reachable: Score },
+}
+
+/// Version 0 recordings are version 1 recordings without the envelope,
+/// which stays optional, so only the version needs stamping
+fn upgrade_v0_to_v1(recording: &mut Value) {
+    recording["formatVersion"] = Value::from(1);
+}
+
+/// A single recorded input, as produced by the client's GameInputRecorder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputEvent {
+    /// Milliseconds since the game started
+    pub timestamp: u64,
+    /// Movement direction
+    pub action: Action,
+}
+
+/// Movement directions the player can input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Forward,
+    Backward,
+    Left,
+    Right,
+}
+
+/// Platform the game client was running on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Web,
+    Ios,
+    Android,
+    Desktop,
+}
+
+/// What ended a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "lowercase")]
+pub enum DeathCause {
+    Car,
+    Train,
+    Water,
+    Timeout,
+}
+
+/// Metadata describing how a replay was recorded
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ReplayMetadata {
+    /// Version of the game client (MAJOR.MINOR.PATCH)
+    pub client_version: String,
+    /// Platform the client was running on
+    pub platform: Platform,
+    /// Version of the recording format
+    pub format_version: u32,
+    /// Game config version the run was played under
+    pub config_version: u32,
+}
+
+/// Parsed MAJOR.MINOR.PATCH client version, ordered by precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClientVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ClientVersion {
+    /// Parse a "MAJOR.MINOR.PATCH" version string
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+/// Replay recording stored as JSON in `PlayerData::replay_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    /// Random seed used to generate the game
+    pub seed: u64,
+    /// Game start (UNIX timestamp, milliseconds)
+    pub start_time: u64,
+    /// Ordered list of input events
+    pub inputs: Vec<InputEvent>,
+    /// Final score claimed by the client
+    pub final_score: u32,
+    /// Game duration in milliseconds
+    pub duration: u64,
+    /// Recorder version
+    pub version: String,
+    /// Version of the game client that recorded the run
+    #[serde(default)]
+    pub client_version: Option<String>,
+    /// Platform the client was running on
+    #[serde(default)]
+    pub platform: Option<Platform>,
+    /// Version of the recording format
+    #[serde(default)]
+    pub format_version: Option<u32>,
+    /// Game config version the run was played under
+    #[serde(default)]
+    pub config_version: Option<u32>,
+    /// What ended the run
+    #[serde(default)]
+    pub death_cause: Option<DeathCause>,
+}
+
+impl Recording {
+    /// Parse a recording from its JSON form
+    pub fn from_json(json: &str) -> Option<Self> {
+        Self::parse(json).ok()
+    }
+
+    /// Parse a recording in any known format version, converting it to the
+    /// current one. The recording keeps the version it was recorded in.
+    pub fn parse(json: &str) -> Result<Self, ReplayFormatError> {
+        let mut value: Value =
+            serde_json::from_str(json).map_err(|_| ReplayFormatError::Malformed)?;
+        let version = recorded_format_version(&value)?;
+        if version > REPLAY_FORMAT_VERSION {
+            return Err(ReplayFormatError::UnknownVersion(version));
+        }
+        for convert in &CONVERTERS[version as usize..] {
+            convert(&mut value);
+        }
+
+        let mut recording: Self =
+            serde_json::from_value(value).map_err(|_| ReplayFormatError::Malformed)?;
+        recording.format_version = Some(version);
+        Ok(recording)
+    }
+
+    /// Envelope metadata, if the recording carries all of it
+    pub fn metadata(&self) -> Option<ReplayMetadata> {
+        Some(ReplayMetadata {
+            client_version: self.client_version.clone()?,
+            platform: self.platform?,
+            format_version: self.format_version?,
+            config_version: self.config_version?,
+        })
+    }
+}
+
+/// Summarize a recording; `checksum` is the digest of its JSON form
+pub fn summarize(recording: &Recording, checksum: String) -> ReplaySummary {
+    let max_lane = ghost_trace(recording)
+        .iter()
+        .map(|point| point.lane)
+        .max()
+        .unwrap_or(0);
+    ReplaySummary {
+        duration: recording.duration,
+        input_count: u32::try_from(recording.inputs.len()).unwrap_or(u32::MAX),
+        max_lane,
+        checksum,
+    }
+}
+
+/// Check a raw replay and return the score it is credited with.
+///
+/// This needs no runtime, so it can be fuzzed and property-tested on its
+/// own: any input must give a score or an error, never a panic. Obstacles are
+/// not simulated yet, so the claimed score is only bounded by the furthest
+/// lane the inputs could reach under `config`. Once a simulator exists it
+/// belongs here, and the score becomes the simulated one.
+pub fn verify_replay(bytes: &[u8], config: &GameConfig) -> Result<Score, ReplayError> {
+    let json = std::str::from_utf8(bytes).map_err(|_| ReplayError::NotUtf8)?;
+    let recording = Recording::parse(json)?;
+
+    let mut previous = 0;
+    for (index, input) in recording.inputs.iter().enumerate() {
+        if input.timestamp < previous {
+            return Err(ReplayError::InputsOutOfOrder { index });
+        }
+        if input.timestamp > recording.duration {
+            return Err(ReplayError::InputAfterEnd { index });
+        }
+        previous = input.timestamp;
+    }
+
+    let max_lane = ghost_trace(&recording)
+        .iter()
+        .map(|point| point.lane)
+        .max()
+        .unwrap_or(0);
+    let reachable = u64::from(max_lane.unsigned_abs())
+        .saturating_mul(u64::from(config.points_per_lane))
+        .saturating_mul(u64::from(config.score_multiplier_percent))
+        / 100;
+    let reachable = Score::try_from(reachable).unwrap_or(Score::MAX);
+    if recording.final_score > reachable {
+        return Err(ReplayError::ScoreUnreachable {
+            claimed: recording.final_score,
+            reachable,
+        });
+    }
+
+    Ok(recording.final_score)
+}
+
+/// Format version a recording was recorded in (0 if it predates versioning)
+fn recorded_format_version(recording: &Value) -> Result<u32, ReplayFormatError> {
+    match recording.get("formatVersion") {
+        None | Some(Value::Null) => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or(ReplayFormatError::Malformed),
+    }
+}
+
+/// Small digest of a high-score replay, stored next to it so most views and
+/// heuristics don't need the full recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ReplaySummary {
+    /// Game duration in milliseconds
+    pub duration: u64,
+    /// Number of recorded inputs
+    pub input_count: u32,
+    /// Furthest lane the player reached
+    pub max_lane: i32,
+    /// Hex SHA-256 of the replay JSON
+    pub checksum: String,
+}
+
+/// Player position at a point in time, used to render a ghost
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "GhostPointInput")]
+pub struct GhostPoint {
+    /// Milliseconds since the game started
+    pub t: u64,
+    /// Lane index (0 is the starting lane)
+    pub lane: i32,
+    /// Column index on the board
+    pub column: i32,
+}
+
+/// Derive the positions the player moved through from the recorded inputs.
+///
+/// Board edges are applied like the client does, but obstacles are not known
+/// without re-simulating the seed, so moves blocked by trees still advance
+/// the ghost.
+pub fn ghost_trace(recording: &Recording) -> Vec<GhostPoint> {
+    let mut lane = 0;
+    let mut column = BOARD_COLUMNS / 2;
+    let mut trace = Vec::with_capacity(recording.inputs.len() + 1);
+
+    trace.push(GhostPoint { t: 0, lane, column });
+
+    for input in &recording.inputs {
+        match input.action {
+            Action::Forward => lane += 1,
+            Action::Backward if lane > 0 => lane -= 1,
+            Action::Left if column > 0 => column -= 1,
+            Action::Right if column < BOARD_COLUMNS - 1 => column += 1,
+            _ => continue,
+        }
+        trace.push(GhostPoint {
+            t: input.timestamp,
+            lane,
+            column,
+        });
+    }
+
+    trace
+}
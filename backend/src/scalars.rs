@@ -0,0 +1,177 @@
+use crate::state::PlayerKey;
+use crate::validation::ValidationError;
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A point in time with second precision. Stored as a UNIX timestamp and
+/// exposed to GraphQL as an RFC 3339 UTC string such as `2024-05-01T12:00:00Z`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct DateTime(pub u64);
+
+impl DateTime {
+    /// Seconds since the UNIX epoch
+    pub fn seconds(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for DateTime {
+    fn from(seconds: u64) -> Self {
+        DateTime(seconds)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days((self.0 / 86_400) as i64);
+        let time = self.0 % 86_400;
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            time / 3_600,
+            time / 60 % 60,
+            time % 60
+        )
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = ValidationError;
+
+    /// Parse an RFC 3339 date-time. Fractions of a second are dropped and
+    /// offsets other than `Z` are converted to UTC.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let bytes = value.as_bytes();
+        if !value.is_ascii()
+            || bytes.len() < 20
+            || bytes[4] != b'-'
+            || bytes[7] != b'-'
+            || !matches!(bytes[10], b'T' | b't' | b' ')
+            || bytes[13] != b':'
+            || bytes[16] != b':'
+        {
+            return Err(ValidationError::InvalidTimestamp);
+        }
+
+        let number = |range: std::ops::Range<usize>| -> Result<u32, ValidationError> {
+            let digits = &value[range];
+            if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(ValidationError::InvalidTimestamp);
+            }
+            digits.parse().map_err(|_| ValidationError::InvalidTimestamp)
+        };
+        let year = i64::from(number(0..4)?);
+        let (month, day) = (number(5..7)?, number(8..10)?);
+        let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(ValidationError::InvalidTimestamp);
+        }
+        let days = days_from_civil(year, month, day);
+        // Catches days past the end of the month, like February 30
+        if !(1..=12).contains(&month) || civil_from_days(days) != (year, month, day) {
+            return Err(ValidationError::InvalidTimestamp);
+        }
+
+        let mut rest = &value[19..];
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return Err(ValidationError::InvalidTimestamp);
+            }
+            rest = &fraction[digits..];
+        }
+        let offset = match rest {
+            "Z" | "z" => 0,
+            _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+                let sign = match rest.as_bytes()[0] {
+                    b'+' => 1,
+                    b'-' => -1,
+                    _ => return Err(ValidationError::InvalidTimestamp),
+                };
+                let hours = i64::from(number(value.len() - 5..value.len() - 3)?);
+                let minutes = i64::from(number(value.len() - 2..value.len())?);
+                if hours > 23 || minutes > 59 {
+                    return Err(ValidationError::InvalidTimestamp);
+                }
+                sign * (hours * 3_600 + minutes * 60)
+            }
+            _ => return Err(ValidationError::InvalidTimestamp),
+        };
+
+        let seconds = days * 86_400
+            + i64::from(hour) * 3_600
+            + i64::from(minute) * 60
+            + i64::from(second)
+            - offset;
+        u64::try_from(seconds)
+            .map(DateTime)
+            .map_err(|_| ValidationError::InvalidTimestamp)
+    }
+}
+
+/// Accepts RFC 3339 strings, or UNIX timestamps in seconds for older clients
+#[Scalar(name = "DateTime")]
+impl ScalarType for DateTime {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(date_time) => date_time.parse().map_err(InputValueError::custom),
+            Value::Number(seconds) => seconds
+                .as_u64()
+                .map(DateTime)
+                .ok_or_else(|| InputValueError::custom(ValidationError::InvalidTimestamp)),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// Wallet addresses, parsed as leniently as `PlayerKey::from_str`
+#[Scalar(name = "Owner")]
+impl ScalarType for PlayerKey {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(wallet) => wallet.parse().map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// Gregorian (year, month, day) of a day counted from 1970-01-01
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = (if month_index < 10 { month_index + 3 } else { month_index - 9 }) as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days from 1970-01-01 to a Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
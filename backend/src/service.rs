@@ -1,37 +1,154 @@
-use crate::state::{CrossyChainState, PlayerData};
-use async_graphql::{Context, Object, Request, Response, Schema};
+use crate::commitment::{self, LeaderboardCommitment, LeaderboardProof, COMMITMENT_SIZE};
+use crate::compression::{self, ReplayCompression};
+use crate::contract::{Message, Operation};
+use crate::eligibility::{Ineligibility, LeaderboardRules};
+use crate::export::{ReplayContainer, REPLAY_CONTAINER_VERSION};
+use crate::oracle::OracleAttestation;
+use crate::partner::{CoinHold, PartnerCapability};
+use crate::rating::{PlayerRating, RatingChange};
+use crate::replay::{
+    ghost_trace, DeathCause, GhostPoint, Recording, ReplayMetadata, ReplaySummary,
+};
+use crate::scalars::{civil_from_days, DateTime};
+use crate::state::{
+    challenge_week, AdminAuditEntry, AllTimeRecords, Appeal, AppealStatus, ArchiveStats,
+    CalendarEntry, ChallengeModifier, ClaimableReward, CommunityEvent, CrossyChainState,
+    DAILY_TOP_N, DailyStanding, DeathTally, EventRollup, EventStanding, EventTeam, FailedAction,
+    GameConfig, GameMode, GhostRaceRecord, GiftRecord, GlobalStats, IndexRebuild, InventoryItem,
+    LeaderboardChange, LeaderboardFreeze, LevelBracket, LootBoxCommit, ModeStats, NameBlocklist,
+    NameReview, NameScriptPolicy, Notification, ONBOARDING_REWARD_COINS, OnboardingStep, OpMetrics,
+    PLACEMENT_RUNS, PersonalBest, PlayerData, PlayerKey, PruningStats, Quest, RENAME_COOLDOWN, Race,
+    RaceMatch, RaceSnapshot, RunStats, SECONDS_PER_DAY, SECONDS_PER_WEEK, SHARD_TOP_N,
+    STATE_SCHEMA_VERSION, ScheduledAction, ScopedScore, ScoreInvalidation, ScoreScope, SeasonDecay,
+    SeasonRecord, SeasonStanding, ShardTopEntry, SubmissionReceipt, TeamStanding, Tip, Trophy,
+    VerificationItem, VerificationStatus,
+};
+use crate::validation::{self, ValidationError};
+use async_graphql::futures_util::{future, stream, Stream};
+use async_graphql::{
+    Context, Enum, ErrorExtensions, Object, Request, Response, Schema, SimpleObject, Subscription,
+};
 use linera_sdk::{
-    base::WithServiceAbi,
-    views::{View, ViewStorageContext},
+    base::{Amount, ApplicationId, ChainId, WithServiceAbi},
+    views::{View, ViewError, ViewStorageContext},
     Service, ServiceRuntime,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde_reflection::{Samples, Tracer, TracerConfig};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, OnceLock};
+use std::task::Poll;
+use thiserror::Error;
+
+/// Service errors, exposed to clients as GraphQL errors with a stable `code` extension.
+/// Mutation pre-validation errors mirror the matching ContractError variants so
+/// clients can reject bad input before a block is proposed.
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    #[error("Storage error: {0}")]
+    Storage(#[from] ViewError),
+
+    #[error("Corrupt replay: the stored replay could not be decoded")]
+    CorruptReplay,
+
+    #[error("Invalid offset: offsets must fall within the replay on a character boundary")]
+    InvalidReplayOffset,
+
+    #[error("Not on mirror: read-only mirrors don't hold the state this query reads")]
+    NotOnMirror,
+
+    #[error("Invalid replay compression: the replay does not match its declared compression")]
+    InvalidReplayCompression,
+
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+}
+
+impl ServiceError {
+    /// Stable machine-readable code for clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServiceError::Storage(_) => "STORAGE_ERROR",
+            ServiceError::CorruptReplay => "CORRUPT_REPLAY",
+            ServiceError::InvalidReplayOffset => "REPLAY_OFFSET_INVALID",
+            ServiceError::NotOnMirror => "NOT_ON_MIRROR",
+            ServiceError::InvalidReplayCompression => "REPLAY_COMPRESSION_INVALID",
+            ServiceError::Invalid(ValidationError::InvalidScore) => "SCORE_INVALID",
+            ServiceError::Invalid(ValidationError::ReplayTooLarge) => "REPLAY_TOO_LARGE",
+            ServiceError::Invalid(ValidationError::InvalidClientVersion) => "CLIENT_VERSION_INVALID",
+            ServiceError::Invalid(ValidationError::InvalidSubmissionId) => "SUBMISSION_ID_INVALID",
+            ServiceError::Invalid(ValidationError::NameTooLong) => "NAME_TOO_LONG",
+            ServiceError::Invalid(ValidationError::NameEmpty) => "NAME_EMPTY",
+            ServiceError::Invalid(ValidationError::InvalidWalletAddress) => "WALLET_ADDRESS_INVALID",
+            ServiceError::Invalid(ValidationError::InvalidTimestamp) => "TIMESTAMP_INVALID",
+            ServiceError::Invalid(ValidationError::InvalidRegion) => "REGION_INVALID",
+            ServiceError::Invalid(ValidationError::NameBlocked) => "NAME_BLOCKED",
+            ServiceError::Invalid(ValidationError::NameScriptNotAllowed) => "NAME_SCRIPT_NOT_ALLOWED",
+            ServiceError::Invalid(ValidationError::NameMixedScripts) => "NAME_MIXED_SCRIPTS",
+        }
+    }
+}
+
+impl ErrorExtensions for ServiceError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", self.code());
+        })
+    }
+}
+
+/// Turn a shared validation result into a coded GraphQL result
+fn validate<T>(result: Result<T, ValidationError>) -> async_graphql::Result<T> {
+    result.map_err(|error| ServiceError::from(error).extend())
+}
+
+/// Turn view results into GraphQL results that carry the STORAGE_ERROR code,
+/// instead of silently treating storage faults as missing data
+trait StorageResultExt<T> {
+    fn storage(self) -> async_graphql::Result<T>;
+}
+
+impl<T> StorageResultExt<T> for Result<T, ViewError> {
+    fn storage(self) -> async_graphql::Result<T> {
+        self.map_err(|error| ServiceError::from(error).extend())
+    }
+}
 
 /// Service for querying game state
 pub struct CrossyChainService {
     state: Arc<CrossyChainState<ServiceRuntime<Self>>>,
+    runtime: ServiceRuntime<Self>,
 }
 
 #[async_trait::async_trait]
 impl Service for CrossyChainService {
-    type Error = ();
+    type Error = ServiceError;
     type State = CrossyChainState<ServiceRuntime<Self>>;
 
-    async fn new(state: Self::State, _runtime: ServiceRuntime<Self>) -> Result<Self, Self::Error> {
+    async fn new(state: Self::State, runtime: ServiceRuntime<Self>) -> Result<Self, Self::Error> {
         Ok(Self {
             state: Arc::new(state),
+            runtime,
         })
     }
 
     async fn handle_query(&self, request: Request) -> Response {
+        // Limits keep a single query (say a full leaderboard with replays)
+        // from tying up the service node
         let schema = Schema::build(
             QueryRoot {
                 state: self.state.clone(),
+                now: self.runtime.system_time().micros() / 1_000_000,
+            },
+            MutationRoot {
+                state: self.state.clone(),
+            },
+            SubscriptionRoot {
+                context: self.runtime.root_view_storage_context(),
             },
-            MutationRoot,
-            async_graphql::EmptySubscription,
         )
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
         .finish();
 
         schema.execute(request).await
@@ -42,121 +159,2331 @@ impl WithServiceAbi for CrossyChainService {
     type Abi = crate::CrossyChainAbi;
 }
 
-/// Leaderboard entry for GraphQL response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Deepest selection set a query may nest
+const MAX_QUERY_DEPTH: usize = 10;
+
+/// Highest total cost a query may have. Plain fields cost 1; replay
+/// payloads cost `REPLAY_FIELD_COST` per returned entry.
+const MAX_QUERY_COMPLEXITY: usize = 5_000;
+
+/// Cost of a field returning a full replay, which can be up to a megabyte
+const REPLAY_FIELD_COST: usize = 50;
+
+/// Cost of a list query returning up to `limit` entries (`default` when
+/// not given), each costing `child_complexity`
+fn list_complexity(limit: Option<i32>, default: i32, child_complexity: usize) -> usize {
+    let limit = limit.unwrap_or(default).max(1) as usize;
+    limit.saturating_mul(child_complexity)
+}
+
+/// Leaderboard entry for GraphQL response. Field names stay snake_case,
+/// as the web client queries them.
+///
+/// Only the wallet and high score are known up front. Every other field
+/// loads the player data on first use, so a query selecting just those two
+/// never reads the stored player or its replay.
 pub struct LeaderboardEntry {
+    state: Arc<CrossyChainState<ServiceRuntime<CrossyChainService>>>,
+    /// Time of the query, as a UNIX timestamp in seconds
+    now: u64,
+    wallet_address: PlayerKey,
+    high_score: u32,
+    player: OnceLock<PlayerData>,
+}
+
+impl LeaderboardEntry {
+    /// An entry whose player data is loaded when a field needs it
+    fn new(query: &QueryRoot, wallet_address: PlayerKey, high_score: u32) -> Self {
+        Self {
+            state: query.state.clone(),
+            now: query.now,
+            wallet_address,
+            high_score,
+            player: OnceLock::new(),
+        }
+    }
+
+    /// An entry for player data that was already loaded
+    fn loaded(query: &QueryRoot, wallet_address: PlayerKey, player: PlayerData) -> Self {
+        let entry = Self::new(query, wallet_address, player.high_score);
+        let _ = entry.player.set(player);
+        entry
+    }
+
+    /// The stored player data, loaded at most once per entry
+    async fn player_data(&self) -> async_graphql::Result<&PlayerData> {
+        if let Some(player) = self.player.get() {
+            return Ok(player);
+        }
+        let player = self.state.players.get(&self.wallet_address).await.storage()?;
+        Ok(self.player.get_or_init(|| player.unwrap_or_default()))
+    }
+}
+
+#[Object(rename_fields = "snake_case")]
+impl LeaderboardEntry {
+    async fn wallet_address(&self) -> PlayerKey {
+        self.wallet_address
+    }
+
+    async fn high_score(&self) -> u32 {
+        self.high_score
+    }
+
+    async fn games_played(&self) -> async_graphql::Result<u32> {
+        Ok(self.player_data().await?.games_played)
+    }
+
+    async fn last_played_at(&self) -> async_graphql::Result<Option<DateTime>> {
+        Ok(self.player_data().await?.last_played_at.map(DateTime))
+    }
+
+    /// When the account was created, at its first registration or score;
+    /// None for accounts created before this was tracked
+    async fn created_at(&self) -> async_graphql::Result<Option<DateTime>> {
+        let created_at = self.player_data().await?.first_seen_at;
+        Ok((created_at != 0).then_some(DateTime(created_at)))
+    }
+
+    /// Whether the account is at least a year old
+    async fn veteran(&self) -> async_graphql::Result<bool> {
+        Ok(self.player_data().await?.is_veteran(self.now))
+    }
+
+    async fn display_name(&self) -> async_graphql::Result<Option<String>> {
+        Ok(self.player_data().await?.display_name.clone())
+    }
+
+    /// Whether an admin verified the player as a known streamer or partner
+    async fn verified(&self) -> async_graphql::Result<bool> {
+        Ok(self.player_data().await?.verified)
+    }
+
+    /// Country the player declared, which picks the regional rules applied
+    async fn country(&self) -> async_graphql::Result<Option<String>> {
+        Ok(self.player_data().await?.country.clone())
+    }
+
+    /// When the display name can next change, if it can't yet
+    async fn rename_available_at(&self) -> async_graphql::Result<Option<DateTime>> {
+        let renamed_at = self.player_data().await?.renamed_at;
+        let available_at = renamed_at.map(|renamed_at| renamed_at.saturating_add(RENAME_COOLDOWN));
+        Ok(available_at.filter(|at| *at > self.now).map(DateTime))
+    }
+
+    #[graphql(complexity = "REPLAY_FIELD_COST")]
+    async fn replay_data(&self) -> async_graphql::Result<Option<String>> {
+        self.player_data()
+            .await?
+            .replay_json()
+            .transpose()
+            .map_err(|_| ServiceError::CorruptReplay.extend())
+    }
+
+    /// Sequence number of the last SaveScore message from `origin_chain` the
+    /// hub settled (the next one must be +1)
+    async fn sequence(&self, origin_chain: ChainId) -> async_graphql::Result<u64> {
+        let key = (origin_chain, self.wallet_address);
+        Ok(self.state.accepted_sequences.get(&key).await.storage()?.unwrap_or(0))
+    }
+
+    /// Client version, platform and format of the stored replay
+    async fn replay_meta(&self) -> async_graphql::Result<Option<ReplayMetadata>> {
+        Ok(self.player_data().await?.replay_meta.clone())
+    }
+
+    /// Format version the replay was recorded in (0 before formats were versioned)
+    async fn replay_format_version(&self) -> async_graphql::Result<u32> {
+        Ok(self.player_data().await?.replay_format_version)
+    }
+
+    /// Summary of the high-score replay, available without loading it
+    async fn replay_summary(&self) -> async_graphql::Result<Option<ReplaySummary>> {
+        Ok(self.player_data().await?.replay_summary.clone())
+    }
+
+    /// Season trophies
+    async fn trophies(&self) -> async_graphql::Result<Vec<Trophy>> {
+        match self.state.trophies.try_load_entry(&self.wallet_address).await.storage()? {
+            Some(log) => {
+                let count = log.count();
+                log.read(0..count).await.storage()
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Sum of every submitted score
+    async fn total_score(&self) -> async_graphql::Result<u64> {
+        Ok(self.player_data().await?.total_score)
+    }
+
+    /// Mean score per game
+    async fn average_score(&self) -> async_graphql::Result<f64> {
+        Ok(average_score(self.player_data().await?))
+    }
+
+    /// Longest run of consecutive days played
+    async fn best_streak(&self) -> async_graphql::Result<u32> {
+        Ok(self.player_data().await?.best_streak)
+    }
+
+    /// Time spent in reported runs (milliseconds)
+    async fn total_playtime(&self) -> async_graphql::Result<u64> {
+        Ok(self.player_data().await?.total_playtime_ms)
+    }
+
+    /// Coin balance
+    async fn coins(&self) -> async_graphql::Result<u64> {
+        Ok(self.player_data().await?.coins)
+    }
+
+    /// Games played and last play time per mode
+    async fn mode_stats(&self) -> async_graphql::Result<Vec<ModeStats>> {
+        Ok(self.player_data().await?.mode_stats.clone())
+    }
+
+    /// Experience points granted by partner applications
+    async fn xp(&self) -> async_graphql::Result<u64> {
+        Ok(self.player_data().await?.xp)
+    }
+
+    /// Whether the player qualifies for a rank on the public leaderboard
+    async fn ranked(&self) -> async_graphql::Result<bool> {
+        Ok(self.ineligibility().await?.is_none())
+    }
+
+    /// Why the player doesn't rank on the public leaderboard, if they don't
+    async fn ineligibility(&self) -> async_graphql::Result<Option<Ineligibility>> {
+        let player = self.player_data().await?;
+        let rules = LeaderboardRules::from_config(self.state.config.get());
+        Ok(rules.check(player, self.now).err())
+    }
+
+    /// Head-to-head rating from ghost races and live races
+    async fn rating(&self) -> async_graphql::Result<PlayerRating> {
+        let rating = self.state.ratings.get(&self.wallet_address).await.storage()?;
+        Ok(rating.unwrap_or_default())
+    }
+
+    /// How far the player is through placement, and the rating and brackets
+    /// it gave them
+    async fn placement(&self) -> async_graphql::Result<PlacementProgress> {
+        let player = self.player_data().await?;
+        Ok(PlacementProgress {
+            runs_played: player.games_played.min(PLACEMENT_RUNS),
+            runs_required: PLACEMENT_RUNS,
+            calibration_rating: player.calibration_rating,
+            level_bracket: player.level_bracket,
+            matchmaking_bucket: player.matchmaking_bucket(),
+        })
+    }
+
+    /// Invalidations of the player's scores, oldest first, starting after the
+    /// one at index `after`, with the status of any appeal against them
+    async fn invalidations(
+        &self,
+        limit: Option<i32>,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<InvalidationRecord>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let log = self.state.wallet_invalidations.try_load_entry(&self.wallet_address).await;
+        let Some(log) = log.storage()? else {
+            return Ok(Vec::new());
+        };
+        let count = log.count();
+        let start = after
+            .map_or(0, |after| usize::try_from(after).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        let mut records = Vec::new();
+        for id in log.read(start..end).await.storage()? {
+            let invalidation = self.state.score_invalidations.get(id as usize).await;
+            let Some(invalidation) = invalidation.storage()? else {
+                continue;
+            };
+            let appeal = self.state.appeals.get(&id).await.storage()?;
+            records.push(InvalidationRecord {
+                invalidation,
+                appeal,
+            });
+        }
+        Ok(records)
+    }
+
+    /// Whether the high score awaits verification and is rolled back unless
+    /// confirmed by block `provisional_until`
+    async fn provisional(&self) -> async_graphql::Result<bool> {
+        Ok(self.player_data().await?.provisional.is_some())
+    }
+
+    /// Block height by which verification must confirm a provisional high score
+    async fn provisional_until(&self) -> async_graphql::Result<Option<u64>> {
+        let provisional = self.player_data().await?.provisional.as_ref();
+        Ok(provisional.map(|provisional| provisional.confirm_by_height))
+    }
+
+    /// Verification of the current high-score replay, if it was queued
+    async fn verification_status(&self) -> async_graphql::Result<Option<VerificationStatus>> {
+        let by_wallet = self.state.verification_by_wallet.get(&self.wallet_address).await;
+        let Some(id) = by_wallet.storage()? else {
+            return Ok(None);
+        };
+        let item = self.state.verification_queue.get(&id).await.storage()?;
+        Ok(item.map(|item| item.status))
+    }
+}
+
+/// Execution metrics of one operation type
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct OpMetricsEntry {
+    pub operation: String,
+    pub metrics: OpMetrics,
+}
+
+/// A player's place on the head-to-head rating leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RatingStanding {
+    pub rank: u32,
+    pub wallet_address: PlayerKey,
+    pub rating: PlayerRating,
+}
+
+/// A player's progress through the placement runs
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlacementProgress {
+    pub runs_played: u32,
+    pub runs_required: u32,
+    /// Set once all placement runs are played
+    pub calibration_rating: Option<u32>,
+    pub level_bracket: Option<LevelBracket>,
+    /// Bucket live races pair the player within
+    pub matchmaking_bucket: u32,
+}
+
+/// A player's progress through onboarding, for the client's tutorial
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct OnboardingStatus {
+    /// Completed steps, in the order they were completed
+    pub completed: Vec<OnboardingStep>,
+    /// Steps left to complete, in tutorial order
+    pub remaining: Vec<OnboardingStep>,
+    pub finished: bool,
+    /// When the last step was completed (UNIX timestamp)
+    pub completed_at: Option<u64>,
+    /// Coins granted for completing onboarding
+    pub reward_coins: u64,
+}
+
+/// A score invalidation and the appeal against it, if any
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct InvalidationRecord {
+    pub invalidation: ScoreInvalidation,
+    pub appeal: Option<Appeal>,
+}
+
+/// A page of the appeals queue
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AppealPage {
+    pub appeals: Vec<Appeal>,
+    /// Pass as `cursor` to read the next page; None once the last appeal was read
+    pub next_cursor: Option<u64>,
+}
+
+/// Mean score per game, 0 before the first game
+fn average_score(player: &PlayerData) -> f64 {
+    if player.games_played == 0 {
+        return 0.0;
+    }
+    player.total_score as f64 / player.games_played as f64
+}
+
+/// Featured replay entry for the community carousel
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FeaturedReplayEntry {
     pub wallet_address: String,
-    pub high_score: u32,
-    pub games_played: u32,
-    pub last_played_at: Option<u64>,
     pub display_name: Option<String>,
+    pub score: u32,
+    pub featured_at: DateTime,
+    #[graphql(complexity = "REPLAY_FIELD_COST")]
     pub replay_data: Option<String>,
 }
 
+/// Ordering for the community replays tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ReplaySort {
+    Likes,
+    Views,
+}
+
+/// Replay entry for the community replays tab
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TopReplayEntry {
+    pub wallet_address: String,
+    pub display_name: Option<String>,
+    pub score: u32,
+    pub likes: u64,
+    pub views: u64,
+}
+
+/// Largest slice of a replay returned by one `replayChunk` query (bytes)
+const MAX_REPLAY_CHUNK: u64 = 64 * 1024;
+
+/// A slice of a stored replay's JSON, for streaming large replays
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ReplayChunk {
+    /// Byte offset of `data` in the replay
+    pub offset: u64,
+    pub data: String,
+    /// Size of the whole replay (bytes)
+    pub total_size: u64,
+    /// Offset to request next, or None once the replay is complete
+    pub next_offset: Option<u64>,
+}
+
+/// Serialization of an exported leaderboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum LeaderboardExportFormat {
+    Json,
+    Csv,
+}
+
+/// One exported leaderboard row; replays are left out to keep dumps small
+#[derive(Debug, Clone, Serialize)]
+struct LeaderboardExportRow {
+    rank: usize,
+    wallet_address: String,
+    display_name: Option<String>,
+    high_score: u32,
+    games_played: u32,
+    last_played_at: Option<u64>,
+    total_score: u64,
+    best_streak: u32,
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Hex SHA-256 of the serde formats of `Operation` and `Message`
+fn abi_hash() -> serde_reflection::Result<String> {
+    // Wallets are parsed from their string form, so traced strings must be one
+    let config = TracerConfig::default().default_string_value("0".repeat(64));
+    let mut tracer = Tracer::new(config);
+    let samples = Samples::new();
+    tracer.trace_type::<Operation>(&samples)?;
+    tracer.trace_type::<Message>(&samples)?;
+    let registry = tracer.registry()?;
+    let formats = serde_json::to_vec(&registry)
+        .map_err(|error| serde_reflection::Error::Custom(error.to_string()))?;
+    Ok(Sha256::digest(formats).iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Versions of the running application, for spotting mismatched deployments
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VersionInfo {
+    /// Version of the application crate
+    pub crate_version: String,
+    /// Hex SHA-256 of the serde formats of the operations and messages;
+    /// changes whenever their encoding does, so clients and other chains
+    /// built against another version would be misread
+    pub abi_hash: String,
+    /// Layout version of the stored state this version expects
+    pub state_schema_version: u32,
+    /// Layout version the stored state has been migrated to; below
+    /// `state_schema_version` while a migration is under way
+    pub stored_schema_version: u32,
+    /// Version of the active game config
+    pub config_version: u32,
+}
+
+/// A replay exported as a portable container
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ReplayExport {
+    /// Suggested name for the downloaded file
+    pub file_name: String,
+    pub content_type: String,
+    /// Hex-encoded container: the magic header followed by CBOR
+    pub data: String,
+}
+
+/// Hub synchronisation state of a wallet's scores on this chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct HubSyncStatus {
+    /// True while some scores bounced off the hub and wait to be resent
+    pub pending_hub_sync: bool,
+    /// Number of scores waiting to be resent
+    pub pending_scores: u32,
+    /// Sequence number of the last score forwarded to the hub
+    pub last_sequence: u64,
+}
+
+/// Community statistics across every player on this chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FunStats {
+    /// How all reported runs ended
+    pub deaths: DeathTally,
+    /// The cause that ended the most runs
+    pub deadliest_cause: Option<DeathCause>,
+}
+
+/// A catalog quest with a player's progress for today
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ActiveQuest {
+    pub quest: Quest,
+    pub progress: u64,
+    pub completed: bool,
+    pub claimed: bool,
+}
+
+/// A partner application on the caller allowlist
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CallerAllowance {
+    pub application_id: ApplicationId,
+    pub capabilities: Vec<PartnerCapability>,
+}
+
+/// The archived top of a past day
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DailyTop {
+    /// Start of the day (UTC)
+    pub date: DateTime,
+    pub standings: Vec<DailyStanding>,
+}
+
+/// The challenge modifier running this week
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CurrentModifier {
+    pub modifier: ChallengeModifier,
+    /// When the challenge week ends
+    pub ends_at: DateTime,
+}
+
+/// A player's best score under a challenge modifier
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ModifierScoreEntry {
+    pub wallet_address: PlayerKey,
+    pub display_name: Option<String>,
+    pub score: u32,
+}
+
+/// Most players a single `stateConsistency` query samples
+const MAX_CONSISTENCY_SAMPLE: i32 = 1_000;
+
+/// Kind of drift between the players map and what is derived from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum DiscrepancyKind {
+    /// The leaderboard index holds another score than the player's high score
+    LeaderboardScoreMismatch,
+    /// The player's high score belongs within the leaderboard cap but isn't indexed
+    MissingFromLeaderboard,
+    /// The leaderboard index holds a wallet with no player data
+    IndexedWithoutPlayer,
+    /// The leaderboard index holds more entries than the leaderboard cap
+    LeaderboardOverCap,
+    /// The replay holders disagree with the player's stored replay
+    ReplayHolderMismatch,
+    /// The all-time highest score record is below a player's high score
+    RecordBehind,
+    /// The chain's death tally counts fewer runs than the sampled players
+    DeathTallyBehind,
+    /// The leaderboard commitment doesn't match the leaderboard index
+    CommitmentStale,
+}
+
+/// One inconsistency found by `stateConsistency`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    /// Player concerned, for per-player checks
+    pub wallet_address: Option<PlayerKey>,
+    /// Expected and found values
+    pub detail: String,
+}
+
+/// Result of a `stateConsistency` check
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ConsistencyReport {
+    pub players_checked: u32,
+    /// Pass as `cursor` to check the next sample; None once the last player was checked
+    pub next_cursor: Option<u64>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
 /// GraphQL query root
 struct QueryRoot {
     state: Arc<CrossyChainState<ServiceRuntime<CrossyChainService>>>,
+    /// Time of the query, as a UNIX timestamp in seconds
+    now: u64,
 }
 
 #[Object]
 impl QueryRoot {
     /// Get leaderboard with top N players sorted by high score
-    async fn leaderboard(&self, top_n: Option<i32>) -> Vec<LeaderboardEntry> {
-        let limit = top_n.unwrap_or(10).max(1).min(100) as usize;
-        
-        let mut entries = Vec::new();
-        
-        // Iterate through all players
-        if let Ok(keys) = self.state.players.keys().await {
-            for key in keys {
-                if let Ok(Some(player)) = self.state.players.get(&key).await {
-                    entries.push(LeaderboardEntry {
-                        wallet_address: key.clone(),
-                        high_score: player.high_score,
-                        games_played: player.games_played,
-                        last_played_at: player.last_played_at,
-                        display_name: player.display_name.clone(),
-                        replay_data: player.replay_data.clone(),
-                    });
-                }
-            }
-        }
-        
-        // Sort by high score descending
-        entries.sort_by(|a, b| b.high_score.cmp(&a.high_score));
-        
-        // Return top N
-        entries.into_iter().take(limit).collect()
+    #[graphql(complexity = "list_complexity(top_n, 10, child_complexity)")]
+    async fn leaderboard(
+        &self,
+        top_n: Option<i32>,
+    ) -> async_graphql::Result<Vec<LeaderboardEntry>> {
+        self.top_players(top_n.unwrap_or(10)).await
     }
 
     /// Get player data by wallet address
-    async fn player(&self, wallet_address: String) -> Option<LeaderboardEntry> {
-        if let Ok(Some(player)) = self.state.players.get(&wallet_address).await {
-            Some(LeaderboardEntry {
-                wallet_address,
+    async fn player(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<LeaderboardEntry>> {
+        // Secondary owners show the profile they are linked to
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let Some(player) = self.state.player_or_archived(&key).await.storage()? else {
+            return Ok(None);
+        };
+        Ok(Some(LeaderboardEntry::loaded(self, key, player)))
+    }
+
+    /// Get a player's best scores, highest first
+    async fn personal_bests(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Vec<PersonalBest>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let player = self.state.players.get(&key).await.storage()?;
+        Ok(player.map(|player| player.personal_bests).unwrap_or_default())
+    }
+
+    /// Get how a player's reported runs ended
+    async fn death_stats(&self, wallet_address: PlayerKey) -> async_graphql::Result<DeathTally> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let player = self.state.players.get(&key).await.storage()?;
+        Ok(player.map(|player| player.death_tally).unwrap_or_default())
+    }
+
+    /// Get community statistics for the fun stats page
+    async fn fun_stats(&self) -> FunStats {
+        let deaths = self.state.death_tally.get().clone();
+        FunStats {
+            deadliest_cause: deaths.deadliest(),
+            deaths,
+        }
+    }
+
+    /// Get total number of registered players
+    async fn player_count(&self) -> async_graphql::Result<i32> {
+        Ok(self.state.players.count().await.storage()? as i32)
+    }
+
+    /// Get the ghost trace of a player's high-score run, without the full replay
+    async fn ghost(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<Vec<GhostPoint>>> {
+        let Some(player) = self.state.players.get(&wallet_address).await.storage()? else {
+            return Ok(None);
+        };
+        let Some(replay_json) = player.replay_json() else {
+            return Ok(None);
+        };
+        let replay_json = replay_json.map_err(|_| ServiceError::CorruptReplay.extend())?;
+        let recording = Recording::from_json(&replay_json)
+            .ok_or_else(|| ServiceError::CorruptReplay.extend())?;
+        Ok(Some(ghost_trace(&recording)))
+    }
+
+    /// Get up to `length` bytes (64KiB at most) of a player's high-score replay
+    /// starting at `offset`, so large replays can be loaded progressively.
+    /// Chunks end on a character boundary; continue from `nextOffset`.
+    async fn replay_chunk(
+        &self,
+        wallet_address: PlayerKey,
+        offset: u64,
+        length: Option<u64>,
+    ) -> async_graphql::Result<Option<ReplayChunk>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let Some(player) = self.state.players.get(&key).await.storage()? else {
+            return Ok(None);
+        };
+        let Some(replay) = player.replay_json() else {
+            return Ok(None);
+        };
+        let replay = replay.map_err(|_| ServiceError::CorruptReplay.extend())?;
+
+        let total_size = replay.len();
+        let start = usize::try_from(offset).unwrap_or(usize::MAX);
+        if start > total_size || !replay.is_char_boundary(start) {
+            return Err(ServiceError::InvalidReplayOffset.extend());
+        }
+        let length = length.unwrap_or(MAX_REPLAY_CHUNK).clamp(1, MAX_REPLAY_CHUNK) as usize;
+        let mut end = start.saturating_add(length).min(total_size);
+        while !replay.is_char_boundary(end) {
+            end += 1;
+        }
+
+        Ok(Some(ReplayChunk {
+            offset,
+            data: replay[start..end].to_string(),
+            total_size: total_size as u64,
+            next_offset: (end < total_size).then_some(end as u64),
+        }))
+    }
+
+    /// Dump the top `top_n` leaderboard entries as JSON or CSV, without replays
+    #[graphql(complexity = "list_complexity(top_n, 100, 1)")]
+    async fn export_leaderboard(
+        &self,
+        format: LeaderboardExportFormat,
+        top_n: Option<i32>,
+    ) -> async_graphql::Result<String> {
+        let mut rows = Vec::new();
+        for (index, entry) in self.top_players(top_n.unwrap_or(100)).await?.iter().enumerate() {
+            let player = entry.player_data().await?;
+            rows.push(LeaderboardExportRow {
+                rank: index + 1,
+                wallet_address: entry.wallet_address.to_string(),
+                display_name: player.display_name.clone(),
                 high_score: player.high_score,
                 games_played: player.games_played,
                 last_played_at: player.last_played_at,
-                display_name: player.display_name.clone(),
-                replay_data: player.replay_data.clone(),
-            })
-        } else {
-            None
+                total_score: player.total_score,
+                best_streak: player.best_streak,
+            });
+        }
+
+        match format {
+            LeaderboardExportFormat::Json => Ok(serde_json::to_string(&rows)?),
+            LeaderboardExportFormat::Csv => {
+                let mut csv = String::from(
+                    "rank,wallet_address,display_name,high_score,games_played,last_played_at,\
+                     total_score,best_streak\n",
+                );
+                for row in rows {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{},{},{}\n",
+                        row.rank,
+                        row.wallet_address,
+                        csv_field(row.display_name.as_deref().unwrap_or("")),
+                        row.high_score,
+                        row.games_played,
+                        row.last_played_at.map(|at| at.to_string()).unwrap_or_default(),
+                        row.total_score,
+                        row.best_streak,
+                    ));
+                }
+                Ok(csv)
+            }
         }
     }
 
-    /// Get total number of registered players
-    async fn player_count(&self) -> i32 {
-        if let Ok(keys) = self.state.players.keys().await {
-            keys.len() as i32
+    /// Export a player's high-score replay with its metadata, game config and
+    /// submitting block, so it can be shared and re-verified elsewhere
+    async fn export_replay(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<ReplayExport>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let Some(player) = self.state.players.get(&key).await.storage()? else {
+            return Ok(None);
+        };
+        let Some(replay) = player.replay_json() else {
+            return Ok(None);
+        };
+        let replay = replay.map_err(|_| ServiceError::CorruptReplay.extend())?;
+
+        let current = *self.state.config_version.get();
+        let config_version = player
+            .replay_meta
+            .as_ref()
+            .map_or(current, |meta| meta.config_version);
+        let game_config = if config_version == current {
+            Some(self.state.game_config.get().clone())
         } else {
-            0
-        }
+            self.state.game_config_history.get(&config_version).await.storage()?
+        };
+
+        let container = ReplayContainer {
+            container_version: REPLAY_CONTAINER_VERSION,
+            wallet_address: key.to_string(),
+            display_name: player.display_name,
+            score: player.high_score,
+            metadata: player.replay_meta,
+            summary: player.replay_summary,
+            config_version,
+            game_config,
+            origin: player.replay_origin,
+            replay,
+        };
+        Ok(Some(ReplayExport {
+            file_name: format!("crossy-{}-{}.crsyrpl", key, player.high_score),
+            content_type: "application/cbor".to_string(),
+            data: container.encode().iter().map(|byte| format!("{byte:02x}")).collect(),
+        }))
     }
-}
 
-/// GraphQL mutation root for triggering contract operations
-struct MutationRoot;
+    /// Get wallet A's ghost race record against wallet B, counting races in both directions
+    async fn ghost_race_record(
+        &self,
+        wallet_a: PlayerKey,
+        wallet_b: PlayerKey,
+    ) -> async_graphql::Result<GhostRaceRecord> {
+        let a_vs_b = self
+            .state
+            .ghost_race_tallies
+            .get(&(wallet_a, wallet_b))
+            .await
+            .storage()?
+            .unwrap_or_default();
+        let b_vs_a = self
+            .state
+            .ghost_race_tallies
+            .get(&(wallet_b, wallet_a))
+            .await
+            .storage()?
+            .unwrap_or_default();
 
-#[Object]
-impl MutationRoot {
-    /// Save a player's score on-chain
-    /// This triggers the SaveScore operation in the contract
-    async fn save_score(
+        Ok(GhostRaceRecord {
+            wins: a_vs_b.wins + b_vs_a.losses,
+            losses: a_vs_b.losses + b_vs_a.wins,
+            draws: a_vs_b.draws + b_vs_a.draws,
+        })
+    }
+
+    /// Get a race by ID
+    async fn race(&self, race_id: u64) -> async_graphql::Result<Option<Race>> {
+        self.state.races.get(&race_id).await.storage()
+    }
+
+    /// Get the unfinished race a wallet is playing, if any
+    async fn active_race(&self, wallet_address: String) -> async_graphql::Result<Option<Race>> {
+        let Some(race_id) = self.state.active_races.get(&wallet_address).await.storage()? else {
+            return Ok(None);
+        };
+        self.state.races.get(&race_id).await.storage()
+    }
+
+    /// Get the latest live position of a race participant
+    async fn race_position(
         &self,
-        score: i32,
-        timestamp: i32,
-        replay_data: Option<String>,
-    ) -> bool {
-        // Note: In Linera, GraphQL mutations trigger contract operations
-        // The actual operation is executed by the contract, not the service
-        // This method just defines the GraphQL schema
-        // The client calls backend.query("mutation { saveScore(...) }")
-        // which creates a block with the SaveScore operation
-        // The replay_data is a JSON string of the game recording
-        true
+        race_id: u64,
+        wallet_address: String,
+    ) -> async_graphql::Result<Option<GhostPoint>> {
+        self.state
+            .race_positions
+            .get(&(race_id, wallet_address))
+            .await
+            .storage()
     }
 
-    /// Register a player with optional display name
-    /// This triggers the RegisterPlayer operation in the contract
-    async fn register_player(&self, display_name: Option<String>) -> bool {
-        // Validate display name if provided
-        if let Some(ref name) = display_name {
-            // Limit display name length
-            if name.len() > 30 {
-                return false;
-            }
-            // Ensure it's not empty or just whitespace
-            if name.trim().is_empty() {
-                return false;
-            }
-        }
-        
-        // Note: In Linera, GraphQL mutations trigger contract operations
-        // The actual operation is executed by the contract, not the service
-        // This method just defines the GraphQL schema
-        // The client calls backend.query("mutation { registerPlayer(...) }")
+    /// Get the latest spectator snapshot this chain received for a race
+    async fn race_snapshot(&self, race_id: u64) -> async_graphql::Result<Option<RaceSnapshot>> {
+        self.state.race_snapshots.get(&race_id).await.storage()
+    }
+
+    /// Get the latest race pairing delivered to this chain for a wallet
+    async fn race_match(&self, wallet_address: String) -> async_graphql::Result<Option<RaceMatch>> {
+        self.state.race_matches.get(&wallet_address).await.storage()
+    }
+
+    /// Get the number of the season currently being played
+    async fn current_season(&self) -> u32 {
+        *self.state.current_season.get()
+    }
+
+    /// Get a wallet's results in a mode (classic by default) and season (the
+    /// current one by default). In the current season, the best score is
+    /// shown decayed if the wallet has been inactive.
+    async fn season_score(
+        &self,
+        wallet_address: PlayerKey,
+        season: Option<u32>,
+        mode: Option<GameMode>,
+    ) -> async_graphql::Result<ScopedScore> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let current_season = *self.state.current_season.get();
+        let season = season.unwrap_or(current_season);
+        let scope = ScoreScope::new(mode.unwrap_or_default(), season);
+        let mut score = self.state.scoped_score(scope, &key).await.storage()?;
+        if season == current_season {
+            score.best_score = score.standing(self.state.config.get().season_decay, self.now);
+        }
+        Ok(score)
+    }
+
+    /// Get chain-wide totals and today's top score, for a live stats banner
+    async fn global_stats(&self) -> GlobalStats {
+        let mut stats = self.state.global_stats.get().clone();
+        let today = self.now / SECONDS_PER_DAY;
+        if stats.top_day != today {
+            stats.top_day = today;
+            stats.top_score_today = 0;
+            stats.top_wallet_today = None;
+        }
+        stats
+    }
+
+    /// Get the top of the day `date` falls in (UTC), up to `limit` players
+    /// (default and at most 20)
+    async fn daily_top(
+        &self,
+        date: DateTime,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<DailyStanding>> {
+        let limit = limit.unwrap_or(DAILY_TOP_N as i32).clamp(1, DAILY_TOP_N as i32) as usize;
+        let day = date.seconds() / SECONDS_PER_DAY;
+        let mut standings = self.state.daily_standings(day).await.storage()?;
+        standings.truncate(limit);
+        Ok(standings)
+    }
+
+    /// Get the archived tops of the same calendar day as `date` in earlier
+    /// years, most recent first
+    async fn on_this_day(&self, date: DateTime) -> async_graphql::Result<Vec<DailyTop>> {
+        let day = date.seconds() / SECONDS_PER_DAY;
+        let (_, month, day_of_month) = civil_from_days(day as i64);
+        let mut tops = Vec::new();
+        for archived in self.state.daily_tops.indices().await.storage()? {
+            let (_, archived_month, archived_day) = civil_from_days(archived as i64);
+            if archived >= day || (archived_month, archived_day) != (month, day_of_month) {
+                continue;
+            }
+            if let Some(standings) = self.state.daily_tops.get(&archived).await.storage()? {
+                tops.push(DailyTop {
+                    date: DateTime(archived * SECONDS_PER_DAY),
+                    standings,
+                });
+            }
+        }
+        tops.sort_by(|a, b| b.date.cmp(&a.date));
+        Ok(tops)
+    }
+
+    /// Get the all-time records. A record held by a player the leaderboard
+    /// rules now keep off is left out.
+    async fn records(&self) -> async_graphql::Result<AllTimeRecords> {
+        let mut records = self.state.records.get().clone();
+        for record in [
+            &mut records.highest_score,
+            &mut records.most_games_in_a_day,
+            &mut records.longest_streak,
+        ] {
+            let Some(holder) = record else {
+                continue;
+            };
+            if !self.passes_index_rules(&holder.wallet_address).await? {
+                *record = None;
+            }
+        }
+        Ok(records)
+    }
+
+    /// Get every closed season, oldest first
+    async fn seasons(&self) -> async_graphql::Result<Vec<SeasonRecord>> {
+        let mut seasons = Vec::new();
+        for season in self.state.season_history.indices().await.storage()? {
+            if let Some(record) = self.state.season_history.get(&season).await.storage()? {
+                seasons.push(record);
+            }
+        }
+        seasons.sort_by_key(|record| record.season);
+        Ok(seasons)
+    }
+
+    /// Get the final top N of a closed season, leaving out players the
+    /// leaderboard rules now keep off; the others keep their final rank
+    async fn season_leaderboard(
+        &self,
+        season_id: u32,
+        top_n: Option<i32>,
+    ) -> async_graphql::Result<Vec<SeasonStanding>> {
+        let limit = top_n.unwrap_or(10).max(1).min(100) as usize;
+        let standings = self
+            .state
+            .season_leaderboards
+            .get(&season_id)
+            .await
+            .storage()?
+            .unwrap_or_default();
+        let mut shown = Vec::new();
+        for standing in standings {
+            if shown.len() == limit {
+                break;
+            }
+            if self.passes_index_rules(&standing.wallet_address).await? {
+                shown.push(standing);
+            }
+        }
+        Ok(shown)
+    }
+
+    /// Get the earliest time the current season can be closed (UNIX timestamp)
+    async fn season_ends_at(&self) -> DateTime {
+        let started_at = *self.state.season_started_at.get();
+        DateTime(started_at.saturating_add(self.state.config.get().season_length))
+    }
+
+    /// Get the rewards a wallet can still claim, including ones past expiry
+    /// that have not been returned to the pool yet
+    async fn claimable_rewards(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Vec<ClaimableReward>> {
+        let mut rewards = Vec::new();
+        let reward_ids = self.state.wallet_rewards.get(&wallet_address).await.storage()?;
+        for reward_id in reward_ids.unwrap_or_default() {
+            if let Some(reward) = self.state.rewards.get(&reward_id).await.storage()? {
+                rewards.push(reward);
+            }
+        }
+
+        Ok(rewards)
+    }
+
+    /// Get up to `limit` featured replays, most recently featured first
+    #[graphql(complexity = "list_complexity(limit, 20, child_complexity)")]
+    async fn featured_replays(
+        &self,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<FeaturedReplayEntry>> {
+        let limit = limit.unwrap_or(20).max(1).min(100) as usize;
+        let mut featured_replays = Vec::new();
+        for wallet in self.state.featured_replays.indices().await.storage()? {
+            if let Some(featured) = self.state.featured_replays.get(&wallet).await.storage()? {
+                featured_replays.push((wallet, featured));
+            }
+        }
+        featured_replays.sort_by(|a, b| b.1.featured_at.cmp(&a.1.featured_at));
+
+        // Only the players of the returned entries are read
+        let mut entries = Vec::new();
+        for (wallet, featured) in featured_replays.into_iter().take(limit) {
+            let player = self.state.players.get(&wallet).await.storage()?.unwrap_or_default();
+            entries.push(FeaturedReplayEntry {
+                wallet_address: wallet.to_string(),
+                display_name: player.display_name,
+                score: featured.score,
+                featured_at: DateTime(featured.featured_at),
+                replay_data: featured.replay_json().ok(),
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Get the most liked or most watched replays
+    #[graphql(complexity = "list_complexity(limit, 10, child_complexity)")]
+    async fn top_replays(
+        &self,
+        sort_by: ReplaySort,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<TopReplayEntry>> {
+        let limit = limit.unwrap_or(10).max(1).min(100) as usize;
+        let mut entries = Vec::new();
+
+        for wallet in self.state.replay_stats.indices().await.storage()? {
+            let Some(stats) = self.state.replay_stats.get(&wallet).await.storage()? else {
+                continue;
+            };
+            let Some(player) = self.state.players.get(&wallet).await.storage()? else {
+                continue;
+            };
+            // Counters of a replaced replay are stale until the next like/view resets them
+            if player.replay_data.is_none() || player.high_score != stats.replay_score {
+                continue;
+            }
+            entries.push(TopReplayEntry {
+                wallet_address: wallet.to_string(),
+                display_name: player.display_name,
+                score: player.high_score,
+                likes: stats.likes,
+                views: stats.views,
+            });
+        }
+
+        match sort_by {
+            ReplaySort::Likes => entries.sort_by(|a, b| b.likes.cmp(&a.likes)),
+            ReplaySort::Views => entries.sort_by(|a, b| b.views.cmp(&a.views)),
+        }
+        Ok(entries.into_iter().take(limit).collect())
+    }
+
+    /// Get replay retention statistics
+    async fn pruning_stats(&self) -> PruningStats {
+        self.state.pruning_stats.get().clone()
+    }
+
+    /// Get the rules deciding who ranks on the public leaderboard
+    async fn leaderboard_rules(&self) -> LeaderboardRules {
+        LeaderboardRules::from_config(self.state.config.get())
+    }
+
+    /// Get the totals of the players archived for inactivity
+    async fn archive_stats(&self) -> ArchiveStats {
+        self.state.archive_stats.get().clone()
+    }
+
+    /// Get how season standings of inactive players decay, if they do
+    async fn season_decay(&self) -> Option<SeasonDecay> {
+        self.state.config.get().season_decay
+    }
+
+    /// Get the actions waiting for their due time, soonest first
+    async fn scheduled_actions(&self) -> async_graphql::Result<Vec<ScheduledAction>> {
+        let mut actions = Vec::new();
+        for id in self.state.scheduled_actions.indices().await.storage()? {
+            if let Some(action) = self.state.scheduled_actions.get(&id).await.storage()? {
+                actions.push(action);
+            }
+        }
+        actions.sort_by_key(|action| (action.due_at, action.id));
+        Ok(actions)
+    }
+
+    /// Get the scheduled actions that failed when they fired, oldest failure first
+    async fn failed_scheduled_actions(&self) -> async_graphql::Result<Vec<FailedAction>> {
+        let mut failed = Vec::new();
+        for id in self.state.failed_actions.indices().await.storage()? {
+            if let Some(action) = self.state.failed_actions.get(&id).await.storage()? {
+                failed.push(action);
+            }
+        }
+        failed.sort_by_key(|failed| (failed.failed_at, failed.action.id));
+        Ok(failed)
+    }
+
+    /// Get the event calendar entries that haven't ended, soonest first
+    async fn upcoming_events(
+        &self,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<CalendarEntry>> {
+        let limit = limit.unwrap_or(20).max(1).min(100) as usize;
+        let mut entries = Vec::new();
+        for id in self.state.calendar.indices().await.storage()? {
+            if let Some(entry) = self.state.calendar.get(&id).await.storage()? {
+                if entry.ends_at > self.now {
+                    entries.push(entry);
+                }
+            }
+        }
+        for key in self.state.remote_calendar.indices().await.storage()? {
+            if let Some(entry) = self.state.remote_calendar.get(&key).await.storage()? {
+                if entry.ends_at > self.now {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort_by_key(|entry| (entry.starts_at, entry.id));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Get the running leaderboard freeze, if any
+    async fn leaderboard_freeze(&self) -> Option<LeaderboardFreeze> {
+        let freeze = self.state.leaderboard_freeze.get().clone();
+        freeze.filter(|freeze| freeze.is_active(self.now))
+    }
+
+    /// Get the wallets whose leaderboard entry waits for the freeze to lift
+    async fn pending_leaderboard_updates(&self) -> async_graphql::Result<Vec<PlayerKey>> {
+        self.state.frozen_leaderboard_updates.indices().await.storage()
+    }
+
+    /// Get the number of runs a freeze held back from the score indexes
+    async fn held_run_count(&self) -> u64 {
+        self.state.held_runs.count() as u64
+    }
+
+    /// Get the prize pool rewards are paid out of: funding, loot box sales,
+    /// entry fees and submission fees, less the rewards granted
+    async fn prize_pool(&self) -> Amount {
+        *self.state.prize_pool.get()
+    }
+
+    /// Get the latest score attestation this chain received for a wallet
+    async fn score_attestation(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<OracleAttestation>> {
+        self.state.oracle_attestations.get(&wallet_address).await.storage()
+    }
+
+    /// Get every allowlisted partner application and the privileged requests it may make
+    async fn caller_allowances(&self) -> async_graphql::Result<Vec<CallerAllowance>> {
+        let mut allowances = Vec::new();
+        for application_id in self.state.partner_capabilities.indices().await.storage()? {
+            let capabilities = self.state.partner_capabilities.get(&application_id).await;
+            if let Some(capabilities) = capabilities.storage()? {
+                allowances.push(CallerAllowance {
+                    application_id,
+                    capabilities,
+                });
+            }
+        }
+        Ok(allowances)
+    }
+
+    /// Get the privileged requests a partner application is allowlisted for
+    async fn partner_capabilities(
+        &self,
+        application_id: ApplicationId,
+    ) -> async_graphql::Result<Vec<PartnerCapability>> {
+        let capabilities = self.state.partner_capabilities.get(&application_id).await.storage()?;
+        Ok(capabilities.unwrap_or_default())
+    }
+
+    /// Get the coins a partner application holds for a wallet, and when the
+    /// player can reclaim them
+    async fn partner_coin_hold(
+        &self,
+        application_id: ApplicationId,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<CoinHold>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = (application_id, linked.unwrap_or(wallet_address));
+        Ok(self.state.partner_coin_holds.get(&key).await.storage()?)
+    }
+
+    /// Get whether a wallet was granted the welcome bonus
+    async fn welcome_bonus_claimed(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<bool> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        self.state.welcome_bonus_claimed.contains(&key).await.storage()
+    }
+
+    /// Get a wallet's progress through onboarding
+    async fn onboarding_status(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<OnboardingStatus> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let onboarding = self.state.onboarding.get(&key).await.storage()?.unwrap_or_default();
+        Ok(OnboardingStatus {
+            remaining: onboarding.remaining(),
+            finished: onboarding.is_finished(),
+            completed: onboarding.completed,
+            completed_at: onboarding.completed_at,
+            reward_coins: ONBOARDING_REWARD_COINS,
+        })
+    }
+
+    /// Get the wallets a player added as friends, oldest first
+    async fn friends(&self, wallet_address: PlayerKey) -> async_graphql::Result<Vec<PlayerKey>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        Ok(self.state.friends.get(&key).await.storage()?.unwrap_or_default())
+    }
+
+    /// Get the fee charged for every accepted score submission
+    async fn submission_fee(&self) -> Amount {
+        self.state.config.get().submission_fee
+    }
+
+    /// Get the oldest client version allowed to submit scores
+    async fn min_client_version(&self) -> Option<String> {
+        self.state.config.get().min_client_version.clone()
+    }
+
+    /// Get the versions of the running application and its state
+    async fn version(&self) -> VersionInfo {
+        let abi_hash = abi_hash().unwrap_or_default();
+        VersionInfo {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            abi_hash,
+            state_schema_version: STATE_SCHEMA_VERSION,
+            stored_schema_version: *self.state.schema_version.get(),
+            config_version: *self.state.config_version.get(),
+        }
+    }
+
+    /// Get the game parameters clients must play with for replays to verify.
+    /// Pass a version to get the config an older replay was played under.
+    async fn game_config(&self, version: Option<u32>) -> async_graphql::Result<Option<GameConfig>> {
+        let current = *self.state.config_version.get();
+        match version {
+            None => Ok(Some(self.state.game_config.get().clone())),
+            Some(version) if version == current => Ok(Some(self.state.game_config.get().clone())),
+            Some(version) => self.state.game_config_history.get(&version).await.storage(),
+        }
+    }
+
+    /// Get the version of the current game config
+    async fn config_version(&self) -> u32 {
+        *self.state.config_version.get()
+    }
+
+    /// Get whether a wallet's scores have all reached the hub chain
+    async fn hub_sync_status(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<HubSyncStatus> {
+        let pending_scores = match self
+            .state
+            .pending_hub_sync
+            .try_load_entry(&wallet_address)
+            .await
+            .storage()?
+        {
+            Some(pending) => pending.count().await.storage()? as u32,
+            None => 0,
+        };
+        let last_sequence = self
+            .state
+            .hub_sequences
+            .get(&wallet_address)
+            .await
+            .storage()?
+            .unwrap_or(0);
+
+        Ok(HubSyncStatus {
+            pending_hub_sync: pending_scores > 0,
+            pending_scores,
+            last_sequence,
+        })
+    }
+
+    /// Get the profile owner a wallet is linked to, if it is a secondary owner
+    async fn linked_to(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<PlayerKey>> {
+        self.state.linked_owners.get(&wallet_address).await.storage()
+    }
+
+    /// Get privileged operations oldest first, starting after the entry with id `after`
+    async fn admin_audit_log(
+        &self,
+        limit: Option<i32>,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<AdminAuditEntry>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let count = self.state.admin_audit_log.count();
+        let start = after
+            .map_or(0, |after| usize::try_from(after).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        self.state.admin_audit_log.read(start..end).await.storage()
+    }
+
+    /// Get invalidated high scores oldest first, starting after the entry with id `after`
+    async fn score_invalidations(
+        &self,
+        limit: Option<i32>,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<ScoreInvalidation>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let count = self.state.score_invalidations.count();
+        let start = after
+            .map_or(0, |after| usize::try_from(after).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        self.state.score_invalidations.read(start..end).await.storage()
+    }
+
+    /// Get every catalog quest with the player's progress for `day` (UNIX days)
+    async fn active_quests(
+        &self,
+        wallet_address: PlayerKey,
+        day: u64,
+    ) -> async_graphql::Result<Vec<ActiveQuest>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+
+        let mut quests = Vec::new();
+        for quest_id in self.state.quests.indices().await.storage()? {
+            let Some(quest) = self.state.quests.get(&quest_id).await.storage()? else {
+                continue;
+            };
+            let progress = self
+                .state
+                .quest_progress
+                .get(&(key, quest_id))
+                .await
+                .storage()?
+                .filter(|progress| progress.day == day)
+                .unwrap_or_default();
+            quests.push(ActiveQuest {
+                completed: progress.progress >= quest.target,
+                progress: progress.progress,
+                claimed: progress.claimed,
+                quest,
+            });
+        }
+
+        Ok(quests)
+    }
+
+    /// Get the challenge modifier clients must apply to runs this week
+    async fn current_modifier(&self) -> async_graphql::Result<Option<CurrentModifier>> {
+        let week = challenge_week(self.now);
+        let modifier = self.state.modifier_schedule.get(&week).await.storage()?;
+        Ok(modifier.map(|modifier| CurrentModifier {
+            modifier,
+            ends_at: DateTime((week + 1) * SECONDS_PER_WEEK),
+        }))
+    }
+
+    /// Get the best scores reached under a challenge modifier
+    #[graphql(complexity = "list_complexity(top_n, 10, child_complexity)")]
+    async fn modifier_leaderboard(
+        &self,
+        modifier: ChallengeModifier,
+        top_n: Option<i32>,
+    ) -> async_graphql::Result<Vec<ModifierScoreEntry>> {
+        let limit = top_n.unwrap_or(10).max(1).min(100) as usize;
+
+        let mut entries = Vec::new();
+        for key in self.state.modifier_scores.indices().await.storage()? {
+            if key.0 != modifier {
+                continue;
+            }
+            let Some(score) = self.state.modifier_scores.get(&key).await.storage()? else {
+                continue;
+            };
+            let player = self.state.players.get(&key.1).await.storage()?.unwrap_or_default();
+            entries.push(ModifierScoreEntry {
+                wallet_address: key.1,
+                display_name: player.display_name,
+                score,
+            });
+        }
+
+        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        Ok(entries.into_iter().take(limit).collect())
+    }
+
+    /// Get the cosmetic items a player owns
+    async fn inventory(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Vec<InventoryItem>> {
+        let mut items = Vec::new();
+        let Some(owned) = self.state.inventory.try_load_entry(&wallet_address).await.storage()?
+        else {
+            return Ok(items);
+        };
+        for item in owned.indices().await.storage()? {
+            let count = owned.get(&item).await.storage()?.unwrap_or(0);
+            items.push(InventoryItem { item, count });
+        }
+
+        Ok(items)
+    }
+
+    /// Get the loot box a player bought and has not opened yet
+    async fn pending_loot_box(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<LootBoxCommit>> {
+        self.state.loot_box_commits.get(&wallet_address).await.storage()
+    }
+
+    /// Get coin gifts oldest first, starting after the gift with id `after`
+    async fn gift_log(
+        &self,
+        limit: Option<i32>,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<GiftRecord>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let count = self.state.gift_log.count();
+        let start = after
+            .map_or(0, |after| usize::try_from(after).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        self.state.gift_log.read(start..end).await.storage()
+    }
+
+    /// Get the tips a wallet has received, most recent first
+    async fn tips_received(
+        &self,
+        wallet_address: PlayerKey,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<Tip>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        match self.state.tips.try_load_entry(&key).await.storage()? {
+            Some(log) => {
+                let count = log.count();
+                let mut tips = log.read(count.saturating_sub(limit)..count).await.storage()?;
+                tips.reverse();
+                Ok(tips)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get what the chain recorded for a wallet's accepted submissions, most
+    /// recent first, to check against what the client sent
+    async fn submission_receipts(
+        &self,
+        wallet_address: PlayerKey,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<SubmissionReceipt>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let limit = limit.unwrap_or(20).max(1).min(100) as usize;
+        match self.state.submission_receipts.try_load_entry(&key).await.storage()? {
+            Some(queue) => {
+                let mut receipts = queue.elements().await.storage()?;
+                receipts.reverse();
+                receipts.truncate(limit);
+                Ok(receipts)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get the chain whose calendar events notify the player as they start,
+    /// or None if the player isn't subscribed
+    async fn event_subscription(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<ChainId>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        if !self.state.event_subscribers.contains(&key).await.storage()? {
+            return Ok(None);
+        }
+        Ok(*self.state.events_source.get())
+    }
+
+    /// Get a wallet's unacknowledged notifications, oldest first, starting
+    /// after the notification with id `after`
+    async fn notifications(
+        &self,
+        wallet_address: PlayerKey,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<Notification>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        match self.state.notifications.try_load_entry(&key).await.storage()? {
+            Some(inbox) => {
+                let mut notifications = inbox.elements().await.storage()?;
+                if let Some(after) = after {
+                    notifications.retain(|notification| notification.id > after);
+                }
+                Ok(notifications)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get queued high-score replays, oldest first, optionally only those with `status`
+    #[graphql(complexity = "list_complexity(limit, 50, child_complexity)")]
+    async fn verification_queue(
+        &self,
+        status: Option<VerificationStatus>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<VerificationItem>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let mut items = Vec::new();
+        for id in self.state.verification_queue.indices().await.storage()? {
+            if items.len() == limit {
+                break;
+            }
+            if let Some(item) = self.state.verification_queue.get(&id).await.storage()? {
+                if status.map_or(true, |status| item.status == status) {
+                    items.push(item);
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// Get the players with a head-to-head rating, highest first, among those
+    /// with at least `min_matches` rated matches
+    async fn rating_leaderboard(
+        &self,
+        limit: Option<i32>,
+        min_matches: Option<u32>,
+    ) -> async_graphql::Result<Vec<RatingStanding>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let min_matches = min_matches.unwrap_or(1);
+        let mut ratings = Vec::new();
+        for wallet in self.state.ratings.indices().await.storage()? {
+            if let Some(rating) = self.state.ratings.get(&wallet).await.storage()? {
+                if rating.matches() >= min_matches {
+                    ratings.push((wallet, rating));
+                }
+            }
+        }
+        ratings.sort_by(|a, b| b.1.rating.cmp(&a.1.rating).then_with(|| a.0.cmp(&b.0)));
+        Ok(ratings
+            .into_iter()
+            .take(limit)
+            .enumerate()
+            .map(|(index, (wallet_address, rating))| RatingStanding {
+                rank: index as u32 + 1,
+                wallet_address,
+                rating,
+            })
+            .collect())
+    }
+
+    /// Get a player's rating changes oldest first, starting after the change
+    /// with index `after`, for the profile graph
+    async fn rating_history(
+        &self,
+        wallet_address: PlayerKey,
+        limit: Option<i32>,
+        after: Option<u64>,
+    ) -> async_graphql::Result<Vec<RatingChange>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let Some(history) = self.state.rating_history.try_load_entry(&key).await.storage()? else {
+            return Ok(Vec::new());
+        };
+        let count = history.count();
+        let start = after
+            .map_or(0, |after| usize::try_from(after).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        history.read(start..end).await.storage()
+    }
+
+    /// Get community events, latest start first
+    async fn events(&self) -> async_graphql::Result<Vec<CommunityEvent>> {
+        let mut events = Vec::new();
+        for tag in self.state.events.indices().await.storage()? {
+            if let Some(event) = self.state.events.get(&tag).await.storage()? {
+                events.push(event);
+            }
+        }
+        events.sort_by(|a, b| b.starts_at.cmp(&a.starts_at).then_with(|| a.tag.cmp(&b.tag)));
+        Ok(events)
+    }
+
+    /// Get a community event by tag
+    async fn event(&self, tag: String) -> async_graphql::Result<Option<CommunityEvent>> {
+        self.state.events.get(&tag).await.storage()
+    }
+
+    /// Get the players of an event, best first, optionally only those playing
+    /// for `country` and `city`. Frozen once the event has ended.
+    async fn event_leaderboard(
+        &self,
+        tag: String,
+        country: Option<String>,
+        city: Option<String>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<EventStanding>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let region = country
+            .map(|country| validate(validation::validate_region(&country, city.as_deref())))
+            .transpose()?;
+        let standings = self.state.event_standings(&tag).await.storage()?;
+        Ok(standings
+            .into_iter()
+            .filter(|standing| {
+                region.as_ref().map_or(true, |(country, city)| {
+                    standing.country == *country && (city.is_none() || standing.city == *city)
+                })
+            })
+            .take(limit)
+            .collect())
+    }
+
+    /// Get the country and city rollups of an event: the frozen results once
+    /// it has ended, the live ones until then
+    async fn event_rollup(&self, tag: String) -> async_graphql::Result<EventRollup> {
+        if let Some(rollup) = self.state.event_results.get(&tag).await.storage()? {
+            return Ok(rollup);
+        }
+        self.state.event_rollup(&tag).await.storage()
+    }
+
+    /// Get the teams of a team relay event, including ones not yet full, in
+    /// the order they were created
+    async fn event_teams(&self, tag: String) -> async_graphql::Result<Vec<EventTeam>> {
+        let team_ids = self.state.teams_by_event.get(&tag).await.storage()?;
+        let mut teams = Vec::new();
+        for team_id in team_ids.unwrap_or_default() {
+            if let Some(team) = self.state.event_teams.get(&team_id).await.storage()? {
+                teams.push(team);
+            }
+        }
+        Ok(teams)
+    }
+
+    /// Get a team relay team by id
+    async fn team(&self, team_id: u64) -> async_graphql::Result<Option<EventTeam>> {
+        self.state.event_teams.get(&team_id).await.storage()
+    }
+
+    /// Get the teams a wallet has a pending invite into
+    async fn team_invites(&self, wallet_address: String) -> async_graphql::Result<Vec<EventTeam>> {
+        let wallet = validate(validation::validate_wallet_address(&wallet_address))?;
+        let mut teams = Vec::new();
+        for team_id in self.state.event_teams.indices().await.storage()? {
+            if let Some(team) = self.state.event_teams.get(&team_id).await.storage()? {
+                if team.invited.contains(&wallet) {
+                    teams.push(team);
+                }
+            }
+        }
+        Ok(teams)
+    }
+
+    /// Get the full teams of a team relay event ranked by team score: the
+    /// frozen results once it has ended, the live ones until then
+    async fn team_leaderboard(
+        &self,
+        tag: String,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<TeamStanding>> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let standings = match self.state.team_results.get(&tag).await.storage()? {
+            Some(standings) => standings,
+            None => self.state.team_standings(&tag).await.storage()?,
+        };
+        Ok(standings.into_iter().take(limit).collect())
+    }
+
+    /// Get appeals against score invalidations in the order they were filed,
+    /// optionally only those with `status`. Each page reads `limit` appeals
+    /// from `cursor` on; without a cursor, pending appeals start at the
+    /// oldest one still waiting.
+    async fn appeals(
+        &self,
+        status: Option<AppealStatus>,
+        limit: Option<i32>,
+        cursor: Option<u64>,
+    ) -> async_graphql::Result<AppealPage> {
+        let limit = limit.unwrap_or(50).max(1).min(100) as usize;
+        let count = self.state.appeal_log.count();
+        let start = match (cursor, status) {
+            (Some(cursor), _) => cursor,
+            (None, Some(AppealStatus::Pending)) => *self.state.first_pending_appeal.get(),
+            (None, _) => 0,
+        };
+        let start = usize::try_from(start).unwrap_or(usize::MAX).min(count);
+        let end = start.saturating_add(limit).min(count);
+
+        let mut appeals = Vec::new();
+        for id in self.state.appeal_log.read(start..end).await.storage()? {
+            if let Some(appeal) = self.state.appeals.get(&id).await.storage()? {
+                if status.map_or(true, |status| appeal.status == status) {
+                    appeals.push(appeal);
+                }
+            }
+        }
+        Ok(AppealPage {
+            appeals,
+            next_cursor: (end < count).then_some(end as u64),
+        })
+    }
+
+    /// Get a queued replay with its verdicts
+    async fn verification(&self, id: u64) -> async_graphql::Result<Option<VerificationItem>> {
+        self.state.verification_queue.get(&id).await.storage()
+    }
+
+    /// Get leaderboard changes after `since_seq` (all of them when omitted),
+    /// oldest first, so clients can sync incrementally
+    async fn leaderboard_changes(
+        &self,
+        since_seq: Option<u64>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<LeaderboardChange>> {
+        let limit = limit.unwrap_or(100).max(1).min(500) as usize;
+        let count = self.state.leaderboard_changes.count();
+        let start = since_seq
+            .map_or(0, |seq| usize::try_from(seq).unwrap_or(usize::MAX).saturating_add(1))
+            .min(count);
+        let end = start.saturating_add(limit).min(count);
+        self.state.leaderboard_changes.read(start..end).await.storage()
+    }
+
+    /// Get the global top `top_n`, merged from the top lists the shard hubs
+    /// reported (coordinator chain only; empty when the leaderboard isn't sharded)
+    async fn global_leaderboard(
+        &self,
+        top_n: Option<i32>,
+    ) -> async_graphql::Result<Vec<ShardTopEntry>> {
+        let top_n = top_n.unwrap_or(10).max(1).min(SHARD_TOP_N as i32) as usize;
+        let config = self.state.config.get();
+
+        let mut entries = Vec::new();
+        for shard in &config.hub_shards {
+            if let Some(top) = self.state.shard_tops.get(shard).await.storage()? {
+                if top.shard_epoch == config.shard_epoch {
+                    entries.extend(top.entries);
+                }
+            }
+        }
+
+        // A wallet can show up on two shards while it is handed off; keep its best score
+        entries.sort_by(|a, b| {
+            a.wallet_address
+                .cmp(&b.wallet_address)
+                .then_with(|| b.high_score.cmp(&a.high_score))
+        });
+        entries.dedup_by(|later, earlier| later.wallet_address == earlier.wallet_address);
+        entries.sort_by(|a, b| {
+            b.high_score
+                .cmp(&a.high_score)
+                .then_with(|| a.wallet_address.cmp(&b.wallet_address))
+        });
+        entries.truncate(top_n);
+        Ok(entries)
+    }
+
+    /// Get the roster position the running `RebalanceShard` pass resumes from,
+    /// or None when no pass is running
+    async fn rebalance_cursor(&self) -> Option<u64> {
+        *self.state.rebalance_cursor.get()
+    }
+
+    /// Get the hub chain holding a wallet's scores under the current shard assignment
+    async fn hub_for(&self, wallet_address: PlayerKey) -> Option<ChainId> {
+        self.state.config.get().hub_for(&wallet_address)
+    }
+
+    /// Get the commitment to the top of the leaderboard
+    async fn leaderboard_commitment(&self) -> LeaderboardCommitment {
+        self.state.leaderboard_commitment.get().clone()
+    }
+
+    /// Get the proof that a wallet's entry is included in the leaderboard
+    /// commitment, or None if it isn't among the committed entries
+    async fn leaderboard_proof(
+        &self,
+        wallet_address: PlayerKey,
+    ) -> async_graphql::Result<Option<LeaderboardProof>> {
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+
+        let top = self.state.leaderboard_top.get();
+        let entries = top.iter().take(COMMITMENT_SIZE).copied().collect::<Vec<_>>();
+        let Some(index) = entries.iter().position(|(wallet, _)| *wallet == key) else {
+            return Ok(None);
+        };
+
+        let leaves = commitment::leaves(&entries);
+        Ok(Some(LeaderboardProof {
+            wallet_address: key,
+            rank: index as u32 + 1,
+            high_score: entries[index].1,
+            leaf: commitment::to_hex(&leaves[index]),
+            path: commitment::merkle_path(&leaves, index),
+            root: commitment::to_hex(&commitment::merkle_root(&leaves)),
+        }))
+    }
+
+    /// Get the scripts display names may use, with regional overrides
+    async fn name_script_policy(&self) -> NameScriptPolicy {
+        self.state.name_script_policy.get().clone()
+    }
+
+    /// Get the display name blocklist
+    async fn name_blocklist(&self) -> NameBlocklist {
+        self.state.name_blocklist.get().clone()
+    }
+
+    /// Get the display names flagged by blocklist updates and waiting for a
+    /// moderator. Flags are public chain state; only the hub holds them, so
+    /// read-only mirrors refuse the query.
+    async fn name_reviews(&self) -> async_graphql::Result<Vec<NameReview>> {
+        if self.state.config.get().mirror_of.is_some() {
+            return Err(ServiceError::NotOnMirror.extend());
+        }
+        let mut reviews = Vec::new();
+        for wallet in self.state.name_reviews.indices().await.storage()? {
+            if let Some(review) = self.state.name_reviews.get(&wallet).await.storage()? {
+                reviews.push(review);
+            }
+        }
+        reviews.sort_by_key(|review| review.flagged_at);
+        Ok(reviews)
+    }
+
+    /// Get a player's previous display names, most recent first. The history
+    /// is kept for moderators but is public: anyone syncing the chain can read
+    /// it. Mirrors don't serve it, as they only follow the leaderboard.
+    async fn name_history(&self, wallet_address: PlayerKey) -> async_graphql::Result<Vec<String>> {
+        if self.state.config.get().mirror_of.is_some() {
+            return Err(ServiceError::NotOnMirror.extend());
+        }
+        let linked = self.state.linked_owners.get(&wallet_address).await.storage()?;
+        let key = linked.unwrap_or(wallet_address);
+        let player = self.state.players.get(&key).await.storage()?;
+        Ok(player.map(|player| player.name_history).unwrap_or_default())
+    }
+
+    /// Get execution metrics of every operation type executed so far, by
+    /// name. The metrics are public; mirrors execute no operations and refuse
+    /// the query rather than report none.
+    async fn op_metrics(&self) -> async_graphql::Result<Vec<OpMetricsEntry>> {
+        if self.state.config.get().mirror_of.is_some() {
+            return Err(ServiceError::NotOnMirror.extend());
+        }
+        let mut entries = Vec::new();
+        for operation in self.state.op_metrics.indices().await.storage()? {
+            if let Some(metrics) = self.state.op_metrics.get(&operation).await.storage()? {
+                entries.push(OpMetricsEntry { operation, metrics });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Cross-check the derived indexes and counters against up to `sample`
+    /// players (100 by default), in roster order from position `cursor`.
+    /// Only the sampled players are read, however many there are.
+    #[graphql(complexity = "list_complexity(sample, 100, 1)")]
+    async fn state_consistency(
+        &self,
+        sample: Option<i32>,
+        cursor: Option<u64>,
+    ) -> async_graphql::Result<ConsistencyReport> {
+        let config = self.state.config.get();
+        if config.mirror_of.is_some() {
+            return Err(ServiceError::NotOnMirror.extend());
+        }
+        let sample = sample.unwrap_or(100).max(1).min(MAX_CONSISTENCY_SAMPLE) as usize;
+        let mut discrepancies = Vec::new();
+        let mut report = |kind, wallet_address, detail: String| {
+            discrepancies.push(Discrepancy {
+                kind,
+                wallet_address,
+                detail,
+            });
+        };
+
+        // The index is bounded by the leaderboard cap, so it is checked in full
+        let mut indexed = Vec::new();
+        for wallet in self.state.leaderboard_index.indices().await.storage()? {
+            if let Some(score) = self.state.leaderboard_index.get(&wallet).await.storage()? {
+                indexed.push((wallet, score));
+            }
+        }
+        if indexed.len() > config.leaderboard_cap as usize {
+            let detail = format!("{} entries, cap {}", indexed.len(), config.leaderboard_cap);
+            report(DiscrepancyKind::LeaderboardOverCap, None, detail);
+        }
+        for (wallet, score) in &indexed {
+            if !self.state.players.contains_key(wallet).await.storage()? {
+                let detail = format!("indexed with {score}");
+                report(DiscrepancyKind::IndexedWithoutPlayer, Some(*wallet), detail);
+            }
+        }
+        let committed = commitment::committed_entries(indexed.clone());
+        let root = commitment::to_hex(&commitment::merkle_root(&commitment::leaves(&committed)));
+        let committed_root = &self.state.leaderboard_commitment.get().root;
+        if !committed.is_empty() && *committed_root != root {
+            let detail = format!("expected root {root}, found {committed_root}");
+            report(DiscrepancyKind::CommitmentStale, None, detail);
+        }
+        let index_full = indexed.len() >= config.leaderboard_cap as usize;
+        let lowest_indexed = indexed.iter().map(|(_, score)| *score).min().unwrap_or(0);
+
+        // Sample by roster position, which every player holds once
+        let roster_len = self.state.player_roster.count();
+        let start = (cursor.unwrap_or(0) as usize).min(roster_len);
+        let end = start.saturating_add(sample).min(roster_len);
+        let batch = self.state.player_roster.read(start..end).await.storage()?;
+
+        let record = self.state.records.get().highest_score.as_ref().map_or(0, |r| r.value);
+        let rules = LeaderboardRules::from_config(config);
+        let mut sampled_deaths = DeathTally::default();
+        for wallet in &batch {
+            // Archived players are kept out of the derived state on purpose
+            let Some(player) = self.state.players.get(wallet).await.storage()? else {
+                continue;
+            };
+            sampled_deaths.merge(&player.death_tally);
+            // Players the leaderboard rules keep off are neither indexed nor hold records
+            let kept_off = rules.check_indexed(&player).is_err();
+
+            let indexed_score = indexed
+                .iter()
+                .find(|(indexed, _)| indexed == wallet)
+                .map(|(_, score)| *score);
+            match indexed_score {
+                Some(score) if score != player.high_score => {
+                    let detail = format!("expected {}, indexed {score}", player.high_score);
+                    report(DiscrepancyKind::LeaderboardScoreMismatch, Some(*wallet), detail);
+                }
+                None if !kept_off
+                    && player.high_score > 0
+                    && (!index_full || player.high_score > lowest_indexed) =>
+                {
+                    let detail = format!("high score {} is not indexed", player.high_score);
+                    report(DiscrepancyKind::MissingFromLeaderboard, Some(*wallet), detail);
+                }
+                _ => {}
+            }
+
+            let holder = self.state.replay_holders.get(wallet).await.storage()?;
+            let expected_holder = player.replay_data.as_ref().map(|_| player.high_score);
+            if holder != expected_holder {
+                let detail = format!("expected {expected_holder:?}, found {holder:?}");
+                report(DiscrepancyKind::ReplayHolderMismatch, Some(*wallet), detail);
+            }
+
+            if !kept_off && player.high_score as u64 > record {
+                let detail = format!("record {record}, high score {}", player.high_score);
+                report(DiscrepancyKind::RecordBehind, Some(*wallet), detail);
+            }
+        }
+
+        let deaths = self.state.death_tally.get();
+        let total = |tally: &DeathTally| tally.car + tally.train + tally.water + tally.timeout;
+        if total(deaths) < total(&sampled_deaths) {
+            let detail = format!(
+                "chain counts {} runs, sampled players {}",
+                total(deaths),
+                total(&sampled_deaths)
+            );
+            report(DiscrepancyKind::DeathTallyBehind, None, detail);
+        }
+
+        let next_cursor = (end < roster_len).then_some(end as u64);
+        Ok(ConsistencyReport {
+            players_checked: batch.len() as u32,
+            next_cursor,
+            discrepancies,
+        })
+    }
+
+    /// Get the progress of the running index rebuild, or None if none is running
+    async fn index_rebuild(&self) -> Option<IndexRebuild> {
+        self.state.index_rebuild.get().clone()
+    }
+
+    /// Get the hub chain this instance mirrors read-only, or None if it is authoritative
+    async fn mirror_of(&self) -> Option<ChainId> {
+        self.state.config.get().mirror_of
+    }
+}
+
+impl QueryRoot {
+    /// Whether the leaderboard rules that only change with player data let
+    /// `wallet_address` rank and hold records
+    async fn passes_index_rules(&self, wallet_address: &str) -> async_graphql::Result<bool> {
+        let Ok(wallet) = wallet_address.parse::<PlayerKey>() else {
+            return Ok(true);
+        };
+        let rules = LeaderboardRules::from_config(self.state.config.get());
+        let player = self.state.player_or_archived(&wallet).await.storage()?;
+        Ok(player.map_or(true, |player| rules.check_indexed(&player).is_ok()))
+    }
+
+    /// Ranked players with the `top_n` best high scores, best first. `top_n`
+    /// is capped by the leaderboard cap. Player data is only loaded up front
+    /// when ranking requirements are set.
+    async fn top_players(&self, top_n: i32) -> async_graphql::Result<Vec<LeaderboardEntry>> {
+        let config = self.state.config.get();
+        let limit = top_n.max(1).min(config.leaderboard_cap as i32) as usize;
+        let rules = LeaderboardRules::from_config(config);
+
+        // Only players within the leaderboard cap are indexed
+        let mut entries = Vec::new();
+        for key in self.state.leaderboard_index.indices().await.storage()? {
+            let Some(high_score) = self.state.leaderboard_index.get(&key).await.storage()? else {
+                continue;
+            };
+            if rules.ranks_every_indexed() {
+                entries.push(LeaderboardEntry::new(self, key, high_score));
+            } else if let Some(player) = self.state.players.get(&key).await.storage()? {
+                if rules.check(&player, self.now).is_ok() {
+                    // Rank by the indexed score, which a leaderboard freeze holds back
+                    let mut entry = LeaderboardEntry::loaded(self, key, player);
+                    entry.high_score = high_score;
+                    entries.push(entry);
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| b.high_score.cmp(&a.high_score));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+}
+
+/// GraphQL subscription root for spectators
+struct SubscriptionRoot {
+    /// Storage of the chain, read again while a stream waits for new data
+    context: ViewStorageContext,
+}
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Stream the snapshots of a race as they are published, ending with the
+    /// final one once the race settles. The chain state is read again each
+    /// time the stream is polled, so a snapshot shows up as soon as the block
+    /// delivering it is executed.
+    async fn race_snapshots(
+        &self,
+        race_id: u64,
+    ) -> async_graphql::Result<impl Stream<Item = async_graphql::Result<RaceSnapshot>>> {
+        let context = self.context.clone();
+        // The state is the last snapshot sent, or None once the final one was
+        let snapshots = stream::unfold(Some(None), move |last: Option<Option<RaceSnapshot>>| {
+            let context = context.clone();
+            async move {
+                let last = last?;
+                loop {
+                    let snapshot = match latest_race_snapshot(&context, race_id).await {
+                        Ok(snapshot) => snapshot,
+                        Err(error) => return Some((Err(error), None)),
+                    };
+                    let fresh = snapshot.filter(|snapshot| last.as_ref() != Some(snapshot));
+                    if let Some(snapshot) = fresh {
+                        let next = (!snapshot.finished).then(|| Some(snapshot.clone()));
+                        return Some((Ok(snapshot), next));
+                    }
+                    next_poll().await;
+                }
+            }
+        });
+        Ok(snapshots)
+    }
+}
+
+/// Latest snapshot of `race_id` in the chain's current state
+async fn latest_race_snapshot(
+    context: &ViewStorageContext,
+    race_id: u64,
+) -> async_graphql::Result<Option<RaceSnapshot>> {
+    let state = CrossyChainState::load(context.clone()).await.storage()?;
+    state.race_snapshots.get(&race_id).await.storage()
+}
+
+/// Let the node run other work before the chain state is read again
+async fn next_poll() {
+    let mut polled = false;
+    future::poll_fn(|cx| {
+        if polled {
+            return Poll::Ready(());
+        }
+        polled = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+/// GraphQL mutation root for triggering contract operations
+struct MutationRoot {
+    state: Arc<CrossyChainState<ServiceRuntime<CrossyChainService>>>,
+}
+
+#[Object]
+impl MutationRoot {
+    /// Save a player's score on-chain
+    /// This triggers the SaveScore operation in the contract
+    async fn save_score(
+        &self,
+        score: u32,
+        timestamp: DateTime,
+        replay_data: Option<String>,
+        compression: Option<ReplayCompression>,
+        client_version: String,
+        submission_id: String,
+        run_stats: Option<RunStats>,
+        proof: Option<String>,
+    ) -> async_graphql::Result<bool> {
+        // Pre-validate with the same rules the contract applies
+        validate(validation::validate_score(score))?;
+        if let Some(replay) = &replay_data {
+            let max_replay_size = self.state.config.get().max_replay_size;
+            let replay = compression::decompress_replay(
+                replay,
+                compression.unwrap_or_default(),
+                max_replay_size,
+            )
+            .map_err(|_| ServiceError::InvalidReplayCompression.extend())?;
+            validate(validation::validate_replay_size(&replay, max_replay_size))?;
+        }
+        validate(validation::validate_client_version(&client_version))?;
+        validate(validation::validate_submission_id(&submission_id))?;
+
+        // Note: In Linera, GraphQL mutations trigger contract operations
+        // The actual operation is executed by the contract, not the service
+        // This method just defines the GraphQL schema
+        // The client calls backend.query("mutation { saveScore(...) }")
+        // which creates a block with the SaveScore operation
+        // The replay_data is a JSON string of the game recording, or its
+        // base64 when `compression` is set
+        Ok(true)
+    }
+
+    /// Register a player with optional display name
+    /// This triggers the RegisterPlayer operation in the contract
+    async fn register_player(&self, display_name: Option<String>) -> async_graphql::Result<bool> {
+        // Validate display name if provided
+        if let Some(ref name) = display_name {
+            validate(validation::validate_display_name(name))?;
+            let terms = &self.state.name_blocklist.get().terms;
+            validate(validation::check_name_blocklist(name, terms))?;
+            // The signer's region isn't known here, so only mixing is checked;
+            // the contract applies the region's allowed scripts
+            validate(validation::validate_name_scripts(name, &[]))?;
+        }
+        
+        // Note: In Linera, GraphQL mutations trigger contract operations
+        // The actual operation is executed by the contract, not the service
+        // This method just defines the GraphQL schema
+        // The client calls backend.query("mutation { registerPlayer(...) }")
         // which creates a block with the RegisterPlayer operation
+        Ok(true)
+    }
+
+    /// Claim a granted reward
+    /// This triggers the ClaimReward operation in the contract
+    async fn claim_reward(&self, reward_id: u64) -> bool {
+        // The contract checks ownership and expiry when the operation executes
+        true
+    }
+
+    /// Start a ghost race against another player's high-score replay
+    /// This triggers the StartGhostRace operation in the contract
+    async fn start_ghost_race(&self, opponent: String) -> bool {
+        true
+    }
+
+    /// Submit the score reached in the active ghost race, with the run's
+    /// replay JSON for the result to be rated
+    /// This triggers the SubmitGhostRaceResult operation in the contract
+    async fn submit_ghost_race_result(&self, score: u32, replay_data: Option<String>) -> bool {
+        true
+    }
+
+    /// Join the matchmaking queue for a live race
+    /// This triggers the EnqueueRace operation in the contract
+    async fn enqueue_race(&self, public_key: Option<String>) -> bool {
+        true
+    }
+
+    /// Leave the matchmaking queue
+    /// This triggers the LeaveRaceQueue operation in the contract
+    async fn leave_race_queue(&self) -> bool {
+        true
+    }
+
+    /// Submit the score reached in a matched race
+    /// This triggers the SubmitRaceRun operation in the contract
+    async fn submit_race_run(&self, race_id: u64, score: u32) -> bool {
+        true
+    }
+
+    /// Follow a race chain's spectator snapshots
+    /// This triggers the SpectateRace operation in the contract
+    async fn spectate_race(&self, race_chain: String) -> bool {
+        true
+    }
+
+    /// Stop following a race chain's spectator snapshots
+    /// This triggers the StopSpectating operation in the contract
+    async fn stop_spectating(&self, race_chain: String) -> bool {
+        true
+    }
+
+    /// Publish the current position on a race chain
+    /// This triggers the UpdateRacePosition operation in the contract
+    async fn update_race_position(&self, race_id: u64, position: GhostPoint) -> bool {
+        true
+    }
+
+    /// Like a player's current high-score replay
+    /// This triggers the LikeReplay operation in the contract
+    async fn like_replay(&self, wallet_address: PlayerKey) -> bool {
+        true
+    }
+
+    /// Count a view of a player's current high-score replay
+    /// This triggers the RecordReplayView operation in the contract
+    async fn record_replay_view(&self, wallet_address: PlayerKey) -> bool {
+        true
+    }
+
+    /// Move the signer's account to another chain running this application
+    /// This triggers the MigrateAccount operation in the contract
+    async fn migrate_account(&self, target_chain: String) -> bool {
+        true
+    }
+
+    /// Offer to link another owner to the signer's profile
+    /// This triggers the RequestLink operation in the contract
+    async fn request_link(&self, owner: PlayerKey) -> bool {
+        true
+    }
+
+    /// Accept a link request from a profile owner
+    /// This triggers the AcceptLink operation in the contract
+    async fn accept_link(&self, primary: PlayerKey) -> bool {
+        true
+    }
+
+    /// Let an ephemeral key submit scores for the signer until `expires_at`
+    /// This triggers the AuthorizeSessionKey operation in the contract
+    async fn authorize_session_key(&self, session_key: PlayerKey, expires_at: DateTime) -> bool {
+        true
+    }
+
+    /// Withdraw a session key before it expires
+    /// This triggers the RevokeSessionKey operation in the contract
+    async fn revoke_session_key(&self, session_key: PlayerKey) -> bool {
+        true
+    }
+
+    /// Collect the reward of a quest completed today
+    /// This triggers the ClaimQuestReward operation in the contract
+    async fn claim_quest_reward(&self, quest_id: u32) -> bool {
+        true
+    }
+
+    /// Buy a loot box, committing to the hex SHA-256 of a secret
+    /// This triggers the BuyLootBox operation in the contract
+    async fn buy_loot_box(&self, commitment: String) -> bool {
+        true
+    }
+
+    /// Open the bought loot box by revealing its secret
+    /// This triggers the OpenLootBox operation in the contract
+    async fn open_loot_box(&self, secret: String) -> bool {
+        true
+    }
+
+    /// Give some of the signer's coins to another player
+    /// This triggers the GiftCoins operation in the contract
+    async fn gift_coins(&self, to: PlayerKey, amount: u64) -> bool {
+        true
+    }
+
+    /// Send native tokens to another player
+    /// This triggers the TipPlayer operation in the contract
+    async fn tip_player(&self, to: PlayerKey, amount: Amount) -> bool {
+        true
+    }
+
+    /// Pay the one-time leaderboard entry fee
+    /// This triggers the PayLeaderboardEntryFee operation in the contract
+    async fn pay_leaderboard_entry_fee(&self) -> bool {
+        true
+    }
+
+    /// Dismiss notifications up to and including `up_to`
+    /// This triggers the AckNotifications operation in the contract
+    async fn ack_notifications(&self, up_to: u64) -> bool {
+        true
+    }
+
+    /// Take back coins an application held for the signer past the hold's expiry
+    /// This triggers the ReclaimCoinHold operation in the contract
+    async fn reclaim_coin_hold(&self, application_id: ApplicationId) -> bool {
         true
     }
 }
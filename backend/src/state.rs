@@ -1,5 +1,135 @@
-use linera_sdk::views::{MapView, RootView, ViewStorageContext};
-use serde::{Deserialize, Serialize};
+use async_graphql::{Enum, InputObject, SimpleObject};
+use crate::commitment::LeaderboardCommitment;
+use crate::compression::{self, CompressionError, ReplayCompression};
+use crate::oracle::OracleAttestation;
+use crate::partner::{CoinHold, PartnerCapability};
+use crate::rating::{PlayerRating, RatingChange};
+use crate::replay::{DeathCause, GhostPoint, ReplayMetadata, ReplaySummary};
+use crate::validation::ValidationError;
+use linera_sdk::base::{Amount, ApplicationId, ChainId, Owner, PublicKey};
+use linera_sdk::views::{
+    ClonableView, CollectionView, Context, LogView, MapView, QueueView, RegisterView, RootView,
+    SetView, ViewError, ViewStorageContext,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Storage key for per-player data: the wallet's `Owner`.
+/// Keys are encoded as the owner's canonical string, the same bytes the
+/// `Owner::to_string()` keys were written with, so entries stored before the
+/// switch to typed keys keep resolving without a migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlayerKey(pub Owner);
+
+impl From<Owner> for PlayerKey {
+    fn from(owner: Owner) -> Self {
+        PlayerKey(owner)
+    }
+}
+
+impl fmt::Display for PlayerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for PlayerKey {
+    type Err = ValidationError;
+
+    /// Accepts wallet strings the way clients tend to write them: with
+    /// surrounding whitespace, a `0x` prefix or upper-case hex
+    fn from_str(wallet: &str) -> Result<Self, Self::Err> {
+        let wallet = wallet.trim();
+        let hex = wallet.strip_prefix("0x").unwrap_or(wallet).to_ascii_lowercase();
+        Owner::from_str(&hex)
+            .map(PlayerKey)
+            .map_err(|_| ValidationError::InvalidWalletAddress)
+    }
+}
+
+impl Serialize for PlayerKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayerKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let wallet = String::deserialize(deserializer)?;
+        wallet.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Statistics of a finished run, reported with its score
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct RunStats {
+    /// How long the run lasted (milliseconds)
+    pub duration_ms: u64,
+    /// What ended the run; must match the replay when one is submitted
+    pub death_cause: Option<DeathCause>,
+    /// Coins collected during the run
+    #[graphql(default)]
+    pub coins: u64,
+}
+
+/// Number of runs that ended by each cause of death
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct DeathTally {
+    pub car: u64,
+    pub train: u64,
+    pub water: u64,
+    pub timeout: u64,
+}
+
+impl DeathTally {
+    /// Count one more run ended by `cause`
+    pub fn add(&mut self, cause: DeathCause) {
+        match cause {
+            DeathCause::Car => self.car += 1,
+            DeathCause::Train => self.train += 1,
+            DeathCause::Water => self.water += 1,
+            DeathCause::Timeout => self.timeout += 1,
+        }
+    }
+
+    /// Add the runs counted in `other`
+    pub fn merge(&mut self, other: &DeathTally) {
+        self.car += other.car;
+        self.train += other.train;
+        self.water += other.water;
+        self.timeout += other.timeout;
+    }
+
+    /// The cause that ended the most runs, if any run was counted
+    pub fn deadliest(&self) -> Option<DeathCause> {
+        [
+            (DeathCause::Car, self.car),
+            (DeathCause::Train, self.train),
+            (DeathCause::Water, self.water),
+            (DeathCause::Timeout, self.timeout),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count)
+        .map(|(cause, _)| cause)
+    }
+}
+
+/// Number of best scores kept per player
+pub const MAX_PERSONAL_BESTS: usize = 10;
+
+/// One of a player's best scores
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PersonalBest {
+    pub score: u32,
+    /// When the game was played (UNIX timestamp, as submitted)
+    pub played_at: u64,
+    /// Challenge modifier active when the run was submitted
+    pub modifier: Option<ChallengeModifier>,
+}
 
 /// Player data stored on-chain
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,8 +145,87 @@ pub struct PlayerData {
     /// Optional replay data stored directly (JSON string of recording)
     /// This is a temporary solution until Linera blob storage is fully integrated
     pub replay_data: Option<String>,
+    /// How `replay_data` is compressed
+    pub replay_compression: ReplayCompression,
     /// Optional display name (if not set, shows wallet address)
     pub display_name: Option<String>,
+    /// Envelope metadata of the stored high-score replay
+    pub replay_meta: Option<ReplayMetadata>,
+    /// Format version the stored replay was recorded in (0 for replays from
+    /// before formats were versioned); `Recording::parse` converts older ones
+    pub replay_format_version: u32,
+    /// Summary of the stored high-score replay, kept after the replay is pruned
+    pub replay_summary: Option<ReplaySummary>,
+    /// Block that submitted the stored replay
+    pub replay_origin: Option<ReplayOrigin>,
+    /// Most recent SaveScore submission ids, used to ignore retried transactions
+    pub recent_submission_ids: Vec<String>,
+    /// No longer written: forwarded scores are sequenced per origin chain in
+    /// `accepted_sequences`
+    pub sequence: u64,
+    /// Start of the current rate limit window (UNIX timestamp)
+    pub submission_window_start: u64,
+    /// Scores submitted since `submission_window_start`
+    pub submissions_in_window: u32,
+    /// Day (UNIX days) of the last game
+    pub play_day: u64,
+    /// Games played on `play_day`
+    pub games_on_play_day: u32,
+    /// Consecutive days, up to `play_day`, with at least one game
+    pub day_streak: u32,
+    /// Best scores, highest first, at most `MAX_PERSONAL_BESTS`
+    pub personal_bests: Vec<PersonalBest>,
+    /// Sum of every submitted score
+    pub total_score: u64,
+    /// Longest `day_streak` ever reached
+    pub best_streak: u32,
+    /// Time spent in reported runs (milliseconds)
+    pub total_playtime_ms: u64,
+    /// How the player's reported runs ended
+    pub death_tally: DeathTally,
+    /// Coin balance
+    pub coins: u64,
+    /// When the account was created, at the wallet's first registration,
+    /// score or entry fee (UNIX timestamp, 0 if before this was tracked).
+    /// Set only on creation, so older accounts never start looking new.
+    pub first_seen_at: u64,
+    /// Day (UNIX days) `gifted_on_gift_day` counts for
+    pub gift_day: u64,
+    /// Coins gifted on `gift_day`
+    pub gifted_on_gift_day: u64,
+    /// Whether the player paid the leaderboard entry fee
+    pub entry_fee_paid: bool,
+    /// The high score awaiting confirmation, while `provisional_window` is set
+    pub provisional: Option<ProvisionalHighScore>,
+    /// Verified high scores, lowest first, at most `MAX_VERIFIED_BESTS`;
+    /// `InvalidateScore` falls back on them, with their replays kept in
+    /// `verified_best_replays`
+    pub verified_bests: Vec<VerifiedBest>,
+    /// Scores of the player's placement runs, in the order played
+    pub placement_scores: Vec<u32>,
+    /// Rating set from the placement runs once `PLACEMENT_RUNS` are played
+    pub calibration_rating: Option<u32>,
+    /// Level bracket assigned with the calibration rating
+    pub level_bracket: Option<LevelBracket>,
+    /// Experience points granted by partner applications
+    pub xp: u64,
+    /// Last time the display name changed (UNIX timestamp); None until the
+    /// first change after the name was first set
+    pub renamed_at: Option<u64>,
+    /// Previous display names, most recent first, at most `MAX_NAME_HISTORY`
+    pub name_history: Vec<String>,
+    /// Whether an admin verified the player as a known streamer or partner
+    pub verified: bool,
+    /// Blocklist version the display name was last checked against
+    pub name_checked_version: u32,
+    /// Country the player declared (ISO 3166-1 alpha-2), for regional rules
+    pub country: Option<String>,
+    /// Games per mode, in mode order; `games_played` counts them all
+    pub mode_stats: Vec<ModeStats>,
+    /// Whether a moderator banned the player from the leaderboard
+    pub banned: bool,
+    /// Whether the player chose to stay off the leaderboard
+    pub hidden: bool,
 }
 
 impl Default for PlayerData {
@@ -27,14 +236,2322 @@ impl Default for PlayerData {
             last_played_at: None,
             replay_blob_id: None,
             replay_data: None,
+            replay_compression: ReplayCompression::None,
             display_name: None,
+            replay_meta: None,
+            replay_format_version: 0,
+            replay_summary: None,
+            replay_origin: None,
+            recent_submission_ids: Vec::new(),
+            sequence: 0,
+            submission_window_start: 0,
+            submissions_in_window: 0,
+            play_day: 0,
+            games_on_play_day: 0,
+            day_streak: 0,
+            personal_bests: Vec::new(),
+            total_score: 0,
+            best_streak: 0,
+            total_playtime_ms: 0,
+            death_tally: DeathTally::default(),
+            coins: 0,
+            first_seen_at: 0,
+            gift_day: 0,
+            gifted_on_gift_day: 0,
+            entry_fee_paid: false,
+            provisional: None,
+            verified_bests: Vec::new(),
+            placement_scores: Vec::new(),
+            calibration_rating: None,
+            level_bracket: None,
+            xp: 0,
+            renamed_at: None,
+            name_history: Vec::new(),
+            verified: false,
+            name_checked_version: 0,
+            country: None,
+            mode_stats: Vec::new(),
+            banned: false,
+            hidden: false,
+        }
+    }
+}
+
+/// The fields of a player a read-only mirror serves on its leaderboard,
+/// published without the replay or the submission bookkeeping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirroredPlayer {
+    pub high_score: u32,
+    pub games_played: u32,
+    pub last_played_at: Option<u64>,
+    pub display_name: Option<String>,
+    pub first_seen_at: u64,
+    pub verified: bool,
+    pub country: Option<String>,
+}
+
+impl From<&PlayerData> for MirroredPlayer {
+    fn from(player: &PlayerData) -> Self {
+        Self {
+            high_score: player.high_score,
+            games_played: player.games_played,
+            last_played_at: player.last_played_at,
+            display_name: player.display_name.clone(),
+            first_seen_at: player.first_seen_at,
+            verified: player.verified,
+            country: player.country.clone(),
+        }
+    }
+}
+
+impl From<MirroredPlayer> for PlayerData {
+    fn from(player: MirroredPlayer) -> Self {
+        Self {
+            high_score: player.high_score,
+            games_played: player.games_played,
+            last_played_at: player.last_played_at,
+            display_name: player.display_name,
+            first_seen_at: player.first_seen_at,
+            verified: player.verified,
+            country: player.country,
+            ..PlayerData::default()
+        }
+    }
+}
+
+/// Most verified high scores kept per player to fall back on
+pub const MAX_VERIFIED_BESTS: usize = 10;
+
+/// Shortest time between two display name changes (seconds)
+pub const RENAME_COOLDOWN: u64 = 7 * 24 * 60 * 60;
+
+/// Previous display names kept per player for moderators
+pub const MAX_NAME_HISTORY: usize = 3;
+
+/// Writing system of a display name's letters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum)]
+pub enum NameScript {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    /// Chinese characters, also used in Japanese and Korean names
+    Han,
+    /// Japanese hiragana and katakana
+    Kana,
+}
+
+impl NameScript {
+    /// Whether names may mix this script with `other`: the scripts of
+    /// Chinese, Japanese and Korean writing are used together
+    pub fn mixes_with(self, other: NameScript) -> bool {
+        use NameScript::{Han, Hangul, Kana};
+        self == other || matches!((self, other), (Han, Kana | Hangul) | (Kana | Hangul, Han))
+    }
+}
+
+/// Scripts a display name may use in one region
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RegionScripts {
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    pub scripts: Vec<NameScript>,
+}
+
+/// Scripts display names may use. An empty list allows every script; names
+/// can't mix scripts either way.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct NameScriptPolicy {
+    pub scripts: Vec<NameScript>,
+    /// Scripts allowed instead of `scripts` for players from a country
+    pub region_overrides: Vec<RegionScripts>,
+}
+
+impl NameScriptPolicy {
+    /// Scripts allowed for a player from `country`, if known
+    pub fn allowed_for(&self, country: Option<&str>) -> &[NameScript] {
+        country
+            .and_then(|country| {
+                self.region_overrides.iter().find(|region| region.country == country)
+            })
+            .map_or(&self.scripts, |region| &region.scripts)
+    }
+}
+
+/// Most terms the display name blocklist can hold
+pub const MAX_BLOCKLIST_TERMS: usize = 1000;
+
+/// Terms display names can't contain, managed by moderators. Every update
+/// bumps the version, and names checked against an older version are
+/// checked again the next time their player is active.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct NameBlocklist {
+    pub version: u32,
+    /// Blocked terms as name skeletons, sorted
+    pub terms: Vec<String>,
+}
+
+/// A display name that matched the blocklist after it was set, waiting for a moderator
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct NameReview {
+    pub wallet_address: PlayerKey,
+    pub display_name: String,
+    /// Blocklist version the name was flagged by
+    pub blocklist_version: u32,
+    /// When the name was flagged (UNIX timestamp)
+    pub flagged_at: u64,
+}
+
+/// Runs a new player plays before their calibration rating is set
+pub const PLACEMENT_RUNS: u32 = 5;
+
+/// Width of a matchmaking bucket in rating points
+pub const RACE_BRACKET_WIDTH: u32 = 50;
+
+/// Level bracket a new player starts in, from their calibration rating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum LevelBracket {
+    /// Rating below 50
+    Beginner,
+    /// Rating from 50 to 149
+    Intermediate,
+    /// Rating from 150 to 299
+    Advanced,
+    /// Rating of 300 and above
+    Expert,
+}
+
+impl LevelBracket {
+    pub fn for_rating(rating: u32) -> Self {
+        match rating {
+            0..=49 => LevelBracket::Beginner,
+            50..=149 => LevelBracket::Intermediate,
+            150..=299 => LevelBracket::Advanced,
+            _ => LevelBracket::Expert,
+        }
+    }
+}
+
+/// Calibration rating of a player's placement runs: the mean of all but the
+/// worst run, so one unlucky early death doesn't sink a new player
+pub fn calibration_rating(placement_scores: &[u32]) -> u32 {
+    let mut scores = placement_scores.to_vec();
+    scores.sort_unstable();
+    let counted = scores.get(1..).filter(|rest| !rest.is_empty()).unwrap_or(&scores);
+    if counted.is_empty() {
+        return 0;
+    }
+    let total: u64 = counted.iter().map(|&score| u64::from(score)).sum();
+    (total / counted.len() as u64) as u32
+}
+
+/// A high score verifiers or a proof confirmed
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VerifiedBest {
+    pub score: u32,
+    /// Verification queue item that confirmed it
+    pub verification_id: u64,
+    /// When it was confirmed (UNIX timestamp)
+    pub verified_at: u64,
+}
+
+/// A high score removed by a verifier or the admin with `InvalidateScore`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ScoreInvalidation {
+    /// Position in the invalidation log, used as the pagination cursor
+    pub id: u64,
+    pub wallet_address: PlayerKey,
+    /// The invalidated high score
+    pub score: u32,
+    /// The verified best the player was reverted to (0 if none)
+    pub restored_score: u32,
+    pub reason: String,
+    /// Wallet that signed the invalidation
+    pub invalidated_by: String,
+    /// When the score was invalidated (UNIX timestamp)
+    pub invalidated_at: u64,
+}
+
+/// Where a player's appeal against an invalidation stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum AppealStatus {
+    /// Waiting for the admin
+    Pending,
+    /// The invalidation stands
+    Upheld,
+    /// The invalidated score was given back
+    Overturned,
+}
+
+/// A player's appeal against one of their score invalidations
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Appeal {
+    /// Id of the appealed invalidation in `scoreInvalidations`
+    pub score_id: u64,
+    pub wallet_address: PlayerKey,
+    /// The player's case
+    pub statement: String,
+    pub status: AppealStatus,
+    /// When the appeal was filed (UNIX timestamp)
+    pub filed_at: u64,
+    /// Wallet that resolved the appeal
+    pub resolved_by: Option<String>,
+    /// Explanation given with the resolution
+    pub resolution_note: Option<String>,
+    /// When the appeal was resolved (UNIX timestamp)
+    pub resolved_at: Option<u64>,
+}
+
+/// A player's high score and the replay that proves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreSnapshot {
+    pub high_score: u32,
+    pub replay_data: Option<String>,
+    pub replay_compression: ReplayCompression,
+    pub replay_meta: Option<ReplayMetadata>,
+    pub replay_format_version: u32,
+    pub replay_summary: Option<ReplaySummary>,
+    pub replay_origin: Option<ReplayOrigin>,
+}
+
+/// A new high score that counts on the leaderboard but is rolled back
+/// unless verification confirms it in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisionalHighScore {
+    /// Verification queue item that confirms or rejects the score
+    pub verification_id: u64,
+    /// Last block height at which verification can confirm it
+    pub confirm_by_height: u64,
+    /// Last confirmed high score, restored on rollback
+    pub previous: HighScoreSnapshot,
+    /// The run that set the score, indexed once verification confirms it.
+    /// Scores made provisional before this was kept were indexed right away.
+    #[serde(default)]
+    pub run: Option<ScoredRun>,
+}
+
+/// A run's score and the context it was submitted in, enough to put it in
+/// the score indexes later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredRun {
+    pub score: u32,
+    /// When the game was played (UNIX timestamp, as submitted)
+    pub played_at: u64,
+    /// When the run was submitted (UNIX timestamp)
+    pub submitted_at: u64,
+    pub season: u32,
+    /// Challenge modifier active when the run was submitted
+    pub modifier: Option<ChallengeModifier>,
+}
+
+impl PlayerData {
+    /// Count a run towards placement, once `games_played` includes it. The
+    /// run that completes placement sets the calibration rating and level
+    /// bracket. Players who had played all their placement runs before
+    /// placement existed are never calibrated.
+    pub fn record_placement_run(&mut self, score: u32) {
+        if self.calibration_rating.is_some() || self.games_played > PLACEMENT_RUNS {
+            return;
+        }
+        self.placement_scores.push(score);
+        if self.games_played == PLACEMENT_RUNS {
+            let rating = calibration_rating(&self.placement_scores);
+            self.calibration_rating = Some(rating);
+            self.level_bracket = Some(LevelBracket::for_rating(rating));
+        }
+    }
+
+    /// How long ago the account was created, as of `now`. Accounts created
+    /// before creation was tracked count as old as can be, as the age gates
+    /// have always treated them.
+    pub fn account_age(&self, now: u64) -> u64 {
+        now.saturating_sub(self.first_seen_at)
+    }
+
+    /// Last time the player was seen active (UNIX timestamp), 0 if unknown
+    pub fn last_active_at(&self) -> u64 {
+        let played_at = self.last_played_at.unwrap_or(0);
+        played_at.max(self.first_seen_at).max(self.renamed_at.unwrap_or(0))
+    }
+
+    /// Whether the account is at least `VETERAN_ACCOUNT_AGE` old as of `now`
+    pub fn is_veteran(&self, now: u64) -> bool {
+        self.account_age(now) >= VETERAN_ACCOUNT_AGE
+    }
+
+    /// Count a game played in `mode` at `played_at`
+    pub fn record_mode_game(&mut self, mode: GameMode, played_at: u64) {
+        let index = match self.mode_stats.binary_search_by_key(&mode, |stats| stats.mode) {
+            Ok(index) => index,
+            Err(index) => {
+                let stats = ModeStats {
+                    mode,
+                    games_played: 0,
+                    last_played_at: 0,
+                };
+                self.mode_stats.insert(index, stats);
+                index
+            }
+        };
+        let stats = &mut self.mode_stats[index];
+        stats.games_played += 1;
+        stats.last_played_at = played_at;
+    }
+
+    /// Keep `run` among the player's best scores, highest first; an equal
+    /// score doesn't displace an older one
+    pub fn record_personal_best(&mut self, run: &ScoredRun) {
+        let position = self
+            .personal_bests
+            .iter()
+            .position(|best| run.score > best.score)
+            .unwrap_or(self.personal_bests.len());
+        if position < MAX_PERSONAL_BESTS {
+            self.personal_bests.insert(
+                position,
+                PersonalBest {
+                    score: run.score,
+                    played_at: run.played_at,
+                    modifier: run.modifier,
+                },
+            );
+            self.personal_bests.truncate(MAX_PERSONAL_BESTS);
+        }
+    }
+
+    /// Merge best scores kept on another chain into the player's, highest
+    /// first; equal scores keep the older run first
+    pub fn merge_personal_bests(&mut self, other: Vec<PersonalBest>) {
+        self.personal_bests.extend(other);
+        self.personal_bests
+            .sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.played_at.cmp(&b.played_at)));
+        self.personal_bests.truncate(MAX_PERSONAL_BESTS);
+    }
+
+    /// Matchmaking bucket the player is paired within: from the higher of the
+    /// calibration rating and the high score, so a player who improved after
+    /// placement moves up with their high score
+    pub fn matchmaking_bucket(&self) -> u32 {
+        let rating = self.calibration_rating.unwrap_or(0).max(self.high_score);
+        rating / RACE_BRACKET_WIDTH
+    }
+
+    /// Replay bytes stored for the player, counted against
+    /// `replay_storage_quota`. Only classic mode keeps a replay so far; modes
+    /// that keep their own add theirs here.
+    pub fn replay_bytes(&self) -> u64 {
+        let previous = self
+            .provisional
+            .as_ref()
+            .and_then(|provisional| provisional.previous.replay_data.as_ref());
+        [self.replay_data.as_ref(), previous]
+            .into_iter()
+            .flatten()
+            .map(|replay| replay.len() as u64)
+            .sum()
+    }
+
+    /// The current high score together with the replay that proves it
+    pub fn high_score_snapshot(&self) -> HighScoreSnapshot {
+        HighScoreSnapshot {
+            high_score: self.high_score,
+            replay_data: self.replay_data.clone(),
+            replay_compression: self.replay_compression,
+            replay_meta: self.replay_meta.clone(),
+            replay_format_version: self.replay_format_version,
+            replay_summary: self.replay_summary.clone(),
+            replay_origin: self.replay_origin.clone(),
+        }
+    }
+
+    /// Put back a high score and its replay taken with `high_score_snapshot`
+    pub fn restore_high_score(&mut self, snapshot: HighScoreSnapshot) {
+        self.high_score = snapshot.high_score;
+        self.replay_data = snapshot.replay_data;
+        self.replay_compression = snapshot.replay_compression;
+        self.replay_meta = snapshot.replay_meta;
+        self.replay_format_version = snapshot.replay_format_version;
+        self.replay_summary = snapshot.replay_summary;
+        self.replay_origin = snapshot.replay_origin;
+    }
+
+    /// The stored replay as recording JSON, decompressed if it was stored
+    /// compressed
+    pub fn replay_json(&self) -> Option<Result<String, CompressionError>> {
+        let max_size = *ConfigField::MaxReplaySize.bounds().end();
+        self.replay_data
+            .as_ref()
+            .map(|replay| compression::decompress_replay(replay, self.replay_compression, max_size))
+    }
+}
+
+/// Where a claimable reward was earned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum RewardSource {
+    Season,
+    Tournament,
+}
+
+/// A reward recorded for a wallet, paid out only when the wallet claims it
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClaimableReward {
+    /// Wallet allowed to claim this reward
+    pub wallet_address: PlayerKey,
+    /// Amount reserved from the prize pool
+    pub amount: Amount,
+    /// What the reward was earned in
+    pub source: RewardSource,
+    /// Season or tournament number the reward belongs to
+    pub source_id: u32,
+    /// When the reward was granted (UNIX timestamp, seconds)
+    pub granted_at: u64,
+    /// After this time the reward can no longer be claimed and returns to the pool
+    pub expires_at: u64,
+}
+
+/// A reward as stored before schema version 5, naming the wallet as it was sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyClaimableReward {
+    pub wallet_address: String,
+    pub amount: Amount,
+    pub source: RewardSource,
+    pub source_id: u32,
+    pub granted_at: u64,
+    pub expires_at: u64,
+}
+
+/// Non-transferable trophy minted for a top-3 finish in a season
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Trophy {
+    /// Season the trophy was won in
+    pub season: u32,
+    /// Final position in the season standings (1, 2 or 3)
+    pub rank: u8,
+    /// Best score of the season that earned the trophy
+    pub score: u32,
+    /// When the season was closed (UNIX timestamp, seconds)
+    pub awarded_at: u64,
+}
+
+/// A ghost race in progress against another player's high-score replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostRace {
+    /// Wallet whose replay is being raced
+    pub opponent: String,
+    /// Opponent's high score when the race started
+    pub opponent_score: u32,
+    /// When the race started (UNIX timestamp, seconds)
+    pub started_at: u64,
+}
+
+/// Win/loss tally of one player's races against another player's ghost
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct GhostRaceRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// A player waiting in the matchmaking queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedRacer {
+    /// Matchmaking bracket the player is waiting in
+    pub bracket: u32,
+    /// Chain the player enqueued from, notified once they are paired
+    pub chain_id: ChainId,
+    /// Key used to co-own a temporary race chain, if the player wants one
+    pub public_key: Option<PublicKey>,
+}
+
+/// A live race between two paired players
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Race {
+    /// Wallets of the two participants
+    pub players: [String; 2],
+    /// Shared seed both participants play on
+    pub seed: u64,
+    /// Submitted scores, in the same order as `players`
+    pub scores: [Option<u32>; 2],
+    /// Winner once both runs are in (None on a draw or while running)
+    pub winner: Option<String>,
+    /// Whether both participants have submitted their runs
+    pub finished: bool,
+    /// When the players were paired (UNIX timestamp, seconds)
+    pub started_at: u64,
+    /// Temporary chain hosting the race, if one was opened
+    pub race_chain: Option<ChainId>,
+}
+
+/// Live view of a race published to spectators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct RaceSnapshot {
+    pub race_id: u64,
+    /// Wallets of the two participants
+    pub players: [String; 2],
+    /// Latest position of each participant, in the same order as `players`
+    pub positions: Vec<GhostPoint>,
+    /// Scores submitted so far
+    pub scores: [Option<u32>; 2],
+    /// Whether this is the final snapshot of the race
+    pub finished: bool,
+    /// When the snapshot was published (UNIX timestamp, seconds)
+    pub taken_at: u64,
+}
+
+/// Where a temporary race chain reports its result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaceChainInfo {
+    /// Chain that paired the players
+    pub hub: ChainId,
+    /// Main chains of the participants, in the same order as `Race::players`
+    pub home_chains: [ChainId; 2],
+}
+
+/// Pairing notification received by a player's chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RaceMatch {
+    pub race_id: u64,
+    pub opponent: String,
+    pub seed: u64,
+    pub race_chain: Option<ChainId>,
+}
+
+/// Application settings controlled by the admin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    /// How long a granted reward stays claimable (seconds)
+    pub reward_claim_window: u64,
+    /// Number of top high scores whose replays are kept; other replays are pruned unless featured
+    pub replay_retention_top_n: u32,
+    /// Oldest client version (MAJOR.MINOR.PATCH) allowed to submit scores
+    pub min_client_version: Option<String>,
+    /// Hub chain keeping the global leaderboard; other chains forward their scores to it
+    pub hub_chain: Option<ChainId>,
+    /// Largest replay accepted with a high score (bytes)
+    pub max_replay_size: u64,
+    /// Most scores a wallet can submit per hour
+    pub max_submissions_per_hour: u32,
+    /// Most players kept in the leaderboard index; players below it keep their
+    /// data but lose their replay
+    pub leaderboard_cap: u32,
+    /// How long a season runs before it can be closed (seconds)
+    pub season_length: u64,
+    /// Price of a loot box, added to the prize pool
+    pub loot_box_price: Amount,
+    /// Most coins a wallet can gift per day
+    pub max_gifts_per_day: u64,
+    /// How long a wallet must have played before it can gift coins (seconds)
+    pub min_gift_account_age: u64,
+    /// Games a player must have played before ranking on the public leaderboard
+    pub leaderboard_min_games: u32,
+    /// How long a player must have played before ranking on the public leaderboard (seconds)
+    pub leaderboard_min_account_age: u64,
+    /// Whether only verified players rank on the public leaderboard
+    pub leaderboard_require_verified: bool,
+    /// One-time fee that ranks a player without meeting the games and age requirements
+    /// (zero disables the fee)
+    pub leaderboard_entry_fee: Amount,
+    /// Matching verdicts needed to settle a replay verification
+    pub verifications_required: u32,
+    /// Notification hub application notable events are forwarded to, if any
+    pub notification_hub: Option<ApplicationId>,
+    /// Hub chains the global leaderboard is sharded across by wallet hash
+    /// (empty: `hub_chain` alone)
+    pub hub_shards: Vec<ChainId>,
+    /// Chain merging the shards' top scores into the global leaderboard
+    pub shard_coordinator: Option<ChainId>,
+    /// Bumped on every change to `hub_shards`; messages routed under an older
+    /// assignment are rejected
+    pub shard_epoch: u32,
+    /// Hub chain this chain mirrors read-only, if any
+    pub mirror_of: Option<ChainId>,
+    /// Most replay bytes kept for a single player across all game modes
+    pub replay_storage_quota: u64,
+    /// Blocks a new high score stays provisional awaiting verification before
+    /// it is rolled back; None accepts high scores outright
+    pub provisional_window: Option<u64>,
+    /// How season standings of inactive players decay; None disables decay
+    pub season_decay: Option<SeasonDecay>,
+    /// Storage entries are measured for one in this many executions of each
+    /// operation type (0 measures none)
+    pub op_metrics_sample_interval: u64,
+    /// Fee charged for every accepted score submission, added to the prize
+    /// pool (zero disables the fee)
+    pub submission_fee: Amount,
+    /// Faucet application granting new players their welcome bonus, if any
+    pub welcome_faucet: Option<ApplicationId>,
+    /// Starter balance the faucet grants on a wallet's first registration
+    pub welcome_bonus: Amount,
+}
+
+/// Decay of an inactive player's season standing, so the season board
+/// reflects active competition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct SeasonDecay {
+    /// Time without a run before the standing starts to decay (seconds)
+    pub inactive_after: u64,
+    /// Share of the best score lost for each started week past
+    /// `inactive_after`, in percent
+    pub percent_per_week: u32,
+}
+
+impl SeasonDecay {
+    /// Longest accepted `inactive_after`
+    pub const MAX_INACTIVE_AFTER: u64 = 365 * 24 * 60 * 60;
+
+    /// Whether the settings are usable: a decay rate of 1 to 100 percent
+    pub fn is_valid(&self) -> bool {
+        (1..=100).contains(&self.percent_per_week)
+            && self.inactive_after <= Self::MAX_INACTIVE_AFTER
+    }
+
+    /// `score`, last played at `last_played_at`, decayed as of `now`
+    pub fn apply(&self, score: u32, last_played_at: u64, now: u64) -> u32 {
+        let idle = now.saturating_sub(last_played_at);
+        if idle <= self.inactive_after {
+            return score;
+        }
+        let weeks = (idle - self.inactive_after - 1) / SECONDS_PER_WEEK + 1;
+        let percent = weeks.saturating_mul(u64::from(self.percent_per_week)).min(100);
+        let kept = u64::from(score) * (100 - percent) / 100;
+        kept as u32
+    }
+}
+
+/// AppConfig fields adjustable with `UpdateConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigField {
+    MaxReplaySize,
+    MaxSubmissionsPerHour,
+    LeaderboardCap,
+    SeasonLength,
+    LeaderboardMinGames,
+    LeaderboardMinAccountAge,
+    /// 1 to rank only verified players, 0 to rank everyone
+    LeaderboardRequireVerified,
+    ReplayStorageQuota,
+    OpMetricsSampleInterval,
+}
+
+impl ConfigField {
+    /// Hard sanity bounds on a field's value, so a mistyped update cannot
+    /// disable submissions or let state grow without limit
+    pub fn bounds(self) -> RangeInclusive<u64> {
+        match self {
+            ConfigField::MaxReplaySize => 1_024..=4_000_000,
+            ConfigField::MaxSubmissionsPerHour => 1..=3_600,
+            ConfigField::LeaderboardCap => 10..=10_000,
+            ConfigField::SeasonLength => 24 * 60 * 60..=365 * 24 * 60 * 60,
+            ConfigField::LeaderboardMinGames => 0..=1_000,
+            ConfigField::LeaderboardMinAccountAge => 0..=90 * 24 * 60 * 60,
+            ConfigField::LeaderboardRequireVerified => 0..=1,
+            ConfigField::ReplayStorageQuota => 1_024..=16_000_000,
+            ConfigField::OpMetricsSampleInterval => 0..=1_000_000,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Hub chain holding `wallet`'s scores: its shard when the leaderboard is
+    /// sharded, otherwise the single hub chain
+    pub fn hub_for(&self, wallet: &PlayerKey) -> Option<ChainId> {
+        match shard_index(wallet, self.hub_shards.len()) {
+            Some(index) => self.hub_shards.get(index).copied(),
+            None => self.hub_chain,
+        }
+    }
+}
+
+/// Shard of `wallet` among `shard_count` shards: the first 8 bytes of the
+/// SHA-256 of its address, modulo the shard count. None without shards.
+pub fn shard_index(wallet: &PlayerKey, shard_count: usize) -> Option<usize> {
+    let digest = Sha256::digest(wallet.to_string().as_bytes());
+    let prefix = u64::from_be_bytes(*digest.first_chunk::<8>()?);
+    let index = prefix.checked_rem(shard_count as u64)?;
+    usize::try_from(index).ok()
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            reward_claim_window: 30 * 24 * 60 * 60, // 30 days
+            replay_retention_top_n: 100,
+            min_client_version: None,
+            hub_chain: None,
+            max_replay_size: crate::validation::DEFAULT_MAX_REPLAY_SIZE,
+            max_submissions_per_hour: 120,
+            leaderboard_cap: 100,
+            season_length: 30 * 24 * 60 * 60, // 30 days
+            loot_box_price: Amount::from_tokens(1),
+            max_gifts_per_day: 500,
+            min_gift_account_age: 7 * 24 * 60 * 60, // 7 days
+            leaderboard_min_games: 0,
+            leaderboard_min_account_age: 0,
+            leaderboard_require_verified: false,
+            leaderboard_entry_fee: Amount::ZERO,
+            verifications_required: 2,
+            notification_hub: None,
+            hub_shards: Vec::new(),
+            shard_coordinator: None,
+            shard_epoch: 0,
+            mirror_of: None,
+            replay_storage_quota: crate::validation::DEFAULT_REPLAY_STORAGE_QUOTA,
+            provisional_window: None,
+            season_decay: None,
+            op_metrics_sample_interval: 0,
+            submission_fee: Amount::ZERO,
+            welcome_faucet: None,
+            welcome_bonus: Amount::ZERO,
+        }
+    }
+}
+
+/// A replay picked by the admin for the community carousel.
+/// Featured replays are pinned and never pruned. The replay is copied when
+/// it is featured, so a later high score doesn't swap it for another run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeaturedReplay {
+    /// High score of the replay when it was featured
+    pub score: u32,
+    /// When the replay was featured (UNIX timestamp, seconds)
+    pub featured_at: u64,
+    /// The featured replay, stored as the player sent it
+    pub replay_data: String,
+    pub replay_compression: ReplayCompression,
+}
+
+impl FeaturedReplay {
+    /// The featured replay as recording JSON, decompressed if it was stored
+    /// compressed
+    pub fn replay_json(&self) -> Result<String, CompressionError> {
+        let max_size = *ConfigField::MaxReplaySize.bounds().end();
+        compression::decompress_replay(&self.replay_data, self.replay_compression, max_size)
+    }
+}
+
+/// Community counters for a player's current high-score replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayStats {
+    /// High score of the replay these counters belong to
+    pub replay_score: u32,
+    /// Number of distinct wallets that liked the replay
+    pub likes: u64,
+    /// Number of distinct wallets that watched the replay
+    pub views: u64,
+}
+
+/// Running totals of the replay retention policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PruningStats {
+    /// Number of replays removed so far
+    pub replays_pruned: u64,
+    /// Total size of the removed replay payloads
+    pub bytes_pruned: u64,
+    /// When a replay was last pruned (UNIX timestamp, seconds)
+    pub last_pruned_at: Option<u64>,
+    /// Roster position the running `PruneReplays` pass resumes from, null
+    /// when no pass is running
+    pub cursor: Option<u64>,
+}
+
+/// Inactivity after which `ArchiveInactivePlayers` moves a player to the
+/// archive (seconds)
+pub const ARCHIVE_AFTER: u64 = 365 * SECONDS_PER_DAY;
+
+/// Running totals of the players in the archive
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct ArchiveStats {
+    /// Players currently archived
+    pub archived_players: u64,
+    /// Games played by the archived players
+    pub games_played: u64,
+    /// Sum of the archived players' scores
+    pub total_score: u64,
+    /// Players restored from the archive so far
+    pub restored: u64,
+    /// When a player was last archived (UNIX timestamp, seconds)
+    pub last_archived_at: Option<u64>,
+    /// Roster position the next `ArchiveInactivePlayers` operation starts from
+    pub cursor: u64,
+}
+
+/// Relative weight of each lane type when a new lane is generated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LaneWeights {
+    pub car: u32,
+    pub truck: u32,
+    pub forest: u32,
+}
+
+/// Speed multiplier applied to every lane from `from_lane` onwards
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct DifficultyStep {
+    pub from_lane: u32,
+    pub speed_multiplier_percent: u32,
+}
+
+/// Deterministic game parameters shared by clients and replay verification.
+/// Speeds are stored in thousandths so every validator computes the same values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct GameConfig {
+    /// Speeds a vehicle lane can be assigned (thousandths of a board unit per frame)
+    pub lane_speeds_milli: Vec<u32>,
+    /// Lane type distribution for generated lanes
+    pub lane_weights: LaneWeights,
+    /// Vehicles spawned on a car lane
+    pub cars_per_lane: u32,
+    /// Vehicles spawned on a truck lane
+    pub trucks_per_lane: u32,
+    /// Points awarded per new lane reached
+    pub points_per_lane: u32,
+    /// Multiplier applied to the final score, in percent
+    pub score_multiplier_percent: u32,
+    /// Speed-up steps by lane reached, sorted by `from_lane` and starting at lane 0
+    pub difficulty_curve: Vec<DifficultyStep>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            lane_speeds_milli: vec![2_000, 2_500, 3_000],
+            lane_weights: LaneWeights {
+                car: 1,
+                truck: 1,
+                forest: 1,
+            },
+            cars_per_lane: 3,
+            trucks_per_lane: 2,
+            points_per_lane: 1,
+            score_multiplier_percent: 100,
+            difficulty_curve: vec![DifficultyStep {
+                from_lane: 0,
+                speed_multiplier_percent: 100,
+            }],
+        }
+    }
+}
+
+impl GameConfig {
+    /// Check that the parameters describe a playable game
+    pub fn is_valid(&self) -> bool {
+        let weights = &self.lane_weights;
+        let curve_sorted = self
+            .difficulty_curve
+            .windows(2)
+            .all(|steps| steps[0].from_lane < steps[1].from_lane);
+
+        !self.lane_speeds_milli.is_empty()
+            && self.lane_speeds_milli.iter().all(|speed| *speed > 0)
+            && weights.car + weights.truck + weights.forest > 0
+            && self.points_per_lane > 0
+            && self.score_multiplier_percent > 0
+            && self.difficulty_curve.first().map(|step| step.from_lane) == Some(0)
+            && self.difficulty_curve.iter().all(|step| step.speed_multiplier_percent > 0)
+            && curve_sorted
+    }
+}
+
+/// A score forwarded to the hub that bounced and waits to be resent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingHubScore {
+    pub score: u32,
+    pub replay_data: Option<String>,
+    pub compression: ReplayCompression,
+    pub timestamp: u64,
+    pub client_version: String,
+    pub submission_id: String,
+    pub run_stats: Option<RunStats>,
+    pub proof: Option<String>,
+    /// When the bounce came back (UNIX timestamp)
+    pub bounced_at: u64,
+}
+
+/// An ephemeral key allowed to submit scores on behalf of a wallet until it expires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionKey {
+    /// Wallet the submitted scores are attributed to
+    pub owner: PlayerKey,
+    /// When the key stops being accepted (UNIX timestamp)
+    pub expires_at: u64,
+}
+
+/// Admin roles. Moderators curate replays, verifiers settle seasons and
+/// rewards, super admins can do everything including changing config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Role {
+    SuperAdmin,
+    Moderator,
+    Verifier,
+}
+
+/// A privileged operation, kept for accountability
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AdminAuditEntry {
+    /// Position in the audit log, used as the pagination cursor
+    pub id: u64,
+    /// Wallet that signed the operation
+    pub actor: String,
+    /// Operation name, e.g. "GrantReward"
+    pub action: String,
+    /// The operation's parameters as JSON
+    pub parameters: String,
+    /// When the operation was executed (UNIX timestamp)
+    pub recorded_at: u64,
+}
+
+/// A player's final position in a closed season
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SeasonStanding {
+    pub rank: u32,
+    pub wallet_address: String,
+    pub score: u32,
+}
+
+/// A closed season. The full standings are published as a JSON data blob
+/// so they stay verifiable after the live season state is reset.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SeasonRecord {
+    pub season: u32,
+    /// When the season started (UNIX timestamp)
+    pub started_at: u64,
+    /// When the season was closed (UNIX timestamp)
+    pub closed_at: u64,
+    /// Number of players with a score in the season
+    pub player_count: u32,
+    /// Hash of the data blob holding the final standings
+    pub standings_blob: String,
+}
+
+/// Number of best players whose scores add up to a region's score in an
+/// event rollup, so a region can't win on headcount alone
+pub const EVENT_REGION_TOP_N: usize = 10;
+
+/// Players in a team relay event team
+pub const TEAM_SIZE: usize = 3;
+
+/// Most teams a team relay event can hold, which bounds its standings
+pub const MAX_TEAMS_PER_EVENT: usize = 500;
+
+/// Most events a wallet can take part in until they are frozen
+pub const MAX_OPEN_EVENTS_PER_WALLET: usize = 20;
+
+/// How a community event is played
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum EventKind {
+    /// Players register for a region, and regions are ranked by rollup
+    #[default]
+    Regional,
+    /// Teams of `TEAM_SIZE` players are ranked by the sum of their members'
+    /// best runs
+    TeamRelay,
+}
+
+/// A community event. In a regional event, registered players declare the
+/// region they play for; in a team relay, they play for a team. Their best
+/// run within the window counts.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CommunityEvent {
+    pub tag: String,
+    pub name: String,
+    #[serde(default)]
+    pub kind: EventKind,
+    /// When runs start counting (UNIX timestamp)
+    pub starts_at: u64,
+    /// When runs stop counting (UNIX timestamp)
+    pub ends_at: u64,
+    pub created_by: String,
+    /// When the results were frozen with `EndEvent` (UNIX timestamp)
+    pub frozen_at: Option<u64>,
+}
+
+impl CommunityEvent {
+    /// Whether a run played at `now` counts for the event
+    pub fn is_running(&self, now: u64) -> bool {
+        self.frozen_at.is_none() && self.starts_at <= now && now < self.ends_at
+    }
+}
+
+/// A member of a team relay team and their runs in the event window
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TeamMember {
+    pub wallet_address: PlayerKey,
+    /// Best score within the event window, 0 before the first run
+    pub best_score: u32,
+    /// Runs played within the event window
+    pub runs: u32,
+}
+
+/// A team in a team relay event. The roster locks when the event starts.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct EventTeam {
+    pub team_id: u64,
+    /// Tag of the event the team plays in
+    pub tag: String,
+    pub name: String,
+    /// Creator of the team, the only member who can invite
+    pub captain: PlayerKey,
+    /// Members in the order they joined, the captain first
+    pub members: Vec<TeamMember>,
+    /// Wallets invited that have not accepted yet
+    pub invited: Vec<PlayerKey>,
+    /// When the team was created (UNIX timestamp)
+    pub created_at: u64,
+}
+
+impl EventTeam {
+    /// Whether the team has all `TEAM_SIZE` members
+    pub fn is_full(&self) -> bool {
+        self.members.len() >= TEAM_SIZE
+    }
+
+    /// Sum of the members' best runs in the event window
+    pub fn score(&self) -> u64 {
+        self.members.iter().map(|member| u64::from(member.best_score)).sum()
+    }
+}
+
+/// A full team's place in a team relay event
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TeamStanding {
+    pub rank: u32,
+    pub team_id: u64,
+    pub name: String,
+    pub members: Vec<TeamMember>,
+    pub score: u64,
+}
+
+/// A player registered into an event
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct EventEntry {
+    /// ISO 3166-1 alpha-2 country code, upper case
+    pub country: String,
+    pub city: Option<String>,
+    /// Best score within the event window, 0 before the first run
+    pub best_score: u32,
+    /// Runs played within the event window
+    pub runs: u32,
+    /// When the player registered (UNIX timestamp)
+    pub registered_at: u64,
+}
+
+/// A player's place in an event
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct EventStanding {
+    pub rank: u32,
+    pub wallet_address: String,
+    pub country: String,
+    pub city: Option<String>,
+    pub score: u32,
+}
+
+/// A region's place in an event rollup. City regions carry their country.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RegionStanding {
+    pub rank: u32,
+    pub country: String,
+    /// None for a country rollup
+    pub city: Option<String>,
+    /// Sum of the best scores of the region's `EVENT_REGION_TOP_N` best players
+    pub score: u64,
+    /// Registered players of the region with a run in the window
+    pub players: u32,
+    pub best_score: u32,
+}
+
+/// Region rollups of an event, by country and by city
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct EventRollup {
+    pub countries: Vec<RegionStanding>,
+    pub cities: Vec<RegionStanding>,
+}
+
+/// Group (country, city, score) entries, given best score first, into
+/// ranked regions
+fn rank_regions<'a>(
+    entries: impl Iterator<Item = (&'a String, Option<&'a String>, u32)>,
+) -> Vec<RegionStanding> {
+    let mut regions: Vec<RegionStanding> = Vec::new();
+    for (country, city, score) in entries {
+        let index = match regions
+            .iter()
+            .position(|region| region.country == *country && region.city.as_ref() == city)
+        {
+            Some(index) => index,
+            None => {
+                regions.push(RegionStanding {
+                    rank: 0,
+                    country: country.clone(),
+                    city: city.cloned(),
+                    score: 0,
+                    players: 0,
+                    best_score: score,
+                });
+                regions.len() - 1
+            }
+        };
+        if let Some(region) = regions.get_mut(index) {
+            if (region.players as usize) < EVENT_REGION_TOP_N {
+                region.score += u64::from(score);
+            }
+            region.players += 1;
         }
     }
+    regions.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.country.cmp(&b.country))
+            .then_with(|| a.city.cmp(&b.city))
+    });
+    for (index, region) in regions.iter_mut().enumerate() {
+        region.rank = index as u32 + 1;
+    }
+    regions
+}
+
+/// Resources consumed by the executions of one type of operation, to tune
+/// limits and fees on
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct OpMetrics {
+    pub executions: u64,
+    /// Replay bytes submitted with the operations, before decompression
+    pub replay_bytes: u64,
+    pub max_replay_bytes: u64,
+    /// Storage entries written or deleted by the sampled executions
+    pub entries_touched: u64,
+    pub max_entries_touched: u64,
+    /// Block of the last execution
+    pub last_block: u64,
+    /// Executions whose storage entries were measured
+    pub sampled_executions: u64,
+}
+
+impl OpMetrics {
+    /// Add one execution, with the storage entries it touched if they were measured
+    pub fn record(&mut self, replay_bytes: u64, entries_touched: Option<u64>, block_height: u64) {
+        self.executions += 1;
+        self.replay_bytes = self.replay_bytes.saturating_add(replay_bytes);
+        self.max_replay_bytes = self.max_replay_bytes.max(replay_bytes);
+        if let Some(entries_touched) = entries_touched {
+            self.sampled_executions += 1;
+            self.entries_touched = self.entries_touched.saturating_add(entries_touched);
+            self.max_entries_touched = self.max_entries_touched.max(entries_touched);
+        }
+        self.last_block = block_height;
+    }
+}
+
+/// Holder of an all-time record
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RecordHolder {
+    pub wallet_address: String,
+    pub value: u64,
+    /// When the record was set (UNIX timestamp)
+    pub set_at: u64,
+}
+
+/// All-time records, kept apart from the rolling leaderboard so season
+/// resets and leaderboard eviction never erase them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct AllTimeRecords {
+    /// Highest single score ever submitted
+    pub highest_score: Option<RecordHolder>,
+    /// Most games played by one wallet in a single day
+    pub most_games_in_a_day: Option<RecordHolder>,
+    /// Longest run of consecutive days with at least one game
+    pub longest_streak: Option<RecordHolder>,
+}
+
+/// Chain-wide totals, kept up to date as runs are submitted so they are cheap to serve
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct GlobalStats {
+    /// Wallets with player data on this chain
+    pub total_players: u64,
+    /// Runs submitted on this chain
+    pub total_runs: u64,
+    /// Rows crossed over all runs (the sum of their scores)
+    pub total_distance: u64,
+    /// Coins collected over all reported runs
+    pub total_coins: u64,
+    /// Day (UNIX days) the top score below was played on
+    pub top_day: u64,
+    /// Best score of `top_day`
+    pub top_score_today: u32,
+    pub top_wallet_today: Option<PlayerKey>,
+}
+
+impl GlobalStats {
+    /// Count a run scoring `score` by `wallet` on `day`
+    pub fn record_run(&mut self, day: u64, score: u32, coins: u64) {
+        self.total_runs += 1;
+        self.total_distance += score as u64;
+        self.total_coins = self.total_coins.saturating_add(coins);
+        if self.top_day != day {
+            self.top_day = day;
+            self.top_score_today = 0;
+            self.top_wallet_today = None;
+        }
+    }
+
+    /// Count `score` towards the top score of `day`, if that is still the
+    /// day being tracked
+    pub fn record_day_score(&mut self, day: u64, wallet: PlayerKey, score: u32) {
+        if self.top_day == day && score > self.top_score_today {
+            self.top_score_today = score;
+            self.top_wallet_today = Some(wallet);
+        }
+    }
+}
+
+/// Players kept in the archived top of each day
+pub const DAILY_TOP_N: usize = 20;
+
+/// Days a day's full score index is kept after the day ends; only its
+/// archived top is kept after that
+pub const DAILY_INDEX_RETENTION_DAYS: u64 = 7;
+
+/// A player's place in a day's top
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DailyStanding {
+    pub rank: u32,
+    pub wallet_address: PlayerKey,
+    /// Best score the player reached that day
+    pub score: u32,
+}
+
+/// What a quest counts, per day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum QuestGoal {
+    /// Number of games played
+    GamesPlayed,
+    /// Best score reached in a single run
+    ScoreInOneRun,
+    /// Sum of all scores
+    TotalScore,
+    /// Time spent in reported runs (milliseconds)
+    Playtime,
+    /// Coins collected in reported runs
+    CoinsCollected,
+}
+
+/// A daily quest from the catalog
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Quest {
+    pub id: u32,
+    /// Text shown to players, e.g. "Play 3 games"
+    pub title: String,
+    pub goal: QuestGoal,
+    /// Progress needed to complete the quest
+    pub target: u64,
+    /// Coins credited to the player when the quest is claimed. Progress comes
+    /// from run reports that aren't verified, so quests never pay tokens.
+    pub reward: u64,
+}
+
+/// A player's progress on a quest for one day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuestProgress {
+    /// Day (UNIX days) the progress counts for
+    pub day: u64,
+    pub progress: u64,
+    pub claimed: bool,
+}
+
+/// Length of a day for streaks and daily records (seconds)
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Account age from which a player is a veteran (seconds)
+pub const VETERAN_ACCOUNT_AGE: u64 = 365 * SECONDS_PER_DAY;
+
+/// Length of a weekly challenge (seconds)
+pub const SECONDS_PER_WEEK: u64 = 7 * 24 * 60 * 60;
+
+/// Week number (UNIX weeks) a timestamp falls in, used to schedule challenge modifiers
+pub fn challenge_week(timestamp: u64) -> u64 {
+    timestamp / SECONDS_PER_WEEK
+}
+
+/// Rule change applied to every run during a challenge week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ChallengeModifier {
+    /// Twice as many vehicles on every road
+    DoubleTraffic,
+    /// Darkened board with only the nearby lanes lit
+    NightMode,
+}
+
+/// A bought loot box waiting for its secret to be revealed
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LootBoxCommit {
+    /// Hex SHA-256 of the player's secret
+    pub commitment: String,
+    /// Block height the box was bought at
+    pub committed_at: u64,
+    /// Price paid into the prize pool, refunded if the hub can't draw a seed
+    #[serde(default)]
+    pub price: Amount,
+    /// Hex seed the hub chain drew after the commitment was made; the drop
+    /// depends only on it and the secret
+    #[serde(default)]
+    pub seed: Option<String>,
+}
+
+/// A cosmetic item owned by a player
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct InventoryItem {
+    pub item: String,
+    pub count: u32,
+}
+
+/// Coins gifted from one player to another, kept for moderation
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GiftRecord {
+    /// Position in the gift log, used as the pagination cursor
+    pub id: u64,
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    /// When the gift was sent (UNIX timestamp)
+    pub sent_at: u64,
+}
+
+/// Native tokens sent to a player as a tip
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Tip {
+    /// Wallet that sent the tip
+    pub from: String,
+    pub amount: Amount,
+    /// When the tip was sent (UNIX timestamp)
+    pub sent_at: u64,
+}
+
+/// Where a high-score replay is in the verification queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum VerificationStatus {
+    /// Waiting for a verifier to claim it
+    Pending,
+    /// A verifier is re-simulating the replay
+    Claimed,
+    /// Verifiers agreed the replay yields the score
+    Verified,
+    /// Verifiers agreed the replay does not yield the score
+    Rejected,
+    /// Verifiers disagreed; the admin settles it
+    Escalated,
+    /// The player set a new high score before this one was settled
+    Superseded,
+}
+
+/// A verifier's verdict on a replay
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VerificationVerdict {
+    pub verifier: String,
+    /// Whether the re-simulated replay yields the claimed score
+    pub valid: bool,
+    /// Hex SHA-256 of the re-simulated result
+    pub result_hash: String,
+    /// When the verdict was submitted (UNIX timestamp)
+    pub submitted_at: u64,
+}
+
+/// A high-score replay waiting for, or settled by, verifiers
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VerificationItem {
+    pub id: u64,
+    pub wallet: String,
+    pub score: u32,
+    pub status: VerificationStatus,
+    /// Verifier holding the claim, while `status` is `Claimed`
+    pub claimed_by: Option<String>,
+    /// When the current claim was made (UNIX timestamp)
+    pub claimed_at: u64,
+    pub verdicts: Vec<VerificationVerdict>,
+    /// When the high score was set (UNIX timestamp)
+    pub queued_at: u64,
+}
+
+impl VerificationStatus {
+    /// Whether the item still needs verifiers or the admin
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            VerificationStatus::Pending
+                | VerificationStatus::Claimed
+                | VerificationStatus::Escalated
+        )
+    }
+}
+
+/// Block that submitted a high-score replay. Its certificate carries the
+/// player's signature, so exported replays can be checked against it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ReplayOrigin {
+    pub chain_id: ChainId,
+    pub block_height: u64,
+    /// Wallet that signed the block
+    pub signer: Option<String>,
+}
+
+/// Version of the stored state layout, bumped whenever a change needs
+/// existing chains to be migrated
+pub const STATE_SCHEMA_VERSION: u32 = 7;
+
+/// How a leaderboard entry changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum LeaderboardChangeKind {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// One change to the leaderboard index, for incremental client sync
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardChange {
+    /// Position in the change log; strictly increasing
+    pub seq: u64,
+    pub kind: LeaderboardChangeKind,
+    pub wallet_address: PlayerKey,
+    /// New high score, None when the entry was removed
+    pub high_score: Option<u32>,
+    /// When the change happened (UNIX timestamp)
+    pub changed_at: u64,
+}
+
+/// Most submission receipts kept per player; the oldest are dropped first
+pub const MAX_SUBMISSION_RECEIPTS: usize = 100;
+
+/// Something notable about how an accepted submission was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ReceiptFlag {
+    /// The score became the player's high score, and its replay was stored
+    NewHighScore,
+    /// The high score waits for verification before it is confirmed
+    Provisional,
+    /// A succinct proof settled the run without verifiers
+    Proven,
+    /// The score was relayed from the player's chain rather than submitted here
+    Relayed,
+    /// A leaderboard freeze held back the player's leaderboard entry
+    LeaderboardFrozen,
+}
+
+/// What the chain recorded for an accepted score submission
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SubmissionReceipt {
+    pub submission_id: String,
+    pub score: u32,
+    pub mode: GameMode,
+    /// Height of the block that recorded the submission
+    pub block_height: u64,
+    /// When the game was played (UNIX timestamp, as submitted)
+    pub played_at: u64,
+    /// When the submission was recorded (UNIX timestamp)
+    pub recorded_at: u64,
+    /// Challenge modifier active when the run was recorded
+    pub modifier: Option<ChallengeModifier>,
+    pub flags: Vec<ReceiptFlag>,
+}
+
+/// Most notifications kept per player; the oldest are dropped first
+pub const MAX_NOTIFICATIONS: usize = 50;
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum NotificationKind {
+    /// Another player beat the player's all-time highest score
+    Dethroned,
+    /// Another player started a ghost race against the player's replay
+    ChallengeReceived,
+    /// A reward was granted to the player and can be claimed
+    RewardClaimable,
+    /// A season the player finished in the top 100 of was closed
+    SeasonEnded,
+    /// A team captain invited the player into their team relay team
+    TeamInvite,
+    /// An event of the calendar the player subscribed to started
+    EventStarted,
+    /// The hub rejected a score forwarded from this chain
+    ScoreRejected,
+}
+
+/// An entry in a player's notification inbox
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Notification {
+    /// Strictly increasing; acknowledging an id acknowledges everything before it
+    pub id: u64,
+    pub kind: NotificationKind,
+    /// The other player involved: the new record holder, the challenger or
+    /// the team captain
+    pub other_player: Option<PlayerKey>,
+    /// Reward id, season number, team id, calendar entry id or score
+    /// sequence, depending on the kind
+    pub subject_id: Option<u64>,
+    /// When the notification was sent (UNIX timestamp)
+    pub created_at: u64,
+}
+
+/// Most entries a shard hub reports to the coordinator
+pub const SHARD_TOP_N: usize = 100;
+
+/// A high score in a shard's top list
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ShardTopEntry {
+    pub wallet_address: PlayerKey,
+    pub high_score: u32,
+}
+
+/// Latest top list a shard hub reported to the coordinator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardTop {
+    /// Shard assignment the list was built under
+    pub shard_epoch: u32,
+    /// Highest score first, at most `SHARD_TOP_N`
+    pub entries: Vec<ShardTopEntry>,
+    /// When the list was received (UNIX timestamp)
+    pub updated_at: u64,
+}
+
+/// Proof that a score was accepted: `digest` is the hex SHA-256 of the chain
+/// id, the big-endian block height, the wallet address, the big-endian score
+/// and timestamp, the replay digest (empty without a replay) and the
+/// submission id, concatenated in that order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreAttestation {
+    pub chain_id: ChainId,
+    /// Height of the block that accepted the score
+    pub block_height: u64,
+    pub wallet_address: PlayerKey,
+    pub score: u32,
+    /// When the run was played, as submitted (UNIX timestamp)
+    pub timestamp: u64,
+    pub submission_id: String,
+    /// Hex SHA-256 of the submitted replay, if any
+    pub replay_digest: Option<String>,
+    pub digest: String,
+    /// Replay bytes the player can still store under `replay_storage_quota`,
+    /// so clients know how far to compress their next replay. Not covered
+    /// by `digest`.
+    pub replay_quota_remaining: u64,
+}
+
+/// Game mode a score was set in. Only the classic mode exists so far; new
+/// modes get their own scores and standings through `ScoreScope`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Enum,
+)]
+pub enum GameMode {
+    #[default]
+    Classic,
+}
+
+/// A (mode, season) pair scores are kept per; with the wallet, the composite
+/// key of `CrossyChainState::scoped_scores`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ScoreScope {
+    pub mode: GameMode,
+    pub season: u32,
+}
+
+impl ScoreScope {
+    pub fn new(mode: GameMode, season: u32) -> Self {
+        Self { mode, season }
+    }
+}
+
+/// A player's results within one mode and season
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct ScopedScore {
+    pub best_score: u32,
+    pub games_played: u32,
+    /// When the last game in the scope was recorded (UNIX timestamp)
+    pub last_played_at: u64,
+}
+
+/// A player's games in one mode, across seasons
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ModeStats {
+    pub mode: GameMode,
+    pub games_played: u32,
+    /// When the last game in the mode was recorded (UNIX timestamp)
+    pub last_played_at: u64,
+}
+
+impl ScopedScore {
+    /// Best score after `decay` for inactivity as of `now`
+    pub fn standing(&self, decay: Option<SeasonDecay>, now: u64) -> u32 {
+        decay.map_or(self.best_score, |decay| {
+            decay.apply(self.best_score, self.last_played_at, now)
+        })
+    }
+}
+
+/// Progress of a `RebuildIndexes` run spread over several blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct IndexRebuild {
+    /// Position in `player_roster` the next batch starts at
+    pub cursor: u64,
+    /// Players rebuilt so far
+    pub processed: u64,
+    /// Death tally summed over the players rebuilt so far
+    pub death_tally: DeathTally,
+    /// Games played summed over the players rebuilt so far
+    #[serde(default)]
+    pub games_played: u64,
+    /// Total score summed over the players rebuilt so far
+    #[serde(default)]
+    pub total_score: u64,
+    /// When the run started (UNIX timestamp)
+    pub started_at: u64,
+}
+
+/// Longest a leaderboard freeze can last, in seconds
+pub const MAX_LEADERBOARD_FREEZE: u64 = 24 * 60 * 60;
+
+/// Most updates held back by a leaderboard freeze that are applied per block
+/// once it lifts
+pub const MAX_HELD_UPDATES_PER_BLOCK: usize = 100;
+
+/// A window during which accepted scores don't change the leaderboard, so
+/// standings can be audited before prizes go out
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardFreeze {
+    /// When the freeze started (UNIX timestamp)
+    pub frozen_at: u64,
+    /// When the freeze lifts on its own (UNIX timestamp)
+    pub until: u64,
+    pub frozen_by: String,
+}
+
+impl LeaderboardFreeze {
+    /// Whether the leaderboard is still frozen at `now`
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.until
+    }
+}
+
+/// Most actions that can be scheduled at once
+pub const MAX_SCHEDULED_ACTIONS: usize = 50;
+
+/// Most rewards one `ExpireRewards` run returns to the prize pool; the
+/// scheduled action comes back a block later while more remain
+pub const MAX_REWARDS_EXPIRED_PER_RUN: usize = 100;
+
+/// Most provisional high scores one `ExpireProvisionalScores` run rolls back
+pub const MAX_PROVISIONAL_EXPIRED_PER_RUN: usize = 50;
+
+/// Seconds between two scheduled checks for expired provisional high scores
+pub const PROVISIONAL_EXPIRY_INTERVAL: u64 = 60 * 60;
+
+/// A time-based transition the contract can perform on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ScheduledActionKind {
+    /// Close the current season, as `CloseSeason` does, and schedule the next close
+    CloseSeason,
+    /// Freeze an event's results, as `EndEvent` does
+    EndEvent,
+    /// Return expired rewards to the prize pool, as `ExpireRewards` does
+    ExpireRewards,
+    /// Lift the leaderboard freeze, as `UnfreezeLeaderboard` does
+    UnfreezeLeaderboard,
+    /// Roll back provisional high scores not confirmed in time, as
+    /// `ExpireProvisionalScores` does, and check again every
+    /// `PROVISIONAL_EXPIRY_INTERVAL` while the provisional window is set
+    ExpireProvisionalScores,
+    /// Tell the chains subscribed to event notifications that a calendar
+    /// entry started
+    AnnounceCalendarEntry,
+}
+
+/// An action fired by the first player operation executed once it is due
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ScheduledAction {
+    pub id: u64,
+    pub kind: ScheduledActionKind,
+    /// Event ended by an `EndEvent` action
+    pub event_tag: Option<String>,
+    /// Calendar entry announced by an `AnnounceCalendarEntry` action
+    #[serde(default)]
+    pub calendar_entry_id: Option<u64>,
+    /// When the action is due (UNIX timestamp)
+    pub due_at: u64,
+    pub scheduled_by: String,
+}
+
+/// A scheduled action that failed when it fired, kept until an admin
+/// cancels it
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FailedAction {
+    pub action: ScheduledAction,
+    /// Error the action failed with
+    pub error: String,
+    /// When the action failed (UNIX timestamp)
+    pub failed_at: u64,
+}
+
+/// Most entries the event calendar can hold
+pub const MAX_CALENDAR_ENTRIES: usize = 100;
+
+/// Most players of one chain notified when a calendar event starts
+pub const MAX_EVENT_SUBSCRIBERS: usize = 500;
+
+/// What an event calendar entry announces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum CalendarEntryKind {
+    Tournament,
+    ClanWar,
+    /// A week played with a challenge modifier
+    WeeklyModifier,
+}
+
+/// An entry of the event calendar the client home screen shows
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CalendarEntry {
+    pub id: u64,
+    pub kind: CalendarEntryKind,
+    pub title: String,
+    /// When the event starts (UNIX timestamp)
+    pub starts_at: u64,
+    /// When the event ends (UNIX timestamp)
+    pub ends_at: u64,
+    /// Community event the entry announces, if any
+    pub event_tag: Option<String>,
+    /// Modifier played during a weekly modifier
+    pub modifier: Option<ChallengeModifier>,
+    pub created_by: String,
+}
+
+/// Coins granted once a player completes every onboarding step
+pub const ONBOARDING_REWARD_COINS: u64 = 100;
+
+/// Most friends a player can add
+pub const MAX_FRIENDS: usize = 200;
+
+/// A step of the onboarding tutorial, completed by doing it on chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum OnboardingStep {
+    Registered,
+    FirstRun,
+    FirstReplay,
+    NameSet,
+    FriendAdded,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 5] = [
+        OnboardingStep::Registered,
+        OnboardingStep::FirstRun,
+        OnboardingStep::FirstReplay,
+        OnboardingStep::NameSet,
+        OnboardingStep::FriendAdded,
+    ];
+}
+
+/// A player's progress through onboarding
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Onboarding {
+    /// Completed steps, in the order they were completed
+    pub completed: Vec<OnboardingStep>,
+    /// When the last step was completed and the reward granted (UNIX timestamp)
+    pub completed_at: Option<u64>,
+}
+
+impl Onboarding {
+    /// Mark `step` completed, returning whether it wasn't already
+    pub fn complete(&mut self, step: OnboardingStep) -> bool {
+        if self.completed.contains(&step) {
+            return false;
+        }
+        self.completed.push(step);
+        true
+    }
+
+    /// Whether every step is completed
+    pub fn is_finished(&self) -> bool {
+        OnboardingStep::ALL.iter().all(|step| self.completed.contains(step))
+    }
+
+    /// Steps left to complete, in tutorial order
+    pub fn remaining(&self) -> Vec<OnboardingStep> {
+        OnboardingStep::ALL
+            .into_iter()
+            .filter(|step| !self.completed.contains(step))
+            .collect()
+    }
 }
 
 /// Application state
-#[derive(RootView)]
+#[derive(RootView, ClonableView)]
 pub struct CrossyChainState<C> {
     /// Map of wallet addresses to player data
-    pub players: MapView<C, String, PlayerData>,
+    pub players: MapView<C, PlayerKey, PlayerData>,
+    /// Wallet address allowed to run privileged operations (set at initialization)
+    pub admin: RegisterView<C, Option<PlayerKey>>,
+    /// Admin-controlled application settings
+    pub config: RegisterView<C, AppConfig>,
+    /// Tokens held by the application that are not yet reserved for a reward
+    pub prize_pool: RegisterView<C, Amount>,
+    /// Outstanding claimable rewards by reward ID, as stored before schema
+    /// version 5 moved them to `rewards`
+    pub legacy_rewards: MapView<C, u64, LegacyClaimableReward>,
+    /// ID assigned to the next granted reward
+    pub next_reward_id: RegisterView<C, u64>,
+    /// Number of the season currently being played
+    pub current_season: RegisterView<C, u32>,
+    /// Best score per wallet in the current season, from before `scoped_scores`;
+    /// only holds scores of the season running when scoped scores were added
+    pub season_scores: MapView<C, PlayerKey, u32>,
+    /// Trophies won by each wallet
+    pub trophies: CollectionView<C, PlayerKey, LogView<C, Trophy>>,
+    /// Active ghost race per racing wallet
+    pub ghost_races: MapView<C, String, GhostRace>,
+    /// Ghost race results keyed by (racer, opponent)
+    pub ghost_race_tallies: MapView<C, (PlayerKey, PlayerKey), GhostRaceRecord>,
+    /// Ghost score of the last rated race, keyed by (racer, ghost owner); a
+    /// ghost is rated against once per high score it held
+    pub rated_ghost_races: MapView<C, (PlayerKey, PlayerKey), u32>,
+    /// Player waiting for an opponent, by matchmaking bracket
+    pub race_queue: MapView<C, u32, String>,
+    /// Players currently waiting in the race queue
+    pub queued_racers: MapView<C, String, QueuedRacer>,
+    /// Races by race ID
+    pub races: MapView<C, u64, Race>,
+    /// ID assigned to the next race
+    pub next_race_id: RegisterView<C, u64>,
+    /// Unfinished race per participating wallet
+    pub active_races: MapView<C, String, u64>,
+    /// Latest pairing received by this chain, per wallet
+    pub race_matches: MapView<C, String, RaceMatch>,
+    /// Reporting targets, set on temporary race chains only
+    pub race_chain_info: MapView<C, u64, RaceChainInfo>,
+    /// Latest live position per (race, wallet)
+    pub race_positions: MapView<C, (u64, String), GhostPoint>,
+    /// Number of position updates received per race, used to pace snapshots
+    pub race_update_counts: MapView<C, u64, u32>,
+    /// Latest spectator snapshot received per race
+    pub race_snapshots: MapView<C, u64, RaceSnapshot>,
+    /// Featured ("replay of the week") replays by wallet
+    pub featured_replays: MapView<C, PlayerKey, FeaturedReplay>,
+    /// Like/view counters per replay owner, keyed by the wallet as it was
+    /// sent; schema version 3 moves them to `replay_stats`
+    pub legacy_replay_stats: MapView<C, String, ReplayStats>,
+    /// Likes as (replay owner, replay score, liker), as stored before schema
+    /// version 7 moved them to `replay_likes`
+    pub legacy_replay_likes: SetView<C, (String, u32, String)>,
+    /// Views as (replay owner, replay score, viewer), as stored before schema
+    /// version 7 moved them to `replay_views`
+    pub legacy_replay_views: SetView<C, (String, u32, String)>,
+    /// Score of the stored replay for every wallet that still has one
+    pub replay_holders: MapView<C, PlayerKey, u32>,
+    /// Replay retention statistics
+    pub pruning_stats: RegisterView<C, PruningStats>,
+    /// Data of players inactive for `ARCHIVE_AFTER`, kept out of `players`
+    /// until their next operation
+    pub archived_players: MapView<C, PlayerKey, PlayerData>,
+    pub archive_stats: RegisterView<C, ArchiveStats>,
+    /// Deterministic game parameters served to clients and used to verify replays
+    pub game_config: RegisterView<C, GameConfig>,
+    /// Version of `game_config`, bumped on every change
+    pub config_version: RegisterView<C, u32>,
+    /// Game configs that were replaced, by the version they were active under
+    pub game_config_history: MapView<C, u32, GameConfig>,
+    /// Chains whose SaveScore, RegisterPlayer and SubmissionFee messages this
+    /// chain accepts
+    pub authorized_chains: SetView<C, ChainId>,
+    /// Last SaveScore sequence number forwarded to the hub, per wallet
+    pub hub_sequences: MapView<C, PlayerKey, u64>,
+    /// Scores that bounced off the hub, by wallet then sequence, resent on the wallet's next
+    /// operation
+    pub pending_hub_sync: CollectionView<C, PlayerKey, MapView<C, u64, PendingHubScore>>,
+    /// Last forwarded SaveScore sequence the hub settled, accepted or rejected, per
+    /// (origin chain, wallet)
+    pub accepted_sequences: MapView<C, (ChainId, PlayerKey), u64>,
+    /// Next id for accounts exported from this chain
+    pub next_migration_id: RegisterView<C, u64>,
+    /// Account imports already applied, by (origin chain, migration id)
+    pub imported_migrations: SetView<C, (ChainId, u64)>,
+    /// Link requests waiting for the new owner to accept, by new owner -> profile owner
+    pub pending_links: MapView<C, PlayerKey, PlayerKey>,
+    /// Secondary owners linked to a profile, by secondary owner -> profile owner
+    pub linked_owners: MapView<C, PlayerKey, PlayerKey>,
+    /// Session keys authorized to submit scores, by session key
+    pub session_keys: MapView<C, PlayerKey, SessionKey>,
+    /// Admin roles granted to wallets other than the admin
+    pub roles: MapView<C, PlayerKey, Role>,
+    /// Wallet the admin offered the admin seat to, until it accepts
+    pub pending_admin: RegisterView<C, Option<PlayerKey>>,
+    /// Append-only record of every privileged operation
+    pub admin_audit_log: LogView<C, AdminAuditEntry>,
+    /// When the current season started (UNIX timestamp)
+    pub season_started_at: RegisterView<C, u64>,
+    /// High score of every player within `leaderboard_cap`, the source of the leaderboard
+    pub leaderboard_index: MapView<C, PlayerKey, u32>,
+    /// Entries of `leaderboard_index`, highest score first, ties broken by
+    /// wallet address
+    pub leaderboard_top: RegisterView<C, Vec<(PlayerKey, u32)>>,
+    /// Layout version the stored state was last migrated to
+    pub schema_version: RegisterView<C, u32>,
+    /// Closed seasons, by season number
+    pub season_history: MapView<C, u32, SeasonRecord>,
+    /// Top of the final standings of closed seasons, by season number
+    pub season_leaderboards: MapView<C, u32, Vec<SeasonStanding>>,
+    /// All-time records
+    pub records: RegisterView<C, AllTimeRecords>,
+    /// How all reported runs on this chain ended
+    pub death_tally: RegisterView<C, DeathTally>,
+    /// Totals served by `globalStats`
+    pub global_stats: RegisterView<C, GlobalStats>,
+    /// Best score of every player on each day (UNIX days), kept for
+    /// `DAILY_INDEX_RETENTION_DAYS` after the day ends
+    pub daily_scores: CollectionView<C, u64, MapView<C, PlayerKey, u32>>,
+    /// Top `DAILY_TOP_N` of every ended day, by day (UNIX days)
+    pub daily_tops: MapView<C, u64, Vec<DailyStanding>>,
+    /// Daily quest catalog, by quest id
+    pub quests: MapView<C, u32, Quest>,
+    /// Id of the next quest added to the catalog
+    pub next_quest_id: RegisterView<C, u32>,
+    /// Daily quest progress, by (player, quest id)
+    pub quest_progress: MapView<C, (PlayerKey, u32), QuestProgress>,
+    /// Challenge modifier scheduled for each week, by `challenge_week`
+    pub modifier_schedule: MapView<C, u64, ChallengeModifier>,
+    /// Best score of each player under each modifier
+    pub modifier_scores: MapView<C, (ChallengeModifier, PlayerKey), u32>,
+    /// Loot boxes bought and not opened yet, one per player
+    pub loot_box_commits: MapView<C, PlayerKey, LootBoxCommit>,
+    /// Hex digest chaining every loot box seed the hub chain drew
+    pub loot_entropy: RegisterView<C, String>,
+    /// Cosmetic items owned, by (player, item) -> count, as stored before
+    /// schema version 6 moved them to `inventory`
+    pub legacy_inventory: MapView<C, (PlayerKey, String), u32>,
+    /// Every coin gift, oldest first
+    pub gift_log: LogView<C, GiftRecord>,
+    /// Tips received, per recipient, oldest first
+    pub tips: CollectionView<C, PlayerKey, LogView<C, Tip>>,
+    /// High-score replays waiting for, or settled by, verifiers
+    pub verification_queue: MapView<C, u64, VerificationItem>,
+    pub next_verification_id: RegisterView<C, u64>,
+    /// Verification item of each player's current high score
+    pub verification_by_wallet: MapView<C, PlayerKey, u64>,
+    /// Every change to `leaderboard_index`, oldest first
+    pub leaderboard_changes: LogView<C, LeaderboardChange>,
+    /// Unacknowledged notifications, per player, oldest first
+    pub notifications: CollectionView<C, PlayerKey, QueueView<C, Notification>>,
+    /// Receipts of each player's accepted submissions, oldest first
+    pub submission_receipts: CollectionView<C, PlayerKey, QueueView<C, SubmissionReceipt>>,
+    /// Id the next notification gets. It is shared by every inbox, so ids
+    /// grow in the order notifications were sent and `AckNotifications`
+    /// can take "up to this id".
+    pub next_notification_id: RegisterView<C, u64>,
+    /// Top list of each shard hub, by shard chain (coordinator chain only)
+    pub shard_tops: MapView<C, ChainId, ShardTop>,
+    /// Commitment to the top of `leaderboard_index`, for inclusion proofs
+    pub leaderboard_commitment: RegisterView<C, LeaderboardCommitment>,
+    /// Results per (mode, season), then per wallet. Use the accessors below
+    /// instead of adding per-mode or per-season maps.
+    pub scoped_scores: CollectionView<C, ScoreScope, MapView<C, PlayerKey, ScopedScore>>,
+    /// Actions waiting for their due time, by id
+    pub scheduled_actions: MapView<C, u64, ScheduledAction>,
+    pub next_scheduled_action_id: RegisterView<C, u64>,
+    /// Scheduled actions that failed when they fired, by id
+    pub failed_actions: MapView<C, u64, FailedAction>,
+    /// Block height that last fired a scheduled action; one action fires
+    /// per block
+    pub last_action_height: RegisterView<C, Option<u64>>,
+    /// Event calendar entries, by id
+    pub calendar: MapView<C, u64, CalendarEntry>,
+    pub next_calendar_entry_id: RegisterView<C, u64>,
+    /// Chain whose event calendar this chain follows, set by the super admin
+    pub events_source: RegisterView<C, Option<ChainId>>,
+    /// Entries of followed calendars that started, by (calendar chain, id)
+    pub remote_calendar: MapView<C, (ChainId, u64), CalendarEntry>,
+    /// Players on this chain notified when a calendar event starts
+    pub event_subscribers: SetView<C, PlayerKey>,
+    /// The current leaderboard freeze, if any
+    pub leaderboard_freeze: RegisterView<C, Option<LeaderboardFreeze>>,
+    /// Wallets whose leaderboard entry changed during the freeze, applied
+    /// when it lifts
+    pub frozen_leaderboard_updates: SetView<C, PlayerKey>,
+    /// Runs accepted during the freeze, oldest first, put in the score
+    /// indexes when it lifts
+    pub held_runs: QueueView<C, (PlayerKey, ScoredRun)>,
+    /// Block height held-back updates were last applied at
+    pub held_updates_height: RegisterView<C, Option<u64>>,
+    /// Every wallet that got an account on this chain, in the order they
+    /// first did; `RebuildIndexes` walks it by position
+    pub player_roster: LogView<C, PlayerKey>,
+    /// Wallets already in `player_roster`
+    pub rostered_players: SetView<C, PlayerKey>,
+    /// Progress of the running `RebuildIndexes`, if any
+    pub index_rebuild: RegisterView<C, Option<IndexRebuild>>,
+    /// Confirmation deadline (block height) of every provisional high score
+    pub provisional_scores: MapView<C, PlayerKey, u64>,
+    /// Every high score removed with `InvalidateScore`, oldest first
+    pub score_invalidations: LogView<C, ScoreInvalidation>,
+    /// Invalidation ids of every wallet with an invalidated score, as stored
+    /// before schema version 4 moved them to `wallet_invalidations`
+    pub legacy_invalidations_by_wallet: MapView<C, PlayerKey, Vec<u64>>,
+    /// The removed high score and replay of each invalidation, given back if
+    /// an appeal overturns it; dropped once an appeal upholds it
+    pub invalidated_high_scores: MapView<C, u64, HighScoreSnapshot>,
+    /// The replay of each verified best that was the player's high score when
+    /// it was verified, by wallet then verification id; `InvalidateScore`
+    /// restores it with the score
+    pub verified_best_replays: CollectionView<C, PlayerKey, MapView<C, u64, HighScoreSnapshot>>,
+    /// Appeals by the id of the invalidation they contest
+    pub appeals: MapView<C, u64, Appeal>,
+    /// Community events by tag
+    pub events: MapView<C, String, CommunityEvent>,
+    /// Registered players of each event
+    pub event_entries: CollectionView<C, String, MapView<C, PlayerKey, EventEntry>>,
+    /// Tags of the events each wallet registered into, until they are frozen
+    pub events_by_wallet: MapView<C, PlayerKey, Vec<String>>,
+    /// Region rollups of ended events, frozen by `EndEvent`
+    pub event_results: MapView<C, String, EventRollup>,
+    /// Id the next team relay team will get
+    pub next_team_id: RegisterView<C, u64>,
+    /// Team relay teams by id
+    pub event_teams: MapView<C, u64, EventTeam>,
+    /// Team ids of each team relay event
+    pub teams_by_event: MapView<C, String, Vec<u64>>,
+    /// Team each wallet plays for, per team relay event
+    pub team_memberships: CollectionView<C, String, MapView<C, PlayerKey, u64>>,
+    /// Team leaderboards of ended team relay events, frozen by `EndEvent`
+    pub team_results: MapView<C, String, Vec<TeamStanding>>,
+    /// Wallets that were granted the welcome bonus, which is granted once
+    pub welcome_bonus_claimed: SetView<C, PlayerKey>,
+    /// Execution metrics by operation name
+    pub op_metrics: MapView<C, String, OpMetrics>,
+    /// Head-to-head rating of every wallet with a rated match
+    pub ratings: MapView<C, PlayerKey, PlayerRating>,
+    /// Rating changes of each wallet, oldest first
+    pub rating_history: CollectionView<C, PlayerKey, LogView<C, RatingChange>>,
+    /// Allowlist of partner applications, with the privileged requests each may make
+    pub partner_capabilities: MapView<C, ApplicationId, Vec<PartnerCapability>>,
+    /// Coins partner applications hold for players, by (application, wallet)
+    pub partner_coin_holds: MapView<C, (ApplicationId, PlayerKey), CoinHold>,
+    /// Scripts display names may use
+    pub name_script_policy: RegisterView<C, NameScriptPolicy>,
+    /// Terms display names can't contain
+    pub name_blocklist: RegisterView<C, NameBlocklist>,
+    /// Display names flagged by a blocklist update, by wallet
+    pub name_reviews: MapView<C, PlayerKey, NameReview>,
+    /// Verified players by the skeleton of their display name, which other
+    /// players can't take a confusable variant of
+    pub verified_names: MapView<C, String, PlayerKey>,
+    /// Latest score attestation received for each wallet from its hub
+    pub oracle_attestations: MapView<C, PlayerKey, OracleAttestation>,
+    /// Onboarding progress of each player who started it
+    pub onboarding: MapView<C, PlayerKey, Onboarding>,
+    /// Wallets each player added as friends, oldest first
+    pub friends: MapView<C, PlayerKey, Vec<PlayerKey>>,
+    /// Like/view counters per replay owner
+    pub replay_stats: MapView<C, PlayerKey, ReplayStats>,
+    /// Temporary chain of each race paired with a wallet of this chain, the
+    /// only chain its position updates and result are taken from
+    pub matched_race_chains: MapView<C, u64, ChainId>,
+    /// Invalidation ids of every wallet with an invalidated score, oldest first
+    pub wallet_invalidations: CollectionView<C, PlayerKey, LogView<C, u64>>,
+    /// Ids of the appealed invalidations, in the order the appeals were filed
+    pub appeal_log: LogView<C, u64>,
+    /// Position in `appeal_log` of the oldest appeal that may still be pending
+    pub first_pending_appeal: RegisterView<C, u64>,
+    /// Block of the last executed operation, so metrics only measure the
+    /// storage entries of the first operation of a block
+    pub last_operation_height: RegisterView<C, Option<u64>>,
+    /// Roster position the running `RebalanceShard` pass resumes from, None
+    /// when no pass is running
+    pub rebalance_cursor: RegisterView<C, Option<u64>>,
+    /// Race chains this chain subscribed to with `SpectateRace`, the only
+    /// chains its race snapshots are taken from
+    pub spectated_race_chains: SetView<C, ChainId>,
+    /// Outstanding claimable rewards by reward ID
+    pub rewards: MapView<C, u64, ClaimableReward>,
+    /// IDs of each wallet's outstanding rewards, oldest first
+    pub wallet_rewards: MapView<C, PlayerKey, Vec<u64>>,
+    /// Cosmetic items each player owns, by item -> count
+    pub inventory: CollectionView<C, PlayerKey, MapView<C, String, u32>>,
+    /// Likes as (replay owner, replay score, liker)
+    pub replay_likes: SetView<C, (PlayerKey, u32, PlayerKey)>,
+    /// Views as (replay owner, replay score, viewer)
+    pub replay_views: SetView<C, (PlayerKey, u32, PlayerKey)>,
+}
+
+impl<C> CrossyChainState<C>
+where
+    C: Context + Send + Sync + Clone + 'static,
+    ViewError: From<C::Error>,
+{
+    /// `wallet`'s player data, from the archive if they went inactive
+    pub async fn player_or_archived(
+        &self,
+        wallet: &PlayerKey,
+    ) -> Result<Option<PlayerData>, ViewError> {
+        match self.players.get(wallet).await? {
+            Some(player) => Ok(Some(player)),
+            None => self.archived_players.get(wallet).await,
+        }
+    }
+
+    /// `wallet`'s results in `scope`, zeroed if it has not played there
+    pub async fn scoped_score(
+        &self,
+        scope: ScoreScope,
+        wallet: &PlayerKey,
+    ) -> Result<ScopedScore, ViewError> {
+        match self.scoped_scores.try_load_entry(&scope).await? {
+            Some(scores) => Ok(scores.get(wallet).await?.unwrap_or_default()),
+            None => Ok(ScopedScore::default()),
+        }
+    }
+
+    /// Count a game by `wallet` in `scope`, keeping its best score
+    pub async fn record_scoped_score(
+        &mut self,
+        scope: ScoreScope,
+        wallet: &PlayerKey,
+        score: u32,
+        played_at: u64,
+    ) -> Result<(), ViewError> {
+        let decay = self.config.get().season_decay;
+        let scores = self.scoped_scores.load_entry_mut(&scope).await?;
+        let mut entry = scores.get(wallet).await?.unwrap_or_default();
+        // Decay from a break ends with this run, so apply it for good first
+        if let Some(decay) = decay {
+            entry.best_score = decay.apply(entry.best_score, entry.last_played_at, played_at);
+        }
+        entry.best_score = entry.best_score.max(score);
+        entry.games_played += 1;
+        entry.last_played_at = played_at;
+        scores.insert(wallet, entry)
+    }
+
+    /// Raise `wallet`'s best score in `scope` to `score` without counting a
+    /// game, for scores indexed after the run was counted
+    pub async fn raise_scoped_best(
+        &mut self,
+        scope: ScoreScope,
+        wallet: &PlayerKey,
+        score: u32,
+    ) -> Result<(), ViewError> {
+        let scores = self.scoped_scores.load_entry_mut(&scope).await?;
+        let Some(mut entry) = scores.get(wallet).await? else {
+            return Ok(());
+        };
+        entry.best_score = entry.best_score.max(score);
+        scores.insert(wallet, entry)
+    }
+
+    /// Every wallet's results in `scope`, in wallet order
+    pub async fn scope_scores(
+        &self,
+        scope: ScoreScope,
+    ) -> Result<Vec<(PlayerKey, ScopedScore)>, ViewError> {
+        let mut entries = Vec::new();
+        if let Some(scores) = self.scoped_scores.try_load_entry(&scope).await? {
+            for wallet in scores.indices().await? {
+                if let Some(entry) = scores.get(&wallet).await? {
+                    entries.push((wallet, entry));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Standings of every registered player of `tag` with a run in the
+    /// window, best first; ties go to the earlier registration
+    pub async fn event_standings(&self, tag: &str) -> Result<Vec<EventStanding>, ViewError> {
+        let mut entries = Vec::new();
+        if let Some(players) = self.event_entries.try_load_entry(tag).await? {
+            for wallet in players.indices().await? {
+                if let Some(entry) = players.get(&wallet).await? {
+                    if entry.runs > 0 {
+                        entries.push((wallet, entry));
+                    }
+                }
+            }
+        }
+        entries.sort_by(|(a_wallet, a), (b_wallet, b)| {
+            b.best_score
+                .cmp(&a.best_score)
+                .then(a.registered_at.cmp(&b.registered_at))
+                .then_with(|| a_wallet.cmp(b_wallet))
+        });
+        Ok(entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (wallet, entry))| EventStanding {
+                rank: index as u32 + 1,
+                wallet_address: wallet.to_string(),
+                country: entry.country,
+                city: entry.city,
+                score: entry.best_score,
+            })
+            .collect())
+    }
+
+    /// Ranking of `day` (UNIX days) from its full score index, or from its
+    /// archived top once the index was pruned. Ties go to the wallet first in order.
+    pub async fn daily_standings(&self, day: u64) -> Result<Vec<DailyStanding>, ViewError> {
+        let Some(scores) = self.daily_scores.try_load_entry(&day).await? else {
+            return Ok(self.daily_tops.get(&day).await?.unwrap_or_default());
+        };
+        let mut entries = Vec::new();
+        for wallet in scores.indices().await? {
+            if let Some(score) = scores.get(&wallet).await? {
+                entries.push((wallet, score));
+            }
+        }
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (wallet, score))| DailyStanding {
+                rank: index as u32 + 1,
+                wallet_address: wallet,
+                score,
+            })
+            .collect())
+    }
+
+    /// Roll the standings of `tag` up by country and by city
+    pub async fn event_rollup(&self, tag: &str) -> Result<EventRollup, ViewError> {
+        let standings = self.event_standings(tag).await?;
+        let countries = rank_regions(
+            standings
+                .iter()
+                .map(|standing| (&standing.country, None, standing.score)),
+        );
+        let cities = rank_regions(standings.iter().filter_map(|standing| {
+            let city = standing.city.as_ref()?;
+            Some((&standing.country, Some(city), standing.score))
+        }));
+        Ok(EventRollup { countries, cities })
+    }
+
+    /// Standings of the full teams of team relay event `tag`, best first;
+    /// ties go to the team created first
+    pub async fn team_standings(&self, tag: &str) -> Result<Vec<TeamStanding>, ViewError> {
+        let mut teams = Vec::new();
+        for team_id in self.teams_by_event.get(tag).await?.unwrap_or_default() {
+            if let Some(team) = self.event_teams.get(&team_id).await? {
+                if team.is_full() {
+                    teams.push(team);
+                }
+            }
+        }
+        teams.sort_by(|a, b| b.score().cmp(&a.score()).then(a.team_id.cmp(&b.team_id)));
+        Ok(teams
+            .into_iter()
+            .enumerate()
+            .map(|(index, team)| TeamStanding {
+                rank: index as u32 + 1,
+                team_id: team.team_id,
+                score: team.score(),
+                name: team.name,
+                members: team.members,
+            })
+            .collect())
+    }
 }
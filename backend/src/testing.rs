@@ -0,0 +1,290 @@
+use crate::compression::ReplayCompression;
+use crate::contract::{ContractError, CrossyChainContract, Message, Operation, OperationResponse};
+use crate::replay::REPLAY_FORMAT_VERSION;
+use crate::service::CrossyChainService;
+use crate::state::RunStats;
+use async_graphql::{Request, Response};
+use linera_sdk::{
+    base::{BlockHeight, ChainId, MessageId, Owner, PublicKey, Timestamp},
+    views::{RootView, View},
+    Contract, ContractRuntime, Service, ServiceRuntime,
+};
+
+/// Client version test submissions claim by default
+pub const TEST_CLIENT_VERSION: &str = "1.0.0";
+
+/// Time test sessions start at (UNIX seconds)
+pub const START: u64 = 1_700_000_000;
+
+/// Test wallet number `index`, the same in every run
+pub fn wallet(index: u64) -> Owner {
+    Owner::from(PublicKey::test_key(index as u8))
+}
+
+/// Drives the contract and the service of one chain against an in-memory
+/// store, without a validator network. Time and block height only move
+/// when the test says so, so sessions replay the same way every run.
+pub struct TestHarness {
+    contract: CrossyChainContract,
+    chain_id: ChainId,
+    /// Current time (UNIX seconds)
+    now: u64,
+    block_height: u64,
+}
+
+impl TestHarness {
+    /// Create the application on `chain_id`, as `admin`, at time `now`
+    pub async fn new(chain_id: ChainId, admin: Owner, now: u64) -> Self {
+        let runtime = ContractRuntime::new()
+            .with_chain_id(chain_id)
+            .with_authenticated_signer(admin)
+            .with_system_time(Timestamp::from(now * 1_000_000))
+            .with_block_height(BlockHeight(0));
+        let context = runtime.root_view_storage_context();
+        let state = <CrossyChainContract as Contract>::State::load(context)
+            .await
+            .expect("the in-memory store never fails");
+        let mut contract = CrossyChainContract::new(state, runtime)
+            .await
+            .expect("creating the contract never fails");
+        contract
+            .initialize(())
+            .await
+            .expect("initializing the contract never fails");
+
+        TestHarness {
+            contract,
+            chain_id,
+            now,
+            block_height: 0,
+        }
+    }
+
+    /// The chain the harness runs
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Current time (UNIX seconds)
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Move the clock forward by `seconds`
+    pub fn advance_time(&mut self, seconds: u64) {
+        self.now += seconds;
+    }
+
+    /// Execute `operation` in a new block, signed by `signer`
+    pub async fn execute_operation(
+        &mut self,
+        signer: Owner,
+        operation: Operation,
+    ) -> Result<OperationResponse, ContractError> {
+        self.next_block(Some(signer));
+        self.contract.execute_operation(operation).await
+    }
+
+    /// Execute `message` in a new block, as if sent by `origin` with the
+    /// authentication of `signer`
+    pub async fn execute_message(
+        &mut self,
+        origin: ChainId,
+        signer: Option<Owner>,
+        message: Message,
+        is_bounced: bool,
+    ) -> Result<(), ContractError> {
+        self.next_block(signer);
+        let runtime = self.contract.runtime_mut();
+        runtime.set_message_id(MessageId {
+            chain_id: origin,
+            height: BlockHeight(0),
+            index: 0,
+        });
+        runtime.set_message_is_bounced(Some(is_bounced));
+        self.contract.execute_message(message).await
+    }
+
+    /// Run a GraphQL request against the state left by the last block
+    pub async fn query(&mut self, request: impl Into<Request>) -> Response {
+        self.contract
+            .state_mut()
+            .save()
+            .await
+            .expect("the in-memory store never fails");
+
+        let store = self.contract.runtime_mut().key_value_store();
+        let runtime = ServiceRuntime::new()
+            .with_chain_id(self.chain_id)
+            .with_system_time(Timestamp::from(self.now * 1_000_000))
+            .with_key_value_store(store.to_mut());
+        let context = runtime.root_view_storage_context();
+        let state = <CrossyChainService as Service>::State::load(context)
+            .await
+            .expect("the in-memory store never fails");
+        let service = CrossyChainService::new(state, runtime)
+            .await
+            .expect("creating the service never fails");
+        service.handle_query(request.into()).await
+    }
+
+    /// Run a GraphQL request that must succeed and return its data as JSON
+    pub async fn query_json(&mut self, request: impl Into<Request>) -> serde_json::Value {
+        let response = self.query(request).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().expect("the response is JSON")
+    }
+
+    /// Point the runtime at a new block, at the current time
+    fn next_block(&mut self, signer: Option<Owner>) {
+        self.block_height += 1;
+        let runtime = self.contract.runtime_mut();
+        runtime.set_authenticated_signer(signer);
+        runtime.set_system_time(Timestamp::from(self.now * 1_000_000));
+        runtime.set_block_height(BlockHeight(self.block_height));
+        runtime.set_message_is_bounced(None);
+    }
+}
+
+/// Builds a score submission, as an operation on the player's own chain or
+/// as the message a hub receives. Unset fields get valid defaults.
+#[derive(Debug, Clone)]
+pub struct SaveScoreBuilder {
+    score: u32,
+    replay_data: Option<String>,
+    compression: ReplayCompression,
+    timestamp: u64,
+    client_version: String,
+    submission_id: Option<String>,
+    run_stats: Option<RunStats>,
+    proof: Option<String>,
+}
+
+impl SaveScoreBuilder {
+    /// A run scoring `score`, without a replay
+    pub fn new(score: u32) -> Self {
+        SaveScoreBuilder {
+            score,
+            replay_data: None,
+            compression: ReplayCompression::None,
+            timestamp: 0,
+            client_version: TEST_CLIENT_VERSION.to_string(),
+            submission_id: None,
+            run_stats: None,
+            proof: None,
+        }
+    }
+
+    /// Attach a recording of `score` forward moves, which reaches the score
+    /// under the default game config
+    pub fn recorded(self) -> Self {
+        let replay = test_recording(self.score);
+        self.replay(replay)
+    }
+
+    /// Attach a replay (JSON recording)
+    pub fn replay(mut self, replay_data: impl Into<String>) -> Self {
+        self.replay_data = Some(replay_data.into());
+        self
+    }
+
+    /// Attach a replay compressed with `compression`, base64-encoded
+    pub fn compressed_replay(
+        mut self,
+        replay_data: impl Into<String>,
+        compression: ReplayCompression,
+    ) -> Self {
+        self.replay_data = Some(replay_data.into());
+        self.compression = compression;
+        self
+    }
+
+    /// When the run was played (UNIX seconds)
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn client_version(mut self, client_version: impl Into<String>) -> Self {
+        self.client_version = client_version.into();
+        self
+    }
+
+    /// Defaults to one derived from the score and timestamp, so two builds of
+    /// the same run count as a retry
+    pub fn submission_id(mut self, submission_id: impl Into<String>) -> Self {
+        self.submission_id = Some(submission_id.into());
+        self
+    }
+
+    pub fn run_stats(mut self, run_stats: RunStats) -> Self {
+        self.run_stats = Some(run_stats);
+        self
+    }
+
+    /// Attach a hex-encoded run proof
+    pub fn proof(mut self, proof: impl Into<String>) -> Self {
+        self.proof = Some(proof.into());
+        self
+    }
+
+    fn resolved_submission_id(&self) -> String {
+        self.submission_id
+            .clone()
+            .unwrap_or_else(|| format!("test-{}-{}", self.score, self.timestamp))
+    }
+
+    /// The `SaveScore` operation a player submits
+    pub fn operation(self) -> Operation {
+        let submission_id = self.resolved_submission_id();
+        Operation::SaveScore {
+            score: self.score,
+            replay_data: self.replay_data,
+            compression: self.compression,
+            timestamp: self.timestamp,
+            client_version: self.client_version,
+            submission_id,
+            run_stats: self.run_stats,
+            proof: self.proof,
+        }
+    }
+
+    /// The `SaveScore` message a hub receives, with the player's `sequence`
+    /// number and the `shard_epoch` it was routed under
+    pub fn message(self, sequence: u64, shard_epoch: u32) -> Message {
+        let submission_id = self.resolved_submission_id();
+        Message::SaveScore {
+            score: self.score,
+            replay_data: self.replay_data,
+            compression: self.compression,
+            timestamp: self.timestamp,
+            client_version: self.client_version,
+            submission_id,
+            run_stats: self.run_stats,
+            proof: self.proof,
+            sequence,
+            shard_epoch,
+        }
+    }
+}
+
+/// JSON recording of a run moving forward `score` lanes, one move every
+/// 200 milliseconds, with the envelope a current client sends
+pub fn test_recording(score: u32) -> String {
+    let inputs = (1..=u64::from(score))
+        .map(|step| serde_json::json!({ "timestamp": step * 200, "action": "forward" }))
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "seed": 1,
+        "startTime": 0,
+        "inputs": inputs,
+        "finalScore": score,
+        "duration": u64::from(score) * 200 + 1_000,
+        "version": "1.0",
+        "clientVersion": TEST_CLIENT_VERSION,
+        "platform": "web",
+        "formatVersion": REPLAY_FORMAT_VERSION,
+        "configVersion": 0,
+    })
+    .to_string()
+}
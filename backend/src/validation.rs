@@ -0,0 +1,219 @@
+use crate::replay::ClientVersion;
+use crate::state::{NameScript, PlayerKey};
+use thiserror::Error;
+
+/// Maximum length of a display name, in characters, after trimming
+pub const MAX_DISPLAY_NAME_LEN: usize = 30;
+
+/// Default for `AppConfig::max_replay_size` (1MB, to prevent state bloat)
+pub const DEFAULT_MAX_REPLAY_SIZE: u64 = 1_000_000;
+
+/// Default for `AppConfig::replay_storage_quota`
+pub const DEFAULT_REPLAY_STORAGE_QUOTA: u64 = 2_000_000;
+
+/// Maximum length of a city name declared for an event, in characters
+pub const MAX_CITY_NAME_LEN: usize = 64;
+
+/// Maximum length of a client-generated submission id
+pub const MAX_SUBMISSION_ID_LEN: usize = 64;
+
+/// Most coins a run can collect per lane it reaches
+pub const MAX_COINS_PER_LANE: u64 = 3;
+
+/// Input validation failures shared by the contract and the GraphQL pre-checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("Invalid score: score must be greater than 0")]
+    InvalidScore,
+
+    #[error("Replay too large: replay data exceeds the configured size limit")]
+    ReplayTooLarge,
+
+    #[error("Invalid version: client versions must look like MAJOR.MINOR.PATCH")]
+    InvalidClientVersion,
+
+    #[error("Invalid submission id: submission ids must be 1 to 64 characters")]
+    InvalidSubmissionId,
+
+    #[error("Name too long: display names are limited to 30 characters")]
+    NameTooLong,
+
+    #[error("Name empty: display names cannot be empty or only whitespace")]
+    NameEmpty,
+
+    #[error("Invalid wallet address: expected a hex-encoded owner")]
+    InvalidWalletAddress,
+
+    #[error("Invalid timestamp: expected an RFC 3339 date-time such as 2024-05-01T12:00:00Z")]
+    InvalidTimestamp,
+
+    #[error("Invalid region: expected a two-letter country code and a city of up to 64 characters")]
+    InvalidRegion,
+
+    #[error("Name blocked: display names cannot contain blocked terms")]
+    NameBlocked,
+
+    #[error("Script not allowed: the display name uses a script its region doesn't allow")]
+    NameScriptNotAllowed,
+
+    #[error("Mixed scripts: display names cannot mix letters of different scripts")]
+    NameMixedScripts,
+
+    #[error("Too many coins: a run collects at most 3 coins per lane it reaches")]
+    TooManyCoins,
+}
+
+/// Scores of 0 are never submitted by a finished game
+pub fn validate_score(score: u32) -> Result<(), ValidationError> {
+    if score == 0 {
+        return Err(ValidationError::InvalidScore);
+    }
+    Ok(())
+}
+
+/// Coins reported for a run are bounded by the `lanes` it reached
+pub fn validate_run_coins(coins: u64, lanes: u64) -> Result<(), ValidationError> {
+    if coins > lanes.saturating_mul(MAX_COINS_PER_LANE) {
+        return Err(ValidationError::TooManyCoins);
+    }
+    Ok(())
+}
+
+/// Replays are capped at `max_size` bytes to keep player state small
+pub fn validate_replay_size(replay: &str, max_size: u64) -> Result<(), ValidationError> {
+    if replay.len() as u64 > max_size {
+        return Err(ValidationError::ReplayTooLarge);
+    }
+    Ok(())
+}
+
+/// Parse a MAJOR.MINOR.PATCH client version
+pub fn validate_client_version(version: &str) -> Result<ClientVersion, ValidationError> {
+    ClientVersion::parse(version).ok_or(ValidationError::InvalidClientVersion)
+}
+
+/// Submission ids must be non-empty and bounded so they can be stored per player
+pub fn validate_submission_id(submission_id: &str) -> Result<(), ValidationError> {
+    if submission_id.is_empty() || submission_id.len() > MAX_SUBMISSION_ID_LEN {
+        return Err(ValidationError::InvalidSubmissionId);
+    }
+    Ok(())
+}
+
+/// Trim a display name and check it is non-empty and at most
+/// `MAX_DISPLAY_NAME_LEN` characters, returning the name to store
+pub fn validate_display_name(name: &str) -> Result<String, ValidationError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(ValidationError::NameEmpty);
+    }
+    if trimmed.chars().count() > MAX_DISPLAY_NAME_LEN {
+        return Err(ValidationError::NameTooLong);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Fold a display name to the skeleton that look-alike names share: lower
+/// case, confusable digits, symbols and Cyrillic or Greek letters mapped to
+/// one Latin letter, separators dropped, and `rn`/`vv` read as `m`/`w`.
+/// Two names with the same skeleton can pass for each other.
+pub fn name_skeleton(name: &str) -> String {
+    let folded = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            '0' | '\u{043e}' | '\u{03bf}' => Some('o'), // Cyrillic o, Greek omicron
+            // Cyrillic i, dotless i, Greek iota
+            '1' | 'i' | '!' | '|' | '\u{0456}' | '\u{0131}' | '\u{03b9}' => Some('l'),
+            '3' | '\u{0435}' | '\u{03b5}' => Some('e'), // Cyrillic ie, Greek epsilon
+            '4' | '@' | '\u{0430}' | '\u{03b1}' => Some('a'), // Cyrillic a, Greek alpha
+            '5' | '$' | '\u{0455}' => Some('s'), // Cyrillic dze
+            '7' | '\u{0442}' | '\u{03c4}' => Some('t'), // Cyrillic te, Greek tau
+            '8' | '\u{0432}' | '\u{03b2}' => Some('b'), // Cyrillic ve, Greek beta
+            '\u{0440}' | '\u{03c1}' => Some('p'), // Cyrillic er, Greek rho
+            '\u{0441}' => Some('c'), // Cyrillic es
+            '\u{0443}' | '\u{03b3}' => Some('y'), // Cyrillic u, Greek gamma
+            '\u{0445}' | '\u{03c7}' => Some('x'), // Cyrillic ha, Greek chi
+            '\u{043a}' | '\u{03ba}' => Some('k'), // Cyrillic ka, Greek kappa
+            '\u{043c}' => Some('m'), // Cyrillic em
+            '\u{043d}' | '\u{03b7}' => Some('h'), // Cyrillic en, Greek eta
+            '\u{03bd}' => Some('v'), // Greek nu
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect::<String>();
+    folded.replace("rn", "m").replace("vv", "w")
+}
+
+/// Script of a letter, or None for characters shared by every script
+/// (digits, punctuation, spaces, symbols)
+pub fn script_of(c: char) -> Option<NameScript> {
+    let script = match c as u32 {
+        0x0041..=0x005A | 0x0061..=0x007A | 0x00C0..=0x024F | 0x1E00..=0x1EFF => {
+            NameScript::Latin
+        }
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => NameScript::Greek,
+        0x0400..=0x052F => NameScript::Cyrillic,
+        0x0590..=0x05FF => NameScript::Hebrew,
+        0x0600..=0x06FF | 0x0750..=0x077F => NameScript::Arabic,
+        0x0900..=0x097F => NameScript::Devanagari,
+        0x0E00..=0x0E7F => NameScript::Thai,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => NameScript::Hangul,
+        0x3040..=0x30FF => NameScript::Kana,
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF => NameScript::Han,
+        _ => return None,
+    };
+    // Latin ranges above include the multiplication and division signs
+    (script != NameScript::Latin || c.is_alphabetic()).then_some(script)
+}
+
+/// Check `name` only uses `allowed` scripts (any script if empty) and
+/// doesn't mix scripts, which look-alike spoofing relies on
+pub fn validate_name_scripts(name: &str, allowed: &[NameScript]) -> Result<(), ValidationError> {
+    let mut first: Option<NameScript> = None;
+    for script in name.chars().filter_map(script_of) {
+        if !allowed.is_empty() && !allowed.contains(&script) {
+            return Err(ValidationError::NameScriptNotAllowed);
+        }
+        match first {
+            Some(first) if !first.mixes_with(script) => {
+                return Err(ValidationError::NameMixedScripts);
+            }
+            Some(_) => {}
+            None => first = Some(script),
+        }
+    }
+    Ok(())
+}
+
+/// Check `name` contains none of the blocked `terms`, given as skeletons so
+/// look-alike spellings of a term are caught too
+pub fn check_name_blocklist(name: &str, terms: &[String]) -> Result<(), ValidationError> {
+    let skeleton = name_skeleton(name);
+    if terms.iter().any(|term| skeleton.contains(term.as_str())) {
+        return Err(ValidationError::NameBlocked);
+    }
+    Ok(())
+}
+
+/// Parse a wallet address into the key its player data is stored under
+pub fn validate_wallet_address(wallet: &str) -> Result<PlayerKey, ValidationError> {
+    wallet.parse()
+}
+
+/// Normalize a declared region: the country must be an ISO 3166-1 alpha-2
+/// code (any case), the city is trimmed and dropped when blank
+pub fn validate_region(
+    country: &str,
+    city: Option<&str>,
+) -> Result<(String, Option<String>), ValidationError> {
+    let country = country.trim().to_ascii_uppercase();
+    if country.len() != 2 || !country.bytes().all(|byte| byte.is_ascii_uppercase()) {
+        return Err(ValidationError::InvalidRegion);
+    }
+    let city = city.map(str::trim).filter(|city| !city.is_empty());
+    if city.map_or(false, |city| city.chars().count() > MAX_CITY_NAME_LEN) {
+        return Err(ValidationError::InvalidRegion);
+    }
+    Ok((country, city.map(str::to_string)))
+}
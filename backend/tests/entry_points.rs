@@ -0,0 +1,129 @@
+//! SaveScore and RegisterPlayer behave the same as an operation on the
+//! player's chain and as a message to the hub, and the hub only takes
+//! submission fees from player chains, run with `cargo test --features testing`
+#![cfg(feature = "testing")]
+
+use crossy_chain::testing::{wallet, SaveScoreBuilder, TestHarness, START};
+use crossy_chain::{ContractError, Message, Operation};
+use linera_sdk::base::{Amount, ChainId, Owner};
+use serde_json::Value;
+
+/// The hub chain the harness runs, with `player_chain` allowed to send it scores
+async fn hub(player_chain: ChainId) -> TestHarness {
+    let admin = wallet(0);
+    let mut harness = TestHarness::new(ChainId::root(0), admin, START).await;
+    let authorize = Operation::AuthorizeChain {
+        chain_id: player_chain,
+    };
+    harness.execute_operation(admin, authorize).await.expect("the admin authorizes the chain");
+    harness
+}
+
+/// High score, games played and display name of `owner`'s profile
+async fn profile(harness: &mut TestHarness, owner: Owner) -> Value {
+    let query = format!(
+        "{{ player(walletAddress: \"{owner}\") {{ high_score games_played display_name }} }}"
+    );
+    let data = harness.query_json(query).await;
+    data["player"].clone()
+}
+
+#[tokio::test]
+async fn save_score_operation_and_message_record_the_same_run() {
+    let player_chain = ChainId::root(1);
+    let mut harness = hub(player_chain).await;
+
+    let run = SaveScoreBuilder::new(12).timestamp(harness.now()).recorded();
+    harness
+        .execute_operation(wallet(1), run.clone().operation())
+        .await
+        .expect("the operation is accepted");
+    harness
+        .execute_message(player_chain, Some(wallet(2)), run.message(1, 0), false)
+        .await
+        .expect("the message is accepted");
+
+    let by_operation = profile(&mut harness, wallet(1)).await;
+    let by_message = profile(&mut harness, wallet(2)).await;
+    assert_eq!(by_operation["high_score"], 12);
+    assert_eq!(by_operation["games_played"], 1);
+    assert_eq!(by_operation, by_message);
+}
+
+#[tokio::test]
+async fn a_replayed_save_score_message_counts_once() {
+    let player_chain = ChainId::root(1);
+    let mut harness = hub(player_chain).await;
+
+    let run = SaveScoreBuilder::new(8).timestamp(harness.now()).recorded();
+    for _ in 0..2 {
+        harness
+            .execute_message(player_chain, Some(wallet(1)), run.clone().message(1, 0), false)
+            .await
+            .expect("the message is accepted");
+    }
+
+    let player = profile(&mut harness, wallet(1)).await;
+    assert_eq!(player["games_played"], 1);
+}
+
+#[tokio::test]
+async fn save_score_message_from_an_unknown_chain_is_refused() {
+    let mut harness = hub(ChainId::root(1)).await;
+
+    let run = SaveScoreBuilder::new(8).timestamp(harness.now()).recorded();
+    let result = harness
+        .execute_message(ChainId::root(2), Some(wallet(1)), run.message(1, 0), false)
+        .await;
+    assert!(result.is_err());
+    assert_eq!(profile(&mut harness, wallet(1)).await, Value::Null);
+}
+
+#[tokio::test]
+async fn register_player_operation_and_message_set_the_same_name() {
+    let player_chain = ChainId::root(1);
+    let mut harness = hub(player_chain).await;
+
+    let register = Operation::RegisterPlayer {
+        display_name: Some("Frogger".to_string()),
+    };
+    harness.execute_operation(wallet(1), register).await.expect("the operation is accepted");
+    let register = Message::RegisterPlayer {
+        display_name: Some("Hopper".to_string()),
+    };
+    harness
+        .execute_message(player_chain, Some(wallet(2)), register, false)
+        .await
+        .expect("the message is accepted");
+
+    let by_operation = profile(&mut harness, wallet(1)).await;
+    let by_message = profile(&mut harness, wallet(2)).await;
+    assert_eq!(by_operation["display_name"], "Frogger");
+    assert_eq!(by_message["display_name"], "Hopper");
+    assert_eq!(by_operation["games_played"], by_message["games_played"]);
+}
+
+/// Tokens in the hub's prize pool
+async fn prize_pool(harness: &mut TestHarness) -> Value {
+    let data = harness.query_json("{ prizePool }").await;
+    data["prizePool"].clone()
+}
+
+#[tokio::test]
+async fn a_submission_fee_is_only_taken_from_a_player_chain() {
+    let player_chain = ChainId::root(1);
+    let mut harness = hub(player_chain).await;
+    let fee = || Message::SubmissionFee {
+        amount: Amount::from_tokens(1),
+    };
+
+    let result = harness.execute_message(ChainId::root(2), None, fee(), false).await;
+    assert!(matches!(result, Err(ContractError::UnauthorizedOrigin)), "{result:?}");
+    assert_eq!(prize_pool(&mut harness).await, Amount::ZERO.to_string());
+
+    harness
+        .execute_message(player_chain, None, fee(), false)
+        .await
+        .expect("the fee goes to the prize pool");
+    assert_eq!(prize_pool(&mut harness).await, Amount::from_tokens(1).to_string());
+}
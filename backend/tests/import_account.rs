@@ -0,0 +1,64 @@
+//! An account imported from another chain is merged into the profile the
+//! wallet already has, run with `cargo test --features testing`
+#![cfg(feature = "testing")]
+
+use crossy_chain::testing::{wallet, SaveScoreBuilder, TestHarness, START};
+use crossy_chain::{Message, Operation, PersonalBest, PlayerData};
+use linera_sdk::base::ChainId;
+use serde_json::Value;
+
+fn personal_best(score: u32, played_at: u64) -> PersonalBest {
+    PersonalBest {
+        score,
+        played_at,
+        modifier: None,
+    }
+}
+
+#[tokio::test]
+async fn an_imported_account_adds_to_the_existing_profile() {
+    let admin = wallet(0);
+    let player_chain = ChainId::root(1);
+    let mut harness = TestHarness::new(ChainId::root(0), admin, START).await;
+    let authorize = Operation::AuthorizeChain {
+        chain_id: player_chain,
+    };
+    harness.execute_operation(admin, authorize).await.expect("the admin authorizes the chain");
+
+    let player = wallet(1);
+    let run = SaveScoreBuilder::new(8).timestamp(harness.now()).recorded();
+    harness.execute_operation(player, run.operation()).await.expect("the run is saved");
+
+    let imported = PlayerData {
+        high_score: 5,
+        games_played: 2,
+        total_score: 9,
+        personal_bests: vec![personal_best(5, START - 100), personal_best(4, START - 200)],
+        ..PlayerData::default()
+    };
+    let import = Message::ImportAccount {
+        migration_id: 0,
+        player: imported,
+        trophies: Vec::new(),
+    };
+    harness
+        .execute_message(player_chain, Some(player), import, false)
+        .await
+        .expect("the account is imported");
+
+    let query = format!(
+        "{{ player(walletAddress: \"{player}\") {{ high_score games_played total_score }} \
+         personalBests(walletAddress: \"{player}\") {{ score }} }}"
+    );
+    let data = harness.query_json(query).await;
+    assert_eq!(data["player"]["high_score"], 8);
+    assert_eq!(data["player"]["games_played"], 3);
+    assert_eq!(data["player"]["total_score"], 17);
+    let scores = data["personalBests"]
+        .as_array()
+        .expect("personal bests are a list")
+        .iter()
+        .map(|best| best["score"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(scores, [Value::from(8), Value::from(5), Value::from(4)]);
+}
@@ -0,0 +1,59 @@
+//! Leaderboard index behavior, run with `cargo test --features testing`
+#![cfg(feature = "testing")]
+
+use crossy_chain::testing::{wallet, SaveScoreBuilder, TestHarness, START};
+use crossy_chain::{ConfigField, Operation};
+use linera_sdk::base::ChainId;
+
+/// Wallets and high scores on the leaderboard, highest first
+async fn leaderboard(harness: &mut TestHarness) -> Vec<(String, u64)> {
+    let data = harness
+        .query_json("{ leaderboard(topN: 100) { wallet_address high_score } }")
+        .await;
+    data["leaderboard"]
+        .as_array()
+        .expect("the leaderboard is a list")
+        .iter()
+        .map(|entry| {
+            let wallet = entry["wallet_address"].as_str().expect("wallets are strings");
+            let high_score = entry["high_score"].as_u64().expect("scores are numbers");
+            (wallet.to_string(), high_score)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn evicts_the_lowest_score_beyond_the_cap() {
+    let admin = wallet(0);
+    let mut harness = TestHarness::new(ChainId::root(0), admin, START).await;
+    let cap = Operation::UpdateConfig {
+        field: ConfigField::LeaderboardCap,
+        value: 10,
+    };
+    harness.execute_operation(admin, cap).await.expect("the admin sets the cap");
+
+    for index in 1..=11 {
+        let run = SaveScoreBuilder::new(10 + index as u32)
+            .timestamp(harness.now())
+            .recorded()
+            .operation();
+        harness.execute_operation(wallet(index), run).await.expect("the run is saved");
+        harness.advance_time(60);
+    }
+
+    let entries = leaderboard(&mut harness).await;
+    assert_eq!(entries.len(), 10);
+    assert_eq!(entries.first().map(|entry| entry.1), Some(21));
+    assert_eq!(entries.last().map(|entry| entry.1), Some(12));
+    let evicted = wallet(1).to_string();
+    assert!(entries.iter().all(|(wallet, _)| *wallet != evicted));
+
+    // A new high score brings the evicted player back, pushing out the lowest
+    let run = SaveScoreBuilder::new(30).timestamp(harness.now()).recorded().operation();
+    harness.execute_operation(wallet(1), run).await.expect("the run is saved");
+
+    let entries = leaderboard(&mut harness).await;
+    assert_eq!(entries.len(), 10);
+    assert_eq!(entries.first(), Some(&(evicted, 30)));
+    assert_eq!(entries.last().map(|entry| entry.1), Some(13));
+}
@@ -0,0 +1,177 @@
+//! Race messages on a player's chain are only taken from the race's own
+//! chain, snapshots only from spectated chains, and ghost races rate the
+//! racer alone, run with `cargo test --features testing`
+#![cfg(feature = "testing")]
+
+use crossy_chain::testing::{test_recording, wallet, SaveScoreBuilder, TestHarness, START};
+use crossy_chain::{ContractError, Message, Operation, Race, RaceSnapshot, INITIAL_RATING};
+use linera_sdk::base::{ChainId, Owner};
+use serde_json::Value;
+
+const RACE_ID: u64 = 7;
+
+fn race_chain() -> ChainId {
+    ChainId::root(5)
+}
+
+/// A race between wallets 1 and 2 on `race_chain()`, settled with `scores`
+fn settled(scores: [u32; 2]) -> Race {
+    let players = [wallet(1).to_string(), wallet(2).to_string()];
+    let winner = if scores[0] >= scores[1] {
+        &players[0]
+    } else {
+        &players[1]
+    };
+    Race {
+        winner: Some(winner.clone()),
+        players: players.clone(),
+        seed: 1,
+        scores: scores.map(Some),
+        finished: true,
+        started_at: START,
+        race_chain: Some(race_chain()),
+    }
+}
+
+/// A player chain the harness runs, told that wallet 1 was paired on `race_chain()`
+async fn paired() -> TestHarness {
+    let mut harness = TestHarness::new(ChainId::root(0), wallet(0), START).await;
+    let matched = Message::RaceMatched {
+        wallet: wallet(1).to_string(),
+        race_id: RACE_ID,
+        opponent: wallet(2).to_string(),
+        seed: 1,
+        race_chain: Some(race_chain()),
+    };
+    let origin = harness.chain_id();
+    harness
+        .execute_message(origin, None, matched, false)
+        .await
+        .expect("the pairing is kept");
+    harness
+}
+
+async fn race(harness: &mut TestHarness) -> Value {
+    let query = format!("{{ race(raceId: {RACE_ID}) {{ scores winner finished }} }}");
+    let data = harness.query_json(query).await;
+    data["race"].clone()
+}
+
+#[tokio::test]
+async fn a_result_from_another_chain_is_refused() {
+    let mut harness = paired().await;
+
+    let settle = Message::RaceSettled {
+        race_id: RACE_ID,
+        race: settled([3, 9]),
+    };
+    let result = harness
+        .execute_message(ChainId::root(6), None, settle, false)
+        .await;
+    assert!(
+        matches!(result, Err(ContractError::UnauthorizedOrigin)),
+        "{result:?}"
+    );
+    assert_eq!(race(&mut harness).await, Value::Null);
+}
+
+#[tokio::test]
+async fn a_race_is_settled_once() {
+    let mut harness = paired().await;
+
+    for scores in [[9, 3], [3, 9]] {
+        let settle = Message::RaceSettled {
+            race_id: RACE_ID,
+            race: settled(scores),
+        };
+        harness
+            .execute_message(race_chain(), None, settle, false)
+            .await
+            .expect("the result is accepted");
+    }
+
+    let race = race(&mut harness).await;
+    assert_eq!(race["scores"], serde_json::json!([9, 3]));
+    assert_eq!(race["winner"], wallet(1).to_string());
+}
+
+/// Rating of `owner`'s profile
+async fn rating(harness: &mut TestHarness, owner: Owner) -> Value {
+    let query = format!("{{ player(walletAddress: \"{owner}\") {{ rating {{ rating }} }} }}");
+    let data = harness.query_json(query).await;
+    data["player"]["rating"]["rating"].clone()
+}
+
+/// A ghost race result of 20, backed by its replay
+fn ghost_race_result() -> Operation {
+    Operation::SubmitGhostRaceResult {
+        score: 20,
+        replay_data: Some(test_recording(20)),
+    }
+}
+
+#[tokio::test]
+async fn a_ghost_race_rates_the_racer_once() {
+    let mut harness = TestHarness::new(ChainId::root(0), wallet(0), START).await;
+    let (racer, ghost) = (wallet(1), wallet(2));
+    for (owner, score) in [(ghost, 10), (racer, 5)] {
+        let run = SaveScoreBuilder::new(score).timestamp(harness.now()).recorded();
+        harness.execute_operation(owner, run.operation()).await.expect("the run is saved");
+    }
+
+    let start = Operation::StartGhostRace {
+        opponent: ghost.to_string(),
+    };
+    harness.execute_operation(racer, start).await.expect("the race starts");
+    harness
+        .execute_operation(racer, ghost_race_result())
+        .await
+        .expect("the result is rated");
+
+    let racer_rating = rating(&mut harness, racer).await;
+    assert!(racer_rating.as_u64() > Some(u64::from(INITIAL_RATING)), "{racer_rating}");
+    assert_eq!(rating(&mut harness, ghost).await, INITIAL_RATING);
+
+    let again = harness.execute_operation(racer, ghost_race_result()).await;
+    assert!(matches!(again, Err(ContractError::NoActiveGhostRace)), "{again:?}");
+    assert_eq!(rating(&mut harness, racer).await, racer_rating);
+}
+
+/// A snapshot of the race between wallets 1 and 2, taken at `taken_at`
+fn snapshot(taken_at: u64) -> Message {
+    Message::RaceSnapshot {
+        snapshot: RaceSnapshot {
+            race_id: RACE_ID,
+            players: [wallet(1).to_string(), wallet(2).to_string()],
+            positions: Vec::new(),
+            scores: [None, None],
+            finished: false,
+            taken_at,
+        },
+    }
+}
+
+async fn snapshot_taken_at(harness: &mut TestHarness) -> Value {
+    let query = format!("{{ raceSnapshot(raceId: {RACE_ID}) {{ takenAt }} }}");
+    let data = harness.query_json(query).await;
+    data["raceSnapshot"]["takenAt"].clone()
+}
+
+#[tokio::test]
+async fn snapshots_are_only_taken_from_a_spectated_chain() {
+    let mut harness = TestHarness::new(ChainId::root(0), wallet(0), START).await;
+    let spectate = Operation::SpectateRace {
+        race_chain: race_chain(),
+    };
+    harness.execute_operation(wallet(1), spectate).await.expect("the race is spectated");
+
+    let result = harness.execute_message(ChainId::root(6), None, snapshot(START), false).await;
+    assert!(matches!(result, Err(ContractError::UnauthorizedOrigin)), "{result:?}");
+    assert_eq!(snapshot_taken_at(&mut harness).await, Value::Null);
+
+    harness
+        .execute_message(race_chain(), None, snapshot(START), false)
+        .await
+        .expect("the snapshot is kept");
+    assert_eq!(snapshot_taken_at(&mut harness).await, START);
+}
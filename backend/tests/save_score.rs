@@ -0,0 +1,75 @@
+//! A SaveScore submission round trip through the test harness, run with
+//! `cargo test --features testing`
+#![cfg(feature = "testing")]
+
+use crossy_chain::testing::{test_recording, wallet, SaveScoreBuilder, TestHarness, START};
+use crossy_chain::{ContractError, GameConfig, Operation, OperationResponse, PlayerKey};
+use linera_sdk::base::ChainId;
+
+#[tokio::test]
+async fn a_saved_score_is_attested_and_served_back() {
+    let mut harness = TestHarness::new(ChainId::root(0), wallet(0), START).await;
+    let player = wallet(1);
+
+    let run = SaveScoreBuilder::new(15).timestamp(harness.now()).recorded();
+    let response = harness
+        .execute_operation(player, run.clone().operation())
+        .await
+        .expect("the run is saved");
+    let OperationResponse::Attestation(attestation) = response else {
+        panic!("an accepted run is attested, got {response:?}");
+    };
+    assert_eq!(attestation.chain_id, harness.chain_id());
+    assert_eq!(attestation.wallet_address, PlayerKey::from(player));
+    assert_eq!(attestation.score, 15);
+    assert_eq!(attestation.timestamp, harness.now());
+    assert!(attestation.replay_digest.is_some());
+
+    let query = format!(
+        "{{ player(walletAddress: \"{player}\") {{ high_score games_played replay_data }} }}"
+    );
+    let data = harness.query_json(query.as_str()).await;
+    assert_eq!(data["player"]["high_score"], 15);
+    assert_eq!(data["player"]["games_played"], 1);
+    assert_eq!(data["player"]["replay_data"], test_recording(15));
+
+    // A retry of the same submission is acknowledged without counting again
+    let response = harness
+        .execute_operation(player, run.operation())
+        .await
+        .expect("the retry is acknowledged");
+    assert_eq!(response, OperationResponse::None);
+    let data = harness.query_json(query.as_str()).await;
+    assert_eq!(data["player"]["games_played"], 1);
+}
+
+#[tokio::test]
+async fn a_high_score_its_replay_does_not_credit_is_refused() {
+    let mut harness = TestHarness::new(ChainId::root(0), wallet(0), START).await;
+
+    let run = SaveScoreBuilder::new(20)
+        .timestamp(harness.now())
+        .replay(test_recording(15))
+        .operation();
+    let result = harness.execute_operation(wallet(1), run).await;
+    assert!(matches!(result, Err(ContractError::ReplayScoreMismatch)), "{result:?}");
+}
+
+#[tokio::test]
+async fn a_replay_is_verified_under_the_config_it_was_played_with() {
+    let admin = wallet(0);
+    let mut harness = TestHarness::new(ChainId::root(0), admin, START).await;
+    let config = GameConfig {
+        points_per_lane: 2,
+        ..GameConfig::default()
+    };
+    harness
+        .execute_operation(admin, Operation::SetGameConfig { config })
+        .await
+        .expect("the admin replaces the game config");
+
+    // `test_recording` was played under config version 0, one point per lane
+    let run = SaveScoreBuilder::new(15).timestamp(harness.now()).recorded();
+    let response = harness.execute_operation(wallet(1), run.operation()).await;
+    assert!(matches!(response, Ok(OperationResponse::Attestation(_))), "{response:?}");
+}
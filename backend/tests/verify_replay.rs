@@ -0,0 +1,88 @@
+//! Properties of `verify_replay`, the replay check SaveScore runs, over
+//! arbitrary inputs
+
+use crossy_chain::{verify_replay, GameConfig, Recording, ReplayError};
+use proptest::prelude::*;
+use serde_json::{json, Value};
+
+const ACTIONS: [&str; 4] = ["forward", "backward", "left", "right"];
+
+/// A recording of `actions`, one every 100 milliseconds, claiming `final_score`
+fn recording(actions: &[usize], final_score: u32) -> Value {
+    let inputs = actions
+        .iter()
+        .zip(1u64..)
+        .map(|(action, step)| json!({ "timestamp": step * 100, "action": ACTIONS[*action] }))
+        .collect::<Vec<_>>();
+    json!({
+        "seed": 1,
+        "startTime": 0,
+        "inputs": inputs,
+        "finalScore": final_score,
+        "duration": actions.len() as u64 * 100 + 1_000,
+        "version": "1.0",
+    })
+}
+
+/// Furthest lane `actions` reach from the starting lane
+fn furthest_lane(actions: &[usize]) -> u32 {
+    let (mut lane, mut furthest) = (0u32, 0u32);
+    for action in actions {
+        match ACTIONS[*action] {
+            "forward" => lane += 1,
+            "backward" => lane = lane.saturating_sub(1),
+            _ => {}
+        }
+        furthest = furthest.max(lane);
+    }
+    furthest
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let config = GameConfig::default();
+        if let Ok(score) = verify_replay(&bytes, &config) {
+            let json = std::str::from_utf8(&bytes).expect("accepted replays are UTF-8");
+            let recording = Recording::parse(json).expect("accepted replays parse");
+            prop_assert_eq!(score, recording.final_score);
+        }
+    }
+
+    #[test]
+    fn a_reachable_score_is_credited_as_claimed(
+        actions in proptest::collection::vec(0..ACTIONS.len(), 0..200),
+        claim in any::<u32>(),
+    ) {
+        let config = GameConfig::default();
+        let reachable = furthest_lane(&actions);
+        let claimed = claim % (reachable + 1);
+        let replay = recording(&actions, claimed).to_string();
+        prop_assert_eq!(verify_replay(replay.as_bytes(), &config).ok(), Some(claimed));
+    }
+
+    #[test]
+    fn an_unreachable_score_is_refused(
+        actions in proptest::collection::vec(0..ACTIONS.len(), 0..200),
+        excess in 1u32..1_000,
+    ) {
+        let config = GameConfig::default();
+        let claimed = furthest_lane(&actions) + excess;
+        let replay = recording(&actions, claimed).to_string();
+        let result = verify_replay(replay.as_bytes(), &config);
+        prop_assert!(matches!(result, Err(ReplayError::ScoreUnreachable { .. })), "{:?}", result);
+    }
+
+    #[test]
+    fn inputs_out_of_order_are_refused(
+        actions in proptest::collection::vec(0..ACTIONS.len(), 2..50),
+        swap in any::<prop::sample::Index>(),
+    ) {
+        let config = GameConfig::default();
+        let mut replay = recording(&actions, 0);
+        let index = swap.index(actions.len() - 1) + 1;
+        replay["inputs"][index]["timestamp"] = json!(0);
+        let result = verify_replay(replay.to_string().as_bytes(), &config);
+        prop_assert!(matches!(result, Err(ReplayError::InputsOutOfOrder { .. })), "{:?}", result);
+    }
+}
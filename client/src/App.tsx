@@ -20,6 +20,8 @@ function App() {
   const [submittingScore, setSubmittingScore] = useState(false);
   const [gameKey, setGameKey] = useState(0);
   const [currentRecording, setCurrentRecording] = useState<GameRecording | null>(null);
+  // One id per finished run, reused by every submit attempt of that run
+  const [submissionId, setSubmissionId] = useState<string>(() => crypto.randomUUID());
   const [replayWalletAddress, setReplayWalletAddress] = useState<string | null>(null);
 
   // Load Press Start 2P font
@@ -54,6 +56,7 @@ function App() {
   const handleGameOver = (finalScore: number, recording?: GameRecording) => {
     setScore(finalScore);
     setCurrentRecording(recording || null);
+    setSubmissionId(crypto.randomUUID());
     setGameState('gameover');
     
     if (walletAddress) {
@@ -98,7 +101,7 @@ function App() {
         console.log('[App] Submitting score with recording:', currentRecording.inputs.length, 'inputs');
       }
 
-      const success = await lineraClient.saveScore(score, currentRecording);
+      const success = await lineraClient.saveScore(score, submissionId, currentRecording);
       
       if (success) {
         toast.success('Score saved on-chain!', {
@@ -674,7 +674,7 @@ export function CrossyGame({
             // Stop recording and pass recording to parent
             let recording: GameRecording | undefined;
             if (enableRecording && recorderRef.current.isRecording()) {
-              recording = recorderRef.current.stop(currentLane);
+              recording = recorderRef.current.stop(currentLane, 'car');
               console.log('[Game] Recording stopped, inputs:', recording.inputs.length);
             }
             
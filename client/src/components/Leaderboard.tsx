@@ -34,9 +34,9 @@ export function Leaderboard({ onClose, currentWallet, onWatchReplay }: Leaderboa
     return `${address.slice(0, 6)}...${address.slice(-4)}`;
   };
 
-  const formatDate = (timestamp?: number) => {
+  const formatDate = (timestamp?: string | number) => {
     if (!timestamp) return 'Never';
-    const date = new Date(timestamp * 1000);
+    const date = new Date(typeof timestamp === 'number' ? timestamp * 1000 : timestamp);
     return date.toLocaleDateString();
   };
 
@@ -23,16 +23,16 @@ export function ReplayViewer({ walletAddress, onBack }: ReplayViewerProps) {
     const fetchReplay = async () => {
       try {
         setReplayState('loading');
-        const playerData = await lineraClient.getPlayer(walletAddress);
+        const replayData = await lineraClient.getReplay(walletAddress);
         
-        if (!playerData || !playerData.replay_data) {
+        if (!replayData) {
           setError('No replay data available for this player');
           setReplayState('error');
           return;
         }
 
         // Parse replay data JSON
-        const replayJson = JSON.parse(playerData.replay_data);
+        const replayJson = JSON.parse(replayData);
         setRecording(replayJson);
         setReplayState('ready');
       } catch (err) {
@@ -13,6 +13,18 @@ export interface InputEvent {
   action: 'forward' | 'backward' | 'left' | 'right';
 }
 
+/** Platform the recording was made on (validated by the contract) */
+export type RecordingPlatform = 'web' | 'ios' | 'android' | 'desktop';
+
+/** What ended a run (validated by the contract against the reported run stats) */
+export type DeathCause = 'car' | 'train' | 'water' | 'timeout';
+
+/** Recording format version understood by the contract */
+export const REPLAY_FORMAT_VERSION = 1;
+
+/** Game client version, compared against the contract's minimum accepted version */
+export const CLIENT_VERSION = '1.0.0';
+
 export interface GameRecording {
   /** Random seed used to generate the game (for deterministic replay) */
   seed: number;
@@ -26,6 +38,16 @@ export interface GameRecording {
   duration: number;
   /** Version of the recorder for compatibility */
   version: string;
+  /** Version of the game client (MAJOR.MINOR.PATCH) */
+  clientVersion: string;
+  /** Platform the game was played on */
+  platform: RecordingPlatform;
+  /** Version of the recording format */
+  formatVersion: number;
+  /** On-chain game config version the run was played under */
+  configVersion: number;
+  /** What ended the run */
+  deathCause?: DeathCause;
 }
 
 export class GameInputRecorder {
@@ -34,15 +56,18 @@ export class GameInputRecorder {
   private startTime: number = 0;
   private gameStartTimestamp: number = 0;
   private inputs: InputEvent[] = [];
+  private configVersion: number = 0;
   private readonly version = '1.0.0';
 
   /**
    * Start recording a new game session
    * @param seed Random seed used for game generation (if applicable)
+   * @param configVersion On-chain game config version the game is played under
    */
-  start(seed?: number): void {
+  start(seed?: number, configVersion: number = 0): void {
     this.recording = true;
     this.seed = seed || Date.now();
+    this.configVersion = configVersion;
     this.startTime = Date.now();
     this.gameStartTimestamp = performance.now();
     this.inputs = [];
@@ -70,8 +95,9 @@ export class GameInputRecorder {
   /**
    * Stop recording and return the complete recording
    * @param finalScore The final score achieved in the game
+   * @param deathCause What ended the run
    */
-  stop(finalScore: number): GameRecording {
+  stop(finalScore: number, deathCause?: DeathCause): GameRecording {
     if (!this.recording) {
       throw new Error('Recorder is not active');
     }
@@ -85,7 +111,12 @@ export class GameInputRecorder {
       inputs: [...this.inputs],
       finalScore,
       duration,
-      version: this.version
+      version: this.version,
+      clientVersion: CLIENT_VERSION,
+      platform: detectPlatform(),
+      formatVersion: REPLAY_FORMAT_VERSION,
+      configVersion: this.configVersion,
+      deathCause
     };
 
     console.log(`[Recorder] Stopped recording - ${this.inputs.length} inputs, final score: ${finalScore}`);
@@ -215,5 +246,16 @@ export class GameInputRecorder {
   }
 }
 
+/**
+ * Detect the platform the game is running on from the user agent
+ */
+function detectPlatform(): RecordingPlatform {
+  const userAgent = typeof navigator !== 'undefined' ? navigator.userAgent : '';
+  if (/iPhone|iPad|iPod/i.test(userAgent)) return 'ios';
+  if (/Android/i.test(userAgent)) return 'android';
+  if (/Electron/i.test(userAgent)) return 'desktop';
+  return 'web';
+}
+
 // Singleton instance for easy access
 export const gameRecorder = new GameInputRecorder();
@@ -1,10 +1,11 @@
 import * as linera from '@linera/client';
+import { CLIENT_VERSION } from './GameInputRecorder';
 
 export interface LeaderboardEntry {
   wallet_address: string;
   high_score: number;
   games_played: number;
-  last_played_at?: number;
+  last_played_at?: string | number; // RFC 3339 from the chain, UNIX seconds from localStorage
   display_name?: string;
   replay_data?: string;
 }
@@ -12,7 +13,7 @@ export interface LeaderboardEntry {
 export interface PlayerData {
   high_score: number;
   games_played: number;
-  last_played_at?: number;
+  last_played_at?: string | number; // RFC 3339 from the chain, UNIX seconds from localStorage
   display_name?: string;
   replay_data?: string;
 }
@@ -187,15 +188,21 @@ class LineraClient {
   }
 
   /**
-   * Save score on-chain via GraphQL mutation
+   * Save score on-chain via GraphQL mutation. `submissionId` identifies the
+   * run: retries of the same run must pass the same id so the chain counts
+   * the game once
    */
-  async saveScore(score: number, replayData?: any): Promise<boolean> {
+  async saveScore(score: number, submissionId: string, replayData?: any): Promise<boolean> {
     if (!this.walletAddress) {
       throw new Error('Wallet not connected');
     }
 
     try {
       const timestamp = Math.floor(Date.now() / 1000);
+      // Run statistics feed the lifetime stats shown on the profile
+      const runStats = replayData?.duration !== undefined
+        ? { durationMs: replayData.duration, deathCause: replayData.deathCause?.toUpperCase() ?? null }
+        : null;
 
       // Serialize replay data to JSON string if provided
       let replayDataJson: string | null = null;
@@ -211,13 +218,16 @@ class LineraClient {
       if (this.backend) {
         // Use actual Linera backend - GraphQL mutation triggers contract operation
         const mutation = JSON.stringify({
-          query: `mutation SaveScore($score: Int!, $timestamp: Int!, $replayData: String) {
-            saveScore(score: $score, timestamp: $timestamp, replayData: $replayData)
+          query: `mutation SaveScore($score: Int!, $timestamp: DateTime!, $replayData: String, $clientVersion: String!, $submissionId: String!, $runStats: RunStats) {
+            saveScore(score: $score, timestamp: $timestamp, replayData: $replayData, clientVersion: $clientVersion, submissionId: $submissionId, runStats: $runStats)
           }`,
           variables: {
             score,
             timestamp,
-            replayData: replayDataJson
+            replayData: replayDataJson,
+            clientVersion: CLIENT_VERSION,
+            submissionId,
+            runStats
           }
         });
 
@@ -371,6 +381,53 @@ class LineraClient {
     }
   }
 
+  /**
+   * Get a player's high-score replay JSON, streamed in chunks so large
+   * replays don't arrive as one huge GraphQL response
+   */
+  async getReplay(walletAddress: string): Promise<string | null> {
+    try {
+      if (this.backend) {
+        const parts: string[] = [];
+        let offset: number | null = 0;
+        while (offset !== null) {
+          const query = JSON.stringify({
+            query: `query ReplayChunk($walletAddress: Owner!, $offset: Int!) {
+              replayChunk(walletAddress: $walletAddress, offset: $offset) {
+                data
+                nextOffset
+              }
+            }`,
+            variables: { walletAddress, offset }
+          });
+
+          const response = await this.backend.query(query);
+          const data = JSON.parse(response);
+
+          if (data.errors) {
+            console.error('GraphQL errors:', data.errors);
+            throw new Error(data.errors[0].message);
+          }
+
+          const chunk = data.data?.replayChunk;
+          if (!chunk) {
+            return null;
+          }
+          parts.push(chunk.data);
+          offset = chunk.nextOffset ?? null;
+        }
+        return parts.join('');
+      } else {
+        // Fallback to localStorage for development
+        const player = await this.getPlayer(walletAddress);
+        return player?.replay_data ?? null;
+      }
+    } catch (error) {
+      console.error('Failed to get replay:', error);
+      return null;
+    }
+  }
+
   /**
    * Check if client is connected to actual blockchain
    */